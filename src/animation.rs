@@ -0,0 +1,150 @@
+use {crate::Frame, std::time::Duration};
+
+#[cfg(feature = "webp")]
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+/// A sequence of rendered frames of the same size, each shown for its duration.
+pub struct Animation {
+    size: (u32, u32),
+    frames: Vec<(Frame, Duration)>,
+    loops: u32,
+}
+
+impl Animation {
+    /// The [`Animation`] constructor.
+    pub fn new(size: (u32, u32)) -> Self {
+        Self {
+            size,
+            frames: vec![],
+            loops: 0,
+        }
+    }
+
+    /// Sets how many times the animation plays, `0` loops forever. Defaults to `0`.
+    pub fn loops(mut self, loops: u32) -> Self {
+        self.loops = loops;
+        self
+    }
+
+    /// Adds a frame shown for the `duration`.
+    ///
+    /// # Panics
+    /// Panics when the frame size differs from the animation size.
+    pub fn push(mut self, frame: Frame, duration: Duration) -> Self {
+        assert_eq!(frame.size(), self.size, "frame size must match");
+        self.frames.push((frame, duration));
+        self
+    }
+
+    pub fn size(&self) -> (u32, u32) {
+        self.size
+    }
+
+    pub fn frames(&self) -> &[(Frame, Duration)] {
+        &self.frames
+    }
+
+    /// Saves the animation as an animated WebP file.
+    ///
+    /// Frames are encoded losslessly without a `quality`,
+    /// otherwise lossy with the quality in range `0..=100`.
+    /// An animation of a single frame is saved as a still image.
+    ///
+    /// # Errors
+    /// Returns an error if there are no frames, the file cannot be created
+    /// or the encoding fails.
+    #[cfg(feature = "webp")]
+    pub fn save_webp<P>(&self, path: P, quality: Option<u8>) -> io::Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        let file = File::create(path)?;
+        let mut w = BufWriter::new(file);
+        self.write_webp(&mut w, quality)?;
+        w.flush()
+    }
+
+    /// Writes the animation as an animated WebP, see [`save_webp`](Self::save_webp).
+    ///
+    /// # Errors
+    /// Returns an error if there are no frames or the encoding fails.
+    #[cfg(feature = "webp")]
+    pub fn write_webp<W>(&self, mut w: W, quality: Option<u8>) -> io::Result<()>
+    where
+        W: Write,
+    {
+        use webp::{AnimEncoder, AnimFrame, WebPConfig};
+
+        if self.frames.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "an animation without frames",
+            ));
+        }
+
+        let mut config =
+            WebPConfig::new().map_err(|()| io::Error::other("failed to initialize the encoder"))?;
+
+        match quality {
+            Some(q) => config.quality = q.min(100) as f32,
+            None => {
+                config.lossless = 1;
+                config.exact = 1;
+            }
+        }
+
+        let (width, height) = self.size;
+        let mut encoder = AnimEncoder::new(width, height, &config);
+        encoder.set_loop_count(self.loops as i32);
+
+        // Frames are placed at timestamps in milliseconds
+        let mut time = Duration::ZERO;
+        for (frame, duration) in &self.frames {
+            let timestamp = time.as_millis() as i32;
+            encoder.add_frame(AnimFrame::from_rgba(&frame.data, width, height, timestamp));
+            time += *duration;
+        }
+
+        let mut data = encoder
+            .try_encode()
+            .map_err(|err| io::Error::other(format!("{err:?}")))?;
+
+        // The encoder isn't given the end of the last frame and guesses its duration
+        set_last_duration(&mut data, time.as_millis() as u32);
+        w.write_all(&data)
+    }
+}
+
+/// Sets the duration of the last frame of an animated WebP,
+/// so the animation lasts the `total` milliseconds.
+#[cfg(feature = "webp")]
+fn set_last_duration(data: &mut [u8], total: u32) {
+    const HEADER: usize = 12;
+    const DURATION: usize = 12;
+
+    let u24 = |b: &[u8]| u32::from_le_bytes([b[0], b[1], b[2], 0]);
+    let mut last = None;
+    let mut elapsed = 0;
+    let mut pos = HEADER;
+    while pos + 8 <= data.len() {
+        let size = u32::from_le_bytes([data[pos + 4], data[pos + 5], data[pos + 6], data[pos + 7]]);
+        let payload = pos + 8;
+        if &data[pos..pos + 4] == b"ANMF" && payload + DURATION + 3 <= data.len() {
+            if let Some(prev) = last.replace(payload + DURATION) {
+                elapsed += u24(&data[prev..]);
+            }
+        }
+
+        // Chunks are padded to an even size
+        pos = payload + size as usize + (size & 1) as usize;
+    }
+
+    if let Some(at) = last {
+        let duration = total.saturating_sub(elapsed).min(0xFF_FFFF);
+        data[at..at + 3].copy_from_slice(&duration.to_le_bytes()[..3]);
+    }
+}
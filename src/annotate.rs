@@ -0,0 +1,442 @@
+//! Ready-made markup for screenshots and diagrams.
+//!
+//! Every builder produces an [`Annotation`] source, which can be layered
+//! over an image like any other source.
+
+use crate::{
+    source::{Borders, Cap, Fill, Offset, Path, Source, Stroke, Text},
+    Color,
+};
+
+type Layer = Box<dyn Source + Send + Sync>;
+
+/// A composed annotation, its layers are painted in order.
+pub struct Annotation {
+    layers: Vec<Layer>,
+}
+
+impl Annotation {
+    fn new() -> Self {
+        Self { layers: vec![] }
+    }
+
+    fn push<S>(&mut self, layer: S)
+    where
+        S: Source + Send + Sync + 'static,
+    {
+        self.layers.push(Box::new(layer));
+    }
+
+    /// Adds a text label centered at the point on a background plate.
+    fn label(&mut self, text: &str, scale: u32, center: (f32, f32), colors: (Color, Color)) {
+        let (text_color, plate) = colors;
+        let text = Text::new(text, scale, text_color);
+        let (w, h) = text.size();
+        let x = (center.0 - w as f32 / 2.).round();
+        let y = (center.1 - h as f32 / 2.).round();
+        let pad = scale as f32 * 2.;
+        self.push(Fill::new(
+            &rect(
+                (x - pad, y - pad),
+                (w as f32 + pad * 2., h as f32 + pad * 2.),
+            ),
+            plate,
+        ));
+
+        self.push(Offset::new(text, (x as i32, y as i32)));
+    }
+}
+
+impl Source for Annotation {
+    fn source(&self, pos: (i32, i32)) -> Color {
+        self.layers.iter().fold(Color::default(), |col, layer| {
+            col.overlay(layer.source(pos))
+        })
+    }
+
+    fn borders(&self) -> Option<Borders> {
+        self.layers
+            .iter()
+            .filter_map(|layer| layer.borders())
+            .reduce(Borders::union)
+    }
+}
+
+/// An arrow pointing from one point to another.
+pub struct Arrow {
+    from: (f32, f32),
+    to: (f32, f32),
+    width: f32,
+    head: f32,
+    color: Color,
+    label: Option<(String, u32, Color)>,
+}
+
+impl Arrow {
+    /// The [`Arrow`] constructor.
+    ///
+    /// The arrow is red and 3 pixels wide by default.
+    pub fn new(from: (f32, f32), to: (f32, f32)) -> Self {
+        Self {
+            from,
+            to,
+            width: 3.,
+            head: 12.,
+            color: Color::from_u32(0xE02020FF),
+            label: None,
+        }
+    }
+
+    /// Sets the shaft width, the head is scaled accordingly.
+    ///
+    /// # Panics
+    /// Panics when a `width` is less than or equal to zero.
+    pub fn width(mut self, width: f32) -> Self {
+        assert!(
+            width > f32::EPSILON,
+            "width cannot be less than or equal to zero",
+        );
+
+        self.width = width;
+        self.head = width * 4.;
+        self
+    }
+
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Puts a label at the tail of the arrow.
+    pub fn label<L>(mut self, text: L, scale: u32) -> Self
+    where
+        L: Into<String>,
+    {
+        self.label = Some((text.into(), scale.max(1), Color::from_u32(0xFFFFFFFF)));
+        self
+    }
+
+    pub fn build(self) -> Annotation {
+        let (dx, dy) = (self.to.0 - self.from.0, self.to.1 - self.from.1);
+        let len = (dx * dx + dy * dy).sqrt().max(f32::EPSILON);
+        let (ux, uy) = (dx / len, dy / len);
+        let head = self.head.min(len);
+        let base = (self.to.0 - ux * head, self.to.1 - uy * head);
+        let half = head / 2.;
+
+        let mut ann = Annotation::new();
+        let shaft = Path::new().move_to(self.from).line_to(base);
+        ann.push(Stroke::new(&shaft, self.width, self.color).cap(Cap::Round));
+
+        let tip = Path::new()
+            .move_to(self.to)
+            .line_to((base.0 - uy * half, base.1 + ux * half))
+            .line_to((base.0 + uy * half, base.1 - ux * half))
+            .close();
+
+        ann.push(Fill::new(&tip, self.color));
+
+        if let Some((text, scale, text_color)) = &self.label {
+            // Keep the label clear of the tail
+            let gap = (Text::GLYPH_SIZE * scale) as f32;
+            let center = (self.from.0 - ux * gap, self.from.1 - uy * gap);
+            ann.label(text, *scale, center, (*text_color, self.color));
+        }
+
+        ann
+    }
+}
+
+/// A text box with an optional pointer to a target.
+pub struct Callout {
+    pos: (f32, f32),
+    text: String,
+    scale: u32,
+    padding: f32,
+    target: Option<(f32, f32)>,
+    text_color: Color,
+    background: Color,
+    border: Color,
+}
+
+impl Callout {
+    /// The [`Callout`] constructor.
+    ///
+    /// The `pos` is the top left corner of the box.
+    /// The box is white with black text and a border by default.
+    pub fn new<T>(pos: (f32, f32), text: T) -> Self
+    where
+        T: Into<String>,
+    {
+        Self {
+            pos,
+            text: text.into(),
+            scale: 2,
+            padding: 8.,
+            target: None,
+            text_color: Color::from_u32(0x000000FF),
+            background: Color::from_u32(0xFFFFFFFF),
+            border: Color::from_u32(0x000000FF),
+        }
+    }
+
+    /// Sets the text scale. Defaults to `2`.
+    pub fn scale(mut self, scale: u32) -> Self {
+        self.scale = scale.max(1);
+        self
+    }
+
+    /// Sets the space between the text and the border. Defaults to `8`.
+    pub fn padding(mut self, padding: f32) -> Self {
+        self.padding = padding.max(0.);
+        self
+    }
+
+    /// Adds a pointer from the box to the target.
+    pub fn target(mut self, target: (f32, f32)) -> Self {
+        self.target = Some(target);
+        self
+    }
+
+    pub fn colors(mut self, text: Color, background: Color, border: Color) -> Self {
+        self.text_color = text;
+        self.background = background;
+        self.border = border;
+        self
+    }
+
+    pub fn build(self) -> Annotation {
+        const BORDER: f32 = 2.;
+
+        let text = Text::new(&self.text, self.scale, self.text_color);
+        let (tw, th) = text.size();
+        let size = (tw as f32 + self.padding * 2., th as f32 + self.padding * 2.);
+
+        let body = rect(self.pos, size);
+        let tail = self.target.map(|target| {
+            let center = (self.pos.0 + size.0 / 2., self.pos.1 + size.1 / 2.);
+            let (dx, dy) = (target.0 - center.0, target.1 - center.1);
+            let len = (dx * dx + dy * dy).sqrt().max(f32::EPSILON);
+            let base = size.0.min(size.1) / 4.;
+            let (nx, ny) = (-dy / len * base, dx / len * base);
+            Path::new()
+                .move_to((center.0 + nx, center.1 + ny))
+                .line_to(target)
+                .line_to((center.0 - nx, center.1 - ny))
+                .close()
+        });
+
+        // Fills go over the strokes, so only the outer outline stays visible
+        let mut ann = Annotation::new();
+        for path in tail.iter().chain([&body]) {
+            ann.push(Stroke::new(path, BORDER * 2., self.border).cap(Cap::Round));
+        }
+
+        for path in tail.iter().chain([&body]) {
+            ann.push(Fill::new(path, self.background));
+        }
+
+        let offset = (
+            (self.pos.0 + self.padding).round() as i32,
+            (self.pos.1 + self.padding).round() as i32,
+        );
+
+        ann.push(Offset::new(text, offset));
+        ann
+    }
+}
+
+/// A crosshair marking a point.
+pub struct Crosshair {
+    center: (f32, f32),
+    size: f32,
+    gap: f32,
+    width: f32,
+    color: Color,
+    label: Option<(String, u32)>,
+}
+
+impl Crosshair {
+    /// The [`Crosshair`] constructor.
+    ///
+    /// The `size` is the length of each arm from the center.
+    pub fn new(center: (f32, f32), size: f32) -> Self {
+        Self {
+            center,
+            size,
+            gap: size / 4.,
+            width: 2.,
+            color: Color::from_u32(0xE02020FF),
+            label: None,
+        }
+    }
+
+    /// Sets the empty space around the center. Defaults to a quarter of the size.
+    pub fn gap(mut self, gap: f32) -> Self {
+        self.gap = gap.max(0.);
+        self
+    }
+
+    /// Sets the line width. Defaults to `2`.
+    ///
+    /// # Panics
+    /// Panics when a `width` is less than or equal to zero.
+    pub fn width(mut self, width: f32) -> Self {
+        assert!(
+            width > f32::EPSILON,
+            "width cannot be less than or equal to zero",
+        );
+
+        self.width = width;
+        self
+    }
+
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Puts a label to the top right of the crosshair.
+    pub fn label<L>(mut self, text: L, scale: u32) -> Self
+    where
+        L: Into<String>,
+    {
+        self.label = Some((text.into(), scale.max(1)));
+        self
+    }
+
+    pub fn build(self) -> Annotation {
+        let (cx, cy) = self.center;
+        let (near, far) = (self.gap, self.size.max(self.gap));
+        let arms = [(1., 0.), (-1., 0.), (0., 1.), (0., -1.)].into_iter().fold(
+            Path::new(),
+            |path, (dx, dy)| {
+                path.move_to((cx + dx * near, cy + dy * near))
+                    .line_to((cx + dx * far, cy + dy * far))
+            },
+        );
+
+        let mut ann = Annotation::new();
+        ann.push(Stroke::new(&arms, self.width, self.color));
+
+        if let Some((text, scale)) = &self.label {
+            let (w, h) = Text::new(text, *scale, self.color).size();
+            let center = (
+                cx + self.size + w as f32 / 2. + *scale as f32 * 2.,
+                cy - self.size - h as f32 / 2.,
+            );
+
+            ann.label(
+                text,
+                *scale,
+                center,
+                (Color::from_u32(0xFFFFFFFF), self.color),
+            );
+        }
+
+        ann
+    }
+}
+
+/// A ruler measuring the distance between two points.
+pub struct Ruler {
+    from: (f32, f32),
+    to: (f32, f32),
+    step: f32,
+    color: Color,
+    label: Option<u32>,
+}
+
+impl Ruler {
+    /// The [`Ruler`] constructor.
+    ///
+    /// The ruler has a tick every 10 pixels and is labeled with its length by default.
+    pub fn new(from: (f32, f32), to: (f32, f32)) -> Self {
+        Self {
+            from,
+            to,
+            step: 10.,
+            color: Color::from_u32(0xE0C020FF),
+            label: Some(1),
+        }
+    }
+
+    /// Sets the distance between ticks, every fifth tick is longer.
+    ///
+    /// # Panics
+    /// Panics when a `step` is less than one.
+    pub fn step(mut self, step: f32) -> Self {
+        assert!(step >= 1., "step cannot be less than one");
+        self.step = step;
+        self
+    }
+
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Sets the scale of the length label, `None` hides it. Defaults to `Some(1)`.
+    pub fn label(mut self, scale: Option<u32>) -> Self {
+        self.label = scale.map(|s| s.max(1));
+        self
+    }
+
+    pub fn build(self) -> Annotation {
+        const TICK: f32 = 4.;
+
+        let (dx, dy) = (self.to.0 - self.from.0, self.to.1 - self.from.1);
+        let len = (dx * dx + dy * dy).sqrt();
+        let (ux, uy) = if len > 0. {
+            (dx / len, dy / len)
+        } else {
+            (1., 0.)
+        };
+
+        let (nx, ny) = (-uy, ux);
+        let mut path = Path::new().move_to(self.from).line_to(self.to);
+        let ticks = (len / self.step) as u32;
+        for i in 0..=ticks {
+            let d = i as f32 * self.step;
+            let t = if i % 5 == 0 { TICK * 2. } else { TICK };
+            let (px, py) = (self.from.0 + ux * d, self.from.1 + uy * d);
+            path = path.move_to((px, py)).line_to((px + nx * t, py + ny * t));
+        }
+
+        let end = TICK * 2.;
+        path = path
+            .move_to(self.to)
+            .line_to((self.to.0 + nx * end, self.to.1 + ny * end));
+
+        let mut ann = Annotation::new();
+        ann.push(Stroke::new(&path, 1., self.color).cap(Cap::Square));
+
+        if let Some(scale) = self.label {
+            let text = format!("{}px", len.round());
+            let gap = TICK * 2. + (Text::GLYPH_SIZE * scale) as f32;
+            let center = (
+                (self.from.0 + self.to.0) / 2. - nx * gap,
+                (self.from.1 + self.to.1) / 2. - ny * gap,
+            );
+
+            ann.label(
+                &text,
+                scale,
+                center,
+                (Color::from_u32(0x000000FF), self.color),
+            );
+        }
+
+        ann
+    }
+}
+
+/// Returns a rectangle path.
+fn rect((x, y): (f32, f32), (w, h): (f32, f32)) -> Path {
+    Path::new()
+        .move_to((x, y))
+        .line_to((x + w, y))
+        .line_to((x + w, y + h))
+        .line_to((x, y + h))
+        .close()
+}
@@ -0,0 +1,78 @@
+use {
+    crate::{
+        source::{Borders, Source},
+        Color,
+    },
+    std::fmt::Write,
+};
+
+/// Renders a source as colored terminal text.
+///
+/// With [`AnsiStyle::HalfBlock`] every character cell shows two pixels stacked
+/// vertically, so the source is sampled over `cols × rows * 2` pixels.
+/// With [`AnsiStyle::Ascii`] every cell shows a single pixel.
+/// Transparent colors are composited over black.
+///
+/// Like [`make`](crate::make), the source is prepared for the sampled pixels first.
+pub fn make_ansi<S>(mut source: S, (cols, rows): (u32, u32), style: AnsiStyle) -> String
+where
+    S: Source,
+{
+    const RESET: &str = "\x1b[0m";
+
+    let height = match style {
+        AnsiStyle::HalfBlock => rows * 2,
+        AnsiStyle::Ascii => rows,
+    };
+
+    if cols > 0 && height > 0 {
+        source.prepare(Borders {
+            w: (0, cols as i32 - 1),
+            h: (0, height as i32 - 1),
+        });
+    }
+
+    let color = |pos: (u32, u32)| {
+        let col = source.source((pos.0 as _, pos.1 as _));
+        let [r, g, b, _] = (col * col.a).into_byte_array();
+        (r, g, b)
+    };
+
+    let mut out = String::new();
+    for row in 0..rows {
+        for col in 0..cols {
+            match style {
+                AnsiStyle::HalfBlock => {
+                    let (tr, tg, tb) = color((col, row * 2));
+                    let (br, bg, bb) = color((col, row * 2 + 1));
+                    _ = write!(
+                        out,
+                        "\x1b[38;2;{tr};{tg};{tb}m\x1b[48;2;{br};{bg};{bb}m\u{2580}",
+                    );
+                }
+                AnsiStyle::Ascii => {
+                    const RAMP: &[u8] = b" .:-=+*#%@";
+
+                    let (r, g, b) = color((col, row));
+                    let luma = Color::from_byte_array([r, g, b, u8::MAX]).luminance();
+                    let idx = (luma * (RAMP.len() - 1) as f32).round() as usize;
+                    let ch = RAMP[idx.min(RAMP.len() - 1)] as char;
+                    _ = write!(out, "\x1b[38;2;{r};{g};{b}m{ch}");
+                }
+            }
+        }
+
+        out.push_str(RESET);
+        out.push('\n');
+    }
+
+    out
+}
+
+pub enum AnsiStyle {
+    /// Upper half block characters with true color foreground and background.
+    HalfBlock,
+
+    /// Characters from a brightness ramp with true color foreground.
+    Ascii,
+}
@@ -0,0 +1,90 @@
+use std::{fmt::Display, process, str::FromStr};
+
+/// Command line arguments left to parse.
+pub struct Args(Vec<String>);
+
+impl Args {
+    pub fn from_env() -> Self {
+        Self(std::env::args().skip(1).collect())
+    }
+
+    /// Takes the first argument if it equals the given subcommand name.
+    pub fn subcommand(&mut self, name: &str) -> bool {
+        let found = self.0.first().is_some_and(|arg| arg == name);
+        if found {
+            self.0.remove(0);
+        }
+
+        found
+    }
+
+    /// Takes a flag with no value.
+    pub fn flag(&mut self, name: &str) -> bool {
+        match self.0.iter().position(|arg| arg == name) {
+            Some(idx) => {
+                self.0.remove(idx);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Takes a value of a flag with one of the given names.
+    pub fn value(&mut self, names: &[&str]) -> Option<String> {
+        let idx = self
+            .0
+            .iter()
+            .position(|arg| names.contains(&arg.as_str()))?;
+        self.0.remove(idx);
+        if idx < self.0.len() {
+            Some(self.0.remove(idx))
+        } else {
+            fail(format_args!("{} requires a value", names[0]))
+        }
+    }
+
+    /// Takes and parses a value of a flag with one of the given names.
+    pub fn parse<T>(&mut self, names: &[&str]) -> Option<T>
+    where
+        T: FromStr,
+    {
+        let value = self.value(names)?;
+        match value.parse() {
+            Ok(v) => Some(v),
+            Err(_) => fail(format_args!("invalid value {value:?} for {}", names[0])),
+        }
+    }
+
+    /// Takes and parses a size in the `WxH` form.
+    pub fn size(&mut self, names: &[&str]) -> Option<(u32, u32)> {
+        let value = self.value(names)?;
+        let size = value
+            .split_once('x')
+            .and_then(|(w, h)| Some((w.parse().ok()?, h.parse().ok()?)));
+
+        match size {
+            Some(size) => Some(size),
+            None => fail(format_args!("invalid size {value:?}, expected WxH")),
+        }
+    }
+
+    /// Returns the remaining positional arguments.
+    ///
+    /// Fails if any unknown flag is left.
+    pub fn finish(self) -> Vec<String> {
+        if let Some(arg) = self.0.iter().find(|arg| arg.starts_with('-')) {
+            fail(format_args!("unknown flag {arg}"));
+        }
+
+        self.0
+    }
+}
+
+/// Prints an error and exits.
+pub fn fail<D>(msg: D) -> !
+where
+    D: Display,
+{
+    eprintln!("error: {msg}");
+    process::exit(1)
+}
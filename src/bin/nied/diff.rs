@@ -0,0 +1,62 @@
+use {
+    crate::{
+        args::{self, Args},
+        encode::Encoding,
+    },
+    image::RgbaImage,
+    nied::{
+        metrics,
+        source::{Diff, DiffMode},
+        Image,
+    },
+};
+
+pub fn run(mut args: Args) {
+    let output = args
+        .value(&["-o", "--output"])
+        .unwrap_or_else(|| "diff.png".to_owned());
+
+    let amplify = args.parse(&["--amplify"]).unwrap_or(4_f32);
+    if amplify < 0. {
+        args::fail("amplify cannot be less than zero");
+    }
+
+    let (psnr, ssim) = match args.value(&["--metric"]).as_deref() {
+        None | Some("all") => (true, true),
+        Some("psnr") => (true, false),
+        Some("ssim") => (false, true),
+        Some(metric) => args::fail(format_args!(
+            "unknown metric {metric:?}, expected psnr, ssim or all",
+        )),
+    };
+
+    let encoding = Encoding::from_args(&mut args);
+    let [a, b] = <[String; 2]>::try_from(args.finish()).unwrap_or_else(|_| {
+        args::fail("usage: nied diff <a> <b> [-o diff.png] [--metric psnr|ssim|all] [--amplify K]")
+    });
+
+    let load = |path: &str| {
+        let im = image::open(path).unwrap_or_else(|err| args::fail(format_args!("{path}: {err}")));
+        Image::from_dynamic(im.into_rgba8().into()).expect("rgba image")
+    };
+
+    let (a_im, b_im) = (load(&a), load(&b));
+    if a_im.size() != b_im.size() {
+        let ((aw, ah), (bw, bh)) = (a_im.size(), b_im.size());
+        args::fail(format_args!(
+            "{a} is {aw}x{ah} but {b} is {bw}x{bh}, images must have the same size",
+        ));
+    }
+
+    if psnr {
+        println!("psnr: {:.2} dB", metrics::psnr(&a_im, &b_im));
+    }
+
+    if ssim {
+        println!("ssim: {:.4}", metrics::ssim(&a_im, &b_im));
+    }
+
+    let size = a_im.size();
+    let diff = Diff::new(&a_im, &b_im, amplify, DiffMode::Heat);
+    encoding.save(RgbaImage::from(nied::make(diff, size)), &output);
+}
@@ -0,0 +1,139 @@
+use {
+    crate::args::{self, Args},
+    image::{
+        codecs::{
+            bmp::BmpEncoder,
+            jpeg::JpegEncoder,
+            png::{CompressionType, FilterType, PngEncoder},
+            tiff::TiffEncoder,
+            webp::{WebPEncoder, WebPQuality},
+        },
+        ColorType, DynamicImage, ImageEncoder, ImageResult, RgbaImage,
+    },
+    std::{fs::File, io::BufWriter, path::Path},
+};
+
+#[derive(Clone, Copy)]
+pub enum Format {
+    Png,
+    Jpeg,
+    WebP,
+    Bmp,
+    Tiff,
+}
+
+impl Format {
+    fn from_name(name: &str) -> Option<Self> {
+        match &*name.to_ascii_lowercase() {
+            "png" => Some(Self::Png),
+            "jpg" | "jpeg" => Some(Self::Jpeg),
+            "webp" => Some(Self::WebP),
+            "bmp" => Some(Self::Bmp),
+            "tif" | "tiff" => Some(Self::Tiff),
+            _ => None,
+        }
+    }
+}
+
+/// Output encoding options.
+pub struct Encoding {
+    format: Option<Format>,
+    quality: Option<u8>,
+    compression: CompressionType,
+}
+
+impl Encoding {
+    /// Takes `--format`, `--quality` and `--compression` flags.
+    pub fn from_args(args: &mut Args) -> Self {
+        let format = args.value(&["--format"]).map(|name| {
+            Format::from_name(&name).unwrap_or_else(|| {
+                args::fail(format_args!(
+                    "unknown format {name:?}, expected png, jpeg, webp, bmp or tiff",
+                ))
+            })
+        });
+
+        let quality = args.parse(&["--quality"]);
+        if quality.is_some_and(|q| !(1..=100).contains(&q)) {
+            args::fail("quality must be in 1..=100");
+        }
+
+        let compression = match args.value(&["--compression"]).as_deref() {
+            None | Some("default") => CompressionType::Default,
+            Some("fast") => CompressionType::Fast,
+            Some("best") => CompressionType::Best,
+            Some(level) => args::fail(format_args!(
+                "unknown compression {level:?}, expected fast, default or best",
+            )),
+        };
+
+        Self {
+            format,
+            quality,
+            compression,
+        }
+    }
+
+    /// Saves the image in the format given by the flag or the extension of the path.
+    ///
+    /// The quality applies to lossy formats: JPEG, which defaults to `90`, and WebP,
+    /// which is lossless without it. Formats without alpha drop it.
+    pub fn save(&self, im: RgbaImage, path: &str) {
+        let format = self.format.or_else(|| {
+            let ext = Path::new(path).extension()?.to_str()?;
+            Format::from_name(ext)
+        });
+
+        let Some(format) = format else {
+            args::fail(format_args!(
+                "{path}: unknown output format, use a known extension or --format",
+            ));
+        };
+
+        if let Err(err) = self.write(im, path, format) {
+            args::fail(format_args!("{path}: {err}"));
+        }
+    }
+
+    fn write(&self, im: RgbaImage, path: &str, format: Format) -> ImageResult<()> {
+        let (width, height) = im.dimensions();
+        let mut file = BufWriter::new(File::create(path)?);
+        match format {
+            Format::Png => {
+                PngEncoder::new_with_quality(&mut file, self.compression, FilterType::Adaptive)
+                    .write_image(&im, width, height, ColorType::Rgba8)
+            }
+            Format::Jpeg => {
+                let im = DynamicImage::from(im).into_rgb8();
+                JpegEncoder::new_with_quality(&mut file, self.quality.unwrap_or(90)).write_image(
+                    &im,
+                    width,
+                    height,
+                    ColorType::Rgb8,
+                )
+            }
+            // Lossy WebP encoding is deprecated in favour of the lossless one,
+            // which is still used when no quality is given
+            #[allow(deprecated)]
+            Format::WebP => {
+                let quality = match self.quality {
+                    Some(q) => WebPQuality::lossy(q),
+                    None => WebPQuality::lossless(),
+                };
+
+                WebPEncoder::new_with_quality(&mut file, quality).write_image(
+                    &im,
+                    width,
+                    height,
+                    ColorType::Rgba8,
+                )
+            }
+            Format::Bmp => {
+                BmpEncoder::new(&mut file).write_image(&im, width, height, ColorType::Rgba8)
+            }
+            Format::Tiff => {
+                TiffEncoder::new(&mut file).write_image(&im, width, height, ColorType::Rgba8)
+            }
+        }
+    }
+}
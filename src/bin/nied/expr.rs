@@ -0,0 +1,32 @@
+use {
+    crate::{
+        args::{self, Args},
+        encode::Encoding,
+        tiling::Check,
+    },
+    image::RgbaImage,
+    nied::source::Expr,
+};
+
+pub fn run(mut args: Args) {
+    let output = args
+        .value(&["-o", "--output"])
+        .unwrap_or_else(|| "expr.png".to_owned());
+
+    let size = args.size(&["--size"]).unwrap_or((256, 256));
+    let time = args.parse(&["--time"]).unwrap_or(0.);
+    let encoding = Encoding::from_args(&mut args);
+    let check = Check::from_args(&mut args);
+    let [text] = <[String; 1]>::try_from(args.finish()).unwrap_or_else(|_| {
+        args::fail("usage: nied expr <expression> [-o out.png] [--size WxH] [--time T] [--check-tiling tiled.png] [--seams]")
+    });
+
+    let expr = Expr::new(&text)
+        .unwrap_or_else(|err| args::fail(format_args!("invalid expression: {err:?}")))
+        .size(size)
+        .time(time);
+
+    let frame = nied::make(expr, size);
+    check.run(&frame, &encoding);
+    encoding.save(RgbaImage::from(frame), &output);
+}
@@ -1,25 +1,84 @@
+mod args;
+mod diff;
+mod encode;
+mod expr;
+mod montage;
+#[cfg(feature = "preview")]
+mod preview;
+mod scene;
+mod thumb;
+mod tiling;
+
+use {args::Args, encode::Encoding};
+
 fn main() {
-    use nied::{
-        source::{Blur, Filter, Offset, Scale, Source},
-        Image,
+    let mut args = Args::from_env();
+    if args.subcommand("montage") {
+        montage::run(args);
+    } else if args.subcommand("expr") {
+        expr::run(args);
+    } else if args.subcommand("ops") {
+        scene::ops(args);
+    } else if args.subcommand("describe") {
+        scene::describe(args);
+    } else if args.subcommand("scene") {
+        scene::run(args);
+    } else if args.subcommand("thumb") {
+        thumb::run(args);
+    } else if args.subcommand("diff") {
+        diff::run(args);
+    } else {
+        demo(args);
+    }
+}
+
+fn demo(mut args: Args) {
+    use {
+        image::RgbaImage,
+        nied::{
+            graph::Graph,
+            source::{Blur, Filter, Offset, Scale},
+            AnsiStyle, Image,
+        },
     };
 
+    const PREVIEW_COLS: u32 = 80;
+
+    let output_path = args
+        .value(&["-o", "--output"])
+        .unwrap_or_else(|| "out.png".to_owned());
+
+    let encoding = Encoding::from_args(&mut args);
+    let preview = args.flag("--preview-term");
+    args.finish();
+
     let source = {
         let im = image::open("./unknown.png").expect("open");
         Image::from_dynamic(im).expect("image")
     };
 
-    let source = Scale::new(source, 0.1, Filter::Near);
-    let blur = Blur::new(&source, 8);
+    let mut graph = Graph::new();
+    let source = graph.leaf(Scale::new(source, 0.1, Filter::Near));
+    let blur = graph.add([source], |[source]| Blur::new(source, 8));
+    let output = graph.add([blur; 4], |taps| {
+        let offsets = [(200, 200), (100, 100), (400, 200), (200, 400)];
+        taps.into_iter()
+            .zip(offsets)
+            .map(|(tap, delta)| Offset::new(tap, delta))
+            .collect::<Vec<_>>()
+    });
 
-    let source: &[Offset<&(dyn Source + Sync)>] = &[
-        Offset::new(&blur, (200, 200)),
-        Offset::new(&blur, (100, 100)),
-        Offset::new(&blur, (400, 200)),
-        Offset::new(&blur, (200, 400)),
-    ];
+    let (width, height) = (600, 600);
+    let frame = graph.make(output, (width, height));
+    if preview {
+        let factor = PREVIEW_COLS as f32 / width as f32;
+        let rows = (height as f32 * factor / 2.).ceil() as u32;
+        let im = Scale::new(&frame, factor, Filter::Linear);
+        print!(
+            "{}",
+            nied::make_ansi(im, (PREVIEW_COLS, rows), AnsiStyle::HalfBlock),
+        );
+    }
 
-    nied::make(source, (600, 600))
-        .save("out.png")
-        .expect("save image");
+    encoding.save(RgbaImage::from(frame), &output_path);
 }
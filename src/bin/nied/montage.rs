@@ -0,0 +1,50 @@
+use {
+    crate::{
+        args::{self, Args},
+        encode::Encoding,
+    },
+    image::RgbaImage,
+    nied::{Color, Image, Montage},
+    std::path::Path,
+};
+
+pub fn run(mut args: Args) {
+    let output = args
+        .value(&["-o", "--output"])
+        .unwrap_or_else(|| "montage.png".to_owned());
+
+    let cols = args.parse(&["--cols"]).unwrap_or(4);
+    let cell = args.size(&["--cell"]).unwrap_or((256, 256));
+    let gutter = args.parse(&["--gutter"]).unwrap_or(8);
+    let border = args.parse(&["--border"]);
+    let labels = args.flag("--labels");
+    let encoding = Encoding::from_args(&mut args);
+    let files = args.finish();
+    if files.is_empty() {
+        args::fail("usage: nied montage <images...> [-o out.png] [--cols N] [--cell WxH] [--gutter N] [--border N] [--labels] [--format F] [--quality Q] [--compression C]");
+    }
+
+    let mut montage = Montage::new(cols, cell).gutter(gutter);
+    if let Some(thickness) = border {
+        montage = montage.border(thickness, Color::from_u32(0xFFFFFFFF));
+    }
+
+    for file in &files {
+        let im = image::open(file).unwrap_or_else(|err| args::fail(format_args!("{file}: {err}")));
+        let im = Image::from_dynamic(im.into_rgba8().into()).expect("rgba image");
+        montage = if labels {
+            let name = Path::new(file)
+                .file_stem()
+                .map_or_else(|| file.clone(), |stem| stem.to_string_lossy().into_owned());
+
+            montage.push_labeled(im, name)
+        } else {
+            montage.push(im)
+        };
+    }
+
+    let grid = montage.build();
+    let size = grid.size();
+    let im = RgbaImage::from(nied::make(&grid, size));
+    encoding.save(im, &output);
+}
@@ -0,0 +1,85 @@
+use {
+    crate::args,
+    minifb::{Key, Window, WindowOptions},
+    nied::{scene::Scene, source::Offset},
+    std::{fs, sync::Arc, time::SystemTime},
+};
+
+/// The number of rows rendered between window updates.
+const BAND: u32 = 16;
+
+/// Shows the scene in a window, rendering it band by band
+/// and reloading it when the file changes. Escape closes the window.
+pub fn run(path: &str) {
+    let mut scene = load(path).unwrap_or_else(|err| args::fail(err));
+    let mut window = open(path, scene.size());
+    let mut buffer = vec![];
+    let mut row = 0;
+    let mut changed = modified(path);
+    while window.is_open() && !window.is_key_down(Key::Escape) {
+        let (width, height) = scene.size();
+        buffer.resize(width as usize * height as usize, 0);
+        if row < height {
+            let band = BAND.min(height - row);
+            let output = Offset::new(Arc::clone(scene.output()), (0, -(row as i32)));
+            let frame = nied::make(output, (width, band));
+            let start = row as usize * width as usize;
+            for (px, rgba) in buffer[start..].iter_mut().zip(frame.data.chunks(4)) {
+                *px = pack(rgba);
+            }
+
+            row += band;
+        }
+
+        if let Err(err) = window.update_with_buffer(&buffer, width as usize, height as usize) {
+            args::fail(format_args!("failed to update the window: {err}"));
+        }
+
+        let now = modified(path);
+        if now == changed {
+            continue;
+        }
+
+        changed = now;
+        match load(path) {
+            Ok(new) => {
+                if new.size() != scene.size() {
+                    window = open(path, new.size());
+                }
+
+                scene = new;
+                row = 0;
+            }
+            Err(err) => eprintln!("{err}"),
+        }
+    }
+}
+
+fn load(path: &str) -> Result<Scene, String> {
+    let text = fs::read_to_string(path).map_err(|err| format!("{path}: {err}"))?;
+    Scene::parse(&text).map_err(|err| format!("{path}: {err:?}"))
+}
+
+fn open(path: &str, (width, height): (u32, u32)) -> Window {
+    let mut window = Window::new(
+        &format!("nied - {path}"),
+        width as usize,
+        height as usize,
+        WindowOptions::default(),
+    )
+    .unwrap_or_else(|err| args::fail(format_args!("failed to open a window: {err}")));
+
+    window.set_target_fps(60);
+    window
+}
+
+fn modified(path: &str) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
+
+/// Packs a pixel composited over black into the `0RGB` window format.
+fn pack(rgba: &[u8]) -> u32 {
+    let a = rgba[3] as u32;
+    let ch = |c: u8| c as u32 * a / 255;
+    ch(rgba[0]) << 16 | ch(rgba[1]) << 8 | ch(rgba[2])
+}
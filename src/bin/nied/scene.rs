@@ -0,0 +1,100 @@
+use {
+    crate::{
+        args::{self, Args},
+        encode::Encoding,
+        tiling::Check,
+    },
+    image::RgbaImage,
+    nied::scene::{self, Kind, Scene},
+};
+
+/// Prints all ops with their summaries.
+pub fn ops(args: Args) {
+    if !args.finish().is_empty() {
+        args::fail("usage: nied ops");
+    }
+
+    let width = scene::ops()
+        .iter()
+        .map(|op| op.name.len())
+        .max()
+        .unwrap_or(0);
+    for op in scene::ops() {
+        println!("{:width$}  {}", op.name, op.summary);
+    }
+}
+
+/// Prints inputs and parameters of an op.
+pub fn describe(args: Args) {
+    let [name] = <[String; 1]>::try_from(args.finish())
+        .unwrap_or_else(|_| args::fail("usage: nied describe <op>"));
+
+    let Some(op) = scene::op(&name) else {
+        args::fail(format_args!("unknown op {name:?}, see nied ops"));
+    };
+
+    println!("{}", op.summary);
+    let inputs = match (*op.inputs.start(), *op.inputs.end()) {
+        (0, 0) => "none".to_owned(),
+        (min, usize::MAX) => format!("{min} or more"),
+        (min, max) if min == max => min.to_string(),
+        (min, max) => format!("{min} to {max}"),
+    };
+
+    println!("\ninputs: {inputs}");
+    if op.params.is_empty() {
+        return;
+    }
+
+    println!("\nparameters:");
+    for param in op.params {
+        let kind = match param.kind {
+            Kind::Int => "int",
+            Kind::Float => "float",
+            Kind::Color => "color",
+            Kind::Pair => "x,y",
+            Kind::Bool => "bool",
+            Kind::Text => "text",
+        };
+
+        let default = if param.default.is_empty() {
+            "required".to_owned()
+        } else {
+            format!("default {}", param.default)
+        };
+
+        println!("  {}: {kind}, {default}", param.name);
+        println!("      {}", param.doc);
+    }
+}
+
+/// Renders a scene file, or shows it in a window with `--preview`.
+pub fn run(mut args: Args) {
+    let preview = args.flag("--preview");
+    let output = args
+        .value(&["-o", "--output"])
+        .unwrap_or_else(|| "scene.png".to_owned());
+
+    let encoding = Encoding::from_args(&mut args);
+    let check = Check::from_args(&mut args);
+    let [path] = <[String; 1]>::try_from(args.finish())
+        .unwrap_or_else(|_| args::fail("usage: nied scene <file> [-o out.png] [--preview] [--check-tiling tiled.png] [--seams]"));
+
+    if preview {
+        #[cfg(feature = "preview")]
+        return crate::preview::run(&path);
+
+        #[cfg(not(feature = "preview"))]
+        args::fail("nied is built without the preview feature");
+    }
+
+    let text = std::fs::read_to_string(&path)
+        .unwrap_or_else(|err| args::fail(format_args!("{path}: {err}")));
+
+    let scene =
+        Scene::parse(&text).unwrap_or_else(|err| args::fail(format_args!("{path}: {err:?}")));
+
+    let frame = scene.render();
+    check.run(&frame, &encoding);
+    encoding.save(RgbaImage::from(frame), &output);
+}
@@ -0,0 +1,135 @@
+use {
+    crate::{
+        args::{self, Args},
+        encode::Encoding,
+    },
+    image::{DynamicImage, RgbaImage},
+    nied::{
+        jpeg_segments,
+        source::{Fit, FitMode, Offset, RoundedCorners, Source},
+        Image, RenderOptions, Sampling,
+    },
+    std::fs,
+};
+
+/// The largest supersampling grid used to downscale.
+const MAX_GRID: u32 = 8;
+
+pub fn run(mut args: Args) {
+    let output = args
+        .value(&["-o", "--output"])
+        .unwrap_or_else(|| "thumb.png".to_owned());
+
+    let size = args.parse(&["--size"]).unwrap_or(256_u32);
+    let radius = args.parse(&["--radius"]).unwrap_or(0_f32);
+    let square = args.flag("--square");
+    let encoding = Encoding::from_args(&mut args);
+    let [input] = <[String; 1]>::try_from(args.finish()).unwrap_or_else(|_| {
+        args::fail("usage: nied thumb <image> [-o thumb.png] [--size N] [--radius R] [--square]")
+    });
+
+    if size == 0 {
+        args::fail("size cannot be zero");
+    }
+
+    let bytes = fs::read(&input).unwrap_or_else(|err| args::fail(format_args!("{input}: {err}")));
+    let im = image::load_from_memory(&bytes)
+        .unwrap_or_else(|err| args::fail(format_args!("{input}: {err}")));
+
+    let im = orient(im, orientation(&bytes).unwrap_or(1));
+    let im = Image::from_dynamic(im.into_rgba8().into()).expect("rgba image");
+
+    // The thumbnail fits into the size without upscaling
+    let (w, h) = im.size();
+    let k = (size as f32 / w.max(h) as f32).min(1.);
+    let tw = ((w as f32 * k).round() as u32).max(1);
+    let th = ((h as f32 * k).round() as u32).max(1);
+
+    // The image is fitted into a grid times larger box, then averaged over the grid
+    let n = ((1. / k).ceil() as u32).clamp(1, MAX_GRID);
+    let fit = Fit::new(im, (tw * n, th * n), FitMode::Fill);
+    let thumb: Box<dyn Source + Sync> = if radius > 0. {
+        Box::new(RoundedCorners::new(fit, radius * n as f32))
+    } else {
+        Box::new(fit)
+    };
+
+    let (canvas, thumb) = if square {
+        let dx = (size - tw) / 2 * n;
+        let dy = (size - th) / 2 * n;
+        ((size, size), Offset::new(thumb, (dx as i32, dy as i32)))
+    } else {
+        ((tw, th), Offset::new(thumb, (0, 0)))
+    };
+
+    let opts = RenderOptions {
+        sampling: Sampling::Grid(n),
+        ..RenderOptions::default()
+    };
+
+    let frame = nied::make_with(thumb, canvas, &opts);
+    encoding.save(RgbaImage::from(frame), &output);
+}
+
+/// Applies an EXIF orientation to the image.
+fn orient(im: DynamicImage, orientation: u16) -> DynamicImage {
+    match orientation {
+        2 => im.fliph(),
+        3 => im.rotate180(),
+        4 => im.flipv(),
+        5 => im.rotate90().fliph(),
+        6 => im.rotate90(),
+        7 => im.rotate270().fliph(),
+        8 => im.rotate270(),
+        _ => im,
+    }
+}
+
+/// Reads the EXIF orientation of a JPEG or TIFF file.
+fn orientation(bytes: &[u8]) -> Option<u16> {
+    if bytes.starts_with(b"II") || bytes.starts_with(b"MM") {
+        return tiff_orientation(bytes);
+    }
+
+    let tiff = jpeg_segments(bytes)
+        .filter(|&(marker, _)| marker == 0xE1)
+        .find_map(|(_, segment)| segment.strip_prefix(b"Exif\0\0"))?;
+
+    tiff_orientation(tiff)
+}
+
+/// Reads the orientation tag of the first IFD of a TIFF structure.
+fn tiff_orientation(tiff: &[u8]) -> Option<u16> {
+    const ORIENTATION: u16 = 0x0112;
+
+    let le = match tiff.get(..2)? {
+        b"II" => true,
+        b"MM" => false,
+        _ => return None,
+    };
+
+    let u16_at = |at: usize| {
+        let b = [*tiff.get(at)?, *tiff.get(at + 1)?];
+        Some(if le {
+            u16::from_le_bytes(b)
+        } else {
+            u16::from_be_bytes(b)
+        })
+    };
+
+    let u32_at = |at: usize| {
+        let b = tiff.get(at..at + 4)?.try_into().ok()?;
+        Some(if le {
+            u32::from_le_bytes(b)
+        } else {
+            u32::from_be_bytes(b)
+        })
+    };
+
+    let ifd = u32_at(4)? as usize;
+    let count = u16_at(ifd)? as usize;
+    (0..count)
+        .map(|i| ifd + 2 + i * 12)
+        .find(|&entry| u16_at(entry) == Some(ORIENTATION))
+        .and_then(|entry| u16_at(entry + 8))
+}
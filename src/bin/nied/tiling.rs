@@ -0,0 +1,47 @@
+use {
+    crate::{args::Args, encode::Encoding},
+    image::RgbaImage,
+    nied::{
+        metrics,
+        source::{Repeat, Source},
+        Frame,
+    },
+};
+
+/// A seamless tiling check of a rendered texture.
+pub struct Check {
+    output: Option<String>,
+    seams: bool,
+}
+
+impl Check {
+    /// Takes `--check-tiling <out>` and `--seams` flags.
+    pub fn from_args(args: &mut Args) -> Self {
+        Self {
+            output: args.value(&["--check-tiling"]),
+            seams: args.flag("--seams"),
+        }
+    }
+
+    /// Saves the frame tiled 3×3 and prints seam metrics if requested.
+    pub fn run(&self, frame: &Frame, encoding: &Encoding) {
+        if let Some(output) = &self.output {
+            let (w, h) = frame.size();
+            let tiled = nied::make(Repeat::new(frame), (w * 3, h * 3));
+            encoding.save(RgbaImage::from(tiled), output);
+        }
+
+        if self.seams {
+            let region = frame.borders().expect("frame borders");
+            let (columns, rows) = metrics::seams(frame, region);
+            for (name, seam) in [("columns", columns), ("rows", rows)] {
+                println!(
+                    "{name}: edge {:.4}, inner {:.4}, ratio {:.2}",
+                    seam.edge,
+                    seam.inner,
+                    seam.ratio(),
+                );
+            }
+        }
+    }
+}
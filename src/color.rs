@@ -1,4 +1,6 @@
-use std::ops;
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::Float;
+use {crate::math::lerp, core::ops};
 
 #[must_use]
 #[derive(Debug, Default, Clone, Copy)]
@@ -50,6 +52,86 @@ impl Color {
         self.a < 1.
     }
 
+    /// Clamps all components to the `0..=1` range.
+    pub fn clamp(self) -> Self {
+        Self {
+            r: self.r.clamp(0., 1.),
+            g: self.g.clamp(0., 1.),
+            b: self.b.clamp(0., 1.),
+            a: self.a.clamp(0., 1.),
+        }
+    }
+
+    /// Returns the relative luminance of the color using the Rec. 709 coefficients.
+    pub fn luminance(self) -> f32 {
+        0.2126 * self.r + 0.7152 * self.g + 0.0722 * self.b
+    }
+
+    /// Converts sRGB encoded components to linear light, keeping the alpha.
+    pub fn to_linear(self) -> Self {
+        let decode = |v: f32| {
+            if v <= 0.04045 {
+                v / 12.92
+            } else {
+                ((v + 0.055) / 1.055).powf(2.4)
+            }
+        };
+
+        Self {
+            r: decode(self.r),
+            g: decode(self.g),
+            b: decode(self.b),
+            a: self.a,
+        }
+    }
+
+    /// Converts linear light components to sRGB encoding, keeping the alpha.
+    pub fn to_srgb(self) -> Self {
+        let encode = |v: f32| {
+            if v <= 0.003_130_8 {
+                v * 12.92
+            } else {
+                1.055 * v.powf(1. / 2.4) - 0.055
+            }
+        };
+
+        Self {
+            r: encode(self.r),
+            g: encode(self.g),
+            b: encode(self.b),
+            a: self.a,
+        }
+    }
+
+    /// Converts the color to naive `[c, m, y, k]` components, dropping the alpha.
+    ///
+    /// The black ink replaces the shared part of the others. The conversion doesn't
+    /// account for inks or paper, so it's a starting point for separations,
+    /// not a substitute for an ICC profile.
+    pub fn to_cmyk(self) -> [f32; 4] {
+        let Self { r, g, b, .. } = self.clamp();
+        let k = 1. - r.max(g).max(b);
+        if k >= 1. {
+            return [0., 0., 0., 1.];
+        }
+
+        let ink = |v: f32| (1. - v - k) / (1. - k);
+        [ink(r), ink(g), ink(b), k]
+    }
+
+    /// Converts naive `[c, m, y, k]` components to an opaque color.
+    ///
+    /// This is the inverse of [`to_cmyk`](Self::to_cmyk).
+    pub fn from_cmyk([c, m, y, k]: [f32; 4]) -> Self {
+        let v = |ink: f32| (1. - ink.clamp(0., 1.)) * (1. - k.clamp(0., 1.));
+        Self {
+            r: v(c),
+            g: v(m),
+            b: v(y),
+            a: 1.,
+        }
+    }
+
     pub fn overlay(self, rhs: Self) -> Self {
         Self {
             r: lerp(self.r, rhs.r, rhs.a),
@@ -67,10 +149,140 @@ impl Color {
             a: lerp(self.a, rhs.a, t),
         }
     }
+
+    /// Interpolates between colors in the given space. The alpha is interpolated linearly.
+    pub fn lerp_in(self, rhs: Self, t: f32, space: LerpSpace) -> Self {
+        // Ends are kept exact, without the round trip through the space
+        if space != LerpSpace::Srgb {
+            if t <= 0. {
+                return self;
+            }
+
+            if t >= 1. {
+                return rhs;
+            }
+        }
+
+        let a = lerp(self.a, rhs.a, t);
+        let mix = |x: [f32; 3], y: [f32; 3]| {
+            [
+                lerp(x[0], y[0], t),
+                lerp(x[1], y[1], t),
+                lerp(x[2], y[2], t),
+            ]
+        };
+
+        match space {
+            LerpSpace::Srgb => self.lerp(rhs, t),
+            LerpSpace::Linear => self.to_linear().lerp(rhs.to_linear(), t).to_srgb(),
+            LerpSpace::Oklab => Self::from_oklab(mix(self.to_oklab(), rhs.to_oklab()), a),
+            LerpSpace::Hsl => {
+                let [mut h0, s0, l0] = self.to_hsl();
+                let [mut h1, s1, l1] = rhs.to_hsl();
+
+                // Gray colors have no hue, so they take the hue of the other one
+                if s0 <= f32::EPSILON {
+                    h0 = h1;
+                } else if s1 <= f32::EPSILON {
+                    h1 = h0;
+                }
+
+                let dh = (h1 - h0 + 0.5).rem_euclid(1.) - 0.5;
+                let h = (h0 + dh * t).rem_euclid(1.);
+                Self::from_hsl([h, lerp(s0, s1, t), lerp(l0, l1, t)], a)
+            }
+        }
+    }
+
+    /// Converts the color to the Oklab `[l, a, b]` components.
+    fn to_oklab(self) -> [f32; 3] {
+        let Self { r, g, b, .. } = self.to_linear();
+        let l = (0.412_221_47 * r + 0.536_332_55 * g + 0.051_445_995 * b).cbrt();
+        let m = (0.211_903_5 * r + 0.680_699_5 * g + 0.107_396_96 * b).cbrt();
+        let s = (0.088_302_46 * r + 0.281_718_85 * g + 0.629_978_7 * b).cbrt();
+        [
+            0.210_454_26 * l + 0.793_617_8 * m - 0.004_072_047 * s,
+            1.977_998_5 * l - 2.428_592_2 * m + 0.450_593_7 * s,
+            0.025_904_037 * l + 0.782_771_77 * m - 0.808_675_77 * s,
+        ]
+    }
+
+    fn from_oklab([l, a, b]: [f32; 3], alpha: f32) -> Self {
+        let cube = |v: f32| v * v * v;
+        let lc = cube(l + 0.396_337_78 * a + 0.215_803_76 * b);
+        let mc = cube(l - 0.105_561_346 * a - 0.063_854_17 * b);
+        let sc = cube(l - 0.089_484_18 * a - 1.291_485_5 * b);
+        Self {
+            r: 4.076_741_7 * lc - 3.307_711_6 * mc + 0.230_969_94 * sc,
+            g: -1.268_438 * lc + 2.609_757_4 * mc - 0.341_319_38 * sc,
+            b: -0.004_196_086_3 * lc - 0.703_418_6 * mc + 1.707_614_7 * sc,
+            a: alpha,
+        }
+        .to_srgb()
+        .clamp()
+    }
+
+    /// Converts the color to `[h, s, l]` components, each in `0..=1` range.
+    fn to_hsl(self) -> [f32; 3] {
+        let Self { r, g, b, .. } = self.clamp();
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let l = (max + min) * 0.5;
+        let d = max - min;
+        if d <= f32::EPSILON {
+            return [0., 0., l];
+        }
+
+        let s = d / (1. - (2. * l - 1.).abs());
+        let h = if max == r {
+            ((g - b) / d).rem_euclid(6.)
+        } else if max == g {
+            (b - r) / d + 2.
+        } else {
+            (r - g) / d + 4.
+        };
+
+        [h / 6., s, l]
+    }
+
+    fn from_hsl([h, s, l]: [f32; 3], alpha: f32) -> Self {
+        let c = (1. - (2. * l - 1.).abs()) * s;
+        let h = h * 6.;
+        let x = c * (1. - (h.rem_euclid(2.) - 1.).abs());
+        let (r, g, b) = match h as u32 {
+            0 => (c, x, 0.),
+            1 => (x, c, 0.),
+            2 => (0., c, x),
+            3 => (0., x, c),
+            4 => (x, 0., c),
+            _ => (c, 0., x),
+        };
+
+        let m = l - c * 0.5;
+        Self {
+            r: r + m,
+            g: g + m,
+            b: b + m,
+            a: alpha,
+        }
+    }
 }
 
-fn lerp(x: f32, y: f32, t: f32) -> f32 {
-    x + t * (y - x)
+/// A color space to interpolate colors in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LerpSpace {
+    /// Interpolates sRGB encoded components directly.
+    #[default]
+    Srgb,
+
+    /// Interpolates in linear light, which mixes colors like light does.
+    Linear,
+
+    /// Interpolates in the Oklab space, which keeps perceived lightness and hue even.
+    Oklab,
+
+    /// Interpolates hue, saturation and lightness, turning the hue the shortest way.
+    Hsl,
 }
 
 impl From<u32> for Color {
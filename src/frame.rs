@@ -0,0 +1,211 @@
+use {
+    crate::{
+        source::{Borders, Source},
+        Color,
+    },
+    alloc::{vec, vec::Vec},
+};
+
+/// A rendered image, RGBA colors row by row.
+///
+/// The data has the layout of a canvas `ImageData`. With the `image` feature
+/// frames convert into `image::RgbaImage` to save or process them further.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Frame {
+    pub width: u32,
+    pub height: u32,
+    pub data: Vec<u8>,
+}
+
+impl Frame {
+    /// The number of bytes of a pixel.
+    pub const N_CHANNELS: usize = 4;
+
+    /// Creates a transparent frame.
+    pub fn new((width, height): (u32, u32)) -> Self {
+        Self {
+            width,
+            height,
+            data: vec![0; width as usize * height as usize * Self::N_CHANNELS],
+        }
+    }
+
+    pub fn size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Returns the pixel bytes at the position.
+    pub fn pixel(&self, (x, y): (u32, u32)) -> Option<[u8; 4]> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+
+        let idx = (y as usize * self.width as usize + x as usize) * Self::N_CHANNELS;
+        let p = &self.data[idx..idx + Self::N_CHANNELS];
+        Some([p[0], p[1], p[2], p[3]])
+    }
+
+    /// Returns an iterator over pixel bytes row by row.
+    pub fn pixels(&self) -> impl Iterator<Item = [u8; 4]> + '_ {
+        self.data
+            .chunks_exact(Self::N_CHANNELS)
+            .map(|p| [p[0], p[1], p[2], p[3]])
+    }
+
+    /// Returns RGBA bytes row by row, ready for a texture upload.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Creates a frame of the size from RGBA bytes row by row.
+    ///
+    /// Returns `None` if the number of bytes doesn't match the size.
+    pub fn from_bytes((width, height): (u32, u32), data: Vec<u8>) -> Option<Self> {
+        let len = (width as usize)
+            .checked_mul(height as usize)?
+            .checked_mul(Self::N_CHANNELS)?;
+
+        (data.len() == len).then_some(Self {
+            width,
+            height,
+            data,
+        })
+    }
+}
+
+impl Source for Frame {
+    fn source(&self, (x, y): (i32, i32)) -> Color {
+        if x < 0 || y < 0 {
+            return Color::default();
+        }
+
+        self.pixel((x as u32, y as u32))
+            .map(Color::from_byte_array)
+            .unwrap_or_default()
+    }
+
+    fn borders(&self) -> Option<Borders> {
+        Some(Borders {
+            w: (0, self.width as i32 - 1),
+            h: (0, self.height as i32 - 1),
+        })
+    }
+}
+
+#[cfg(feature = "image")]
+impl From<Frame> for image::RgbaImage {
+    fn from(frame: Frame) -> Self {
+        Self::from_raw(frame.width, frame.height, frame.data).expect("the buffer fits")
+    }
+}
+
+#[cfg(feature = "image")]
+impl From<image::RgbaImage> for Frame {
+    fn from(im: image::RgbaImage) -> Self {
+        let (width, height) = im.dimensions();
+        Self {
+            width,
+            height,
+            data: im.into_raw(),
+        }
+    }
+}
+
+/// A rendered image of float RGBA components row by row, see [`make_float`](crate::make_float).
+///
+/// Unlike a [`Frame`], components aren't clamped to the `0..=1` range,
+/// so bright colors keep their dynamic range. Colors are sRGB encoded, as sources produce them.
+#[derive(Clone, PartialEq, Debug)]
+pub struct FloatFrame {
+    pub width: u32,
+    pub height: u32,
+    pub data: Vec<f32>,
+}
+
+impl FloatFrame {
+    /// Creates a transparent frame.
+    pub fn new((width, height): (u32, u32)) -> Self {
+        Self {
+            width,
+            height,
+            data: vec![0.; width as usize * height as usize * Frame::N_CHANNELS],
+        }
+    }
+
+    pub fn size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Returns the pixel color at the position.
+    pub fn pixel(&self, (x, y): (u32, u32)) -> Option<Color> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+
+        let idx = (y as usize * self.width as usize + x as usize) * Frame::N_CHANNELS;
+        let p = &self.data[idx..idx + Frame::N_CHANNELS];
+        Some(Color::from_array([p[0], p[1], p[2], p[3]]))
+    }
+
+    /// Returns an iterator over pixel colors row by row.
+    pub fn pixels(&self) -> impl Iterator<Item = Color> + '_ {
+        self.data
+            .chunks_exact(Frame::N_CHANNELS)
+            .map(|p| Color::from_array([p[0], p[1], p[2], p[3]]))
+    }
+
+    /// Converts the frame to bytes, clamping components.
+    pub fn to_frame(&self) -> Frame {
+        Frame {
+            width: self.width,
+            height: self.height,
+            data: self
+                .pixels()
+                .flat_map(|c| c.clamp().into_byte_array())
+                .collect(),
+        }
+    }
+
+    /// Saves the frame as an OpenEXR file of 32-bit float channels.
+    ///
+    /// Colors are stored in linear light with premultiplied alpha, as the format expects.
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be created or the encoding fails.
+    #[cfg(feature = "exr")]
+    pub fn save_exr<P>(&self, path: P) -> std::io::Result<()>
+    where
+        P: AsRef<std::path::Path>,
+    {
+        use exr::{error::Error, prelude::write_rgba_file};
+
+        let size = (self.width as usize, self.height as usize);
+        let res = write_rgba_file(path, size.0, size.1, |x, y| {
+            let c = self.pixel((x as u32, y as u32)).unwrap_or_default();
+            let Color { r, g, b, a } = c.to_linear();
+            (r * a, g * a, b * a, a)
+        });
+
+        res.map_err(|err| match err {
+            Error::Io(err) => err,
+            err => std::io::Error::other(err),
+        })
+    }
+}
+
+impl Source for FloatFrame {
+    fn source(&self, (x, y): (i32, i32)) -> Color {
+        if x < 0 || y < 0 {
+            return Color::default();
+        }
+
+        self.pixel((x as u32, y as u32)).unwrap_or_default()
+    }
+
+    fn borders(&self) -> Option<Borders> {
+        Some(Borders {
+            w: (0, self.width as i32 - 1),
+            h: (0, self.height as i32 - 1),
+        })
+    }
+}
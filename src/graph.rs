@@ -0,0 +1,435 @@
+//! Render graphs with shared nodes.
+//!
+//! Sources composed by nesting can't share a subtree: a source used by several
+//! layers is evaluated again for every layer that samples it. A [`Graph`] keeps
+//! sources as nodes referenced by cheap [`NodeId`] handles, so the same node can
+//! feed many others, and it knows the whole structure before rendering.
+//!
+//! When rendering, every node used more than once is baked over the rendered
+//! region before its consumers are evaluated, so its work is done only once per
+//! pixel. Positions outside the baked region are evaluated as usual.
+//!
+//! Nodes can be named to render several outputs at once with [`Graph::make_all`],
+//! the work shared between them is done only once too.
+
+use {
+    crate::{
+        profile::{Report, Stats},
+        source::{Borders, Source},
+        Color, Frame,
+    },
+    std::{
+        collections::HashMap,
+        ops::Range,
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc, RwLock,
+        },
+    },
+};
+
+/// A handle of a node in a [`Graph`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NodeId(usize);
+
+/// A graph of sources.
+#[derive(Default)]
+pub struct Graph {
+    entries: Vec<Entry>,
+    names: HashMap<String, NodeId>,
+}
+
+struct Entry {
+    node: Arc<Node>,
+    inputs: Vec<NodeId>,
+    cache: Option<bool>,
+}
+
+impl Graph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a source without inputs.
+    pub fn leaf<S>(&mut self, source: S) -> NodeId
+    where
+        S: Source + Send + Sync + 'static,
+    {
+        self.add([], |[]| source)
+    }
+
+    /// Adds a source built from the given input nodes.
+    ///
+    /// The closure receives a [`Tap`] of each input in the same order.
+    ///
+    /// # Panics
+    /// Panics if an input doesn't belong to the graph.
+    pub fn add<const N: usize, S, F>(&mut self, inputs: [NodeId; N], f: F) -> NodeId
+    where
+        S: Source + Send + Sync + 'static,
+        F: FnOnce([Tap; N]) -> S,
+    {
+        let taps = inputs.map(|id| self.tap(id));
+        let id = NodeId(self.entries.len());
+        self.entries.push(Entry {
+            node: Arc::new(Node {
+                source: RwLock::new(Box::new(f(taps))),
+                baked: RwLock::new(None),
+                profiling: AtomicBool::new(false),
+                stats: Stats::default(),
+            }),
+            inputs: inputs.to_vec(),
+            cache: None,
+        });
+
+        id
+    }
+
+    /// Returns a source reading the node.
+    ///
+    /// Taps made outside of [`add`](Self::add) are not known to the graph,
+    /// so they don't count as uses of the node.
+    ///
+    /// # Panics
+    /// Panics if the node doesn't belong to the graph.
+    pub fn tap(&self, id: NodeId) -> Tap {
+        Tap(Arc::clone(&self.entry(id).node))
+    }
+
+    /// Forces baking the node on or off, overriding the automatic choice.
+    ///
+    /// # Panics
+    /// Panics if the node doesn't belong to the graph.
+    pub fn cache(&mut self, id: NodeId, cache: bool) {
+        self.entry_mut(id).cache = Some(cache);
+    }
+
+    /// Names the node, so it can be found with [`named`](Self::named)
+    /// and rendered with [`make_all`](Self::make_all).
+    ///
+    /// A node can have several names. Using an existing name moves it to the node.
+    ///
+    /// # Panics
+    /// Panics if the node doesn't belong to the graph.
+    pub fn name(&mut self, id: NodeId, name: &str) {
+        self.entry(id);
+        self.names.insert(name.to_owned(), id);
+    }
+
+    /// Returns the node with the given name.
+    pub fn named(&self, name: &str) -> Option<NodeId> {
+        self.names.get(name).copied()
+    }
+
+    /// Returns an iterator over all names and their nodes.
+    pub fn names(&self) -> impl Iterator<Item = (&str, NodeId)> {
+        self.names.iter().map(|(name, &id)| (name.as_str(), id))
+    }
+
+    /// Returns the number of nodes.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns an iterator over all nodes in the order they were added,
+    /// so inputs always come before their consumers.
+    pub fn nodes(&self) -> impl Iterator<Item = NodeId> {
+        (0..self.entries.len()).map(NodeId)
+    }
+
+    /// Returns the inputs of the node.
+    ///
+    /// # Panics
+    /// Panics if the node doesn't belong to the graph.
+    pub fn inputs(&self, id: NodeId) -> &[NodeId] {
+        &self.entry(id).inputs
+    }
+
+    /// Returns the number of nodes using the node as an input.
+    ///
+    /// # Panics
+    /// Panics if the node doesn't belong to the graph.
+    pub fn uses(&self, id: NodeId) -> usize {
+        self.entry(id);
+        self.entries
+            .iter()
+            .flat_map(|entry| &entry.inputs)
+            .filter(|&&input| input == id)
+            .count()
+    }
+
+    /// Returns the nodes that will be baked when rendering the output,
+    /// in evaluation order.
+    ///
+    /// Only nodes the output depends on are considered. A node is baked if it's used
+    /// more than once by them, unless it's overridden with [`cache`](Self::cache).
+    ///
+    /// # Panics
+    /// Panics if the output doesn't belong to the graph.
+    pub fn plan(&self, output: NodeId) -> Vec<NodeId> {
+        self.plan_all(&[output])
+    }
+
+    /// Same as [`plan`](Self::plan) but for several outputs rendered together.
+    ///
+    /// Each output counts as a use, so an output also used by
+    /// another one is baked and then read from the bake.
+    ///
+    /// # Panics
+    /// Panics if an output doesn't belong to the graph.
+    pub fn plan_all(&self, outputs: &[NodeId]) -> Vec<NodeId> {
+        let mut reachable = vec![false; self.entries.len()];
+        let mut uses = vec![0; self.entries.len()];
+        for &output in outputs {
+            self.entry(output);
+            reachable[output.0] = true;
+            uses[output.0] += 1;
+        }
+
+        for (n, entry) in self.entries.iter().enumerate().rev() {
+            if !reachable[n] {
+                continue;
+            }
+
+            for input in &entry.inputs {
+                reachable[input.0] = true;
+                uses[input.0] += 1;
+            }
+        }
+
+        self.nodes()
+            .filter(|&id| reachable[id.0])
+            .filter(|&id| self.entries[id.0].cache.unwrap_or(uses[id.0] > 1))
+            .collect()
+    }
+
+    /// Renders the output node like [`make`](crate::make).
+    ///
+    /// # Panics
+    /// Panics if the output doesn't belong to the graph.
+    pub fn make(&self, output: NodeId, size: (u32, u32)) -> Frame {
+        let [im] = self.render([output], size);
+        im
+    }
+
+    /// Renders the named nodes, returning images in the same order as the names.
+    ///
+    /// # Panics
+    /// Panics if there is no node with one of the names.
+    pub fn make_all<const N: usize>(&self, names: [&str; N], size: (u32, u32)) -> [Frame; N] {
+        let outputs = names.map(|name| match self.named(name) {
+            Some(id) => id,
+            None => panic!("the graph has no node named {name:?}"),
+        });
+
+        self.render(outputs, size)
+    }
+
+    /// Renders the output node like [`make`](Self::make), timing every node.
+    ///
+    /// Nodes are reported by their names, unnamed nodes by their index.
+    /// Baked nodes are timed while baking.
+    ///
+    /// # Panics
+    /// Panics if the output doesn't belong to the graph.
+    pub fn make_profiled(&self, output: NodeId, size: (u32, u32)) -> (Frame, Report) {
+        for entry in &self.entries {
+            entry.node.stats.reset();
+            entry.node.profiling.store(true, Ordering::Relaxed);
+        }
+
+        let [im] = self.render([output], size);
+        for entry in &self.entries {
+            entry.node.profiling.store(false, Ordering::Relaxed);
+        }
+
+        let mut names: Vec<_> = self.names().collect();
+        names.sort_unstable();
+        let report = Report::new(self.nodes().map(|id| {
+            let name = match names.iter().find(|(_, named)| *named == id) {
+                Some((name, _)) => name.to_string(),
+                None => format!("#{}", id.0),
+            };
+
+            self.entry(id).node.stats.line(&name)
+        }));
+
+        (im, report)
+    }
+
+    fn render<const N: usize>(&self, outputs: [NodeId; N], size: (u32, u32)) -> [Frame; N] {
+        let plan = self.plan_all(&outputs);
+        let (width, height) = size;
+        let area = Borders {
+            w: (0, width as i32 - 1),
+            h: (0, height as i32 - 1),
+        };
+
+        // Taps of outputs prepare the nodes they read, before some are baked
+        let mut taps = outputs.map(|output| self.tap(output));
+        for tap in &mut taps {
+            tap.prepare(area);
+        }
+
+        for &id in &plan {
+            self.entries[id.0].node.bake(area);
+        }
+
+        // Taps are already prepared, so they're rendered by reference
+        let ims = taps.each_ref().map(|tap| crate::make(tap, size));
+        for &id in &plan {
+            self.entries[id.0].node.clear();
+        }
+
+        ims
+    }
+
+    fn entry(&self, NodeId(n): NodeId) -> &Entry {
+        self.entries.get(n).expect("the node belongs to the graph")
+    }
+
+    fn entry_mut(&mut self, NodeId(n): NodeId) -> &mut Entry {
+        self.entries
+            .get_mut(n)
+            .expect("the node belongs to the graph")
+    }
+}
+
+struct Node {
+    source: RwLock<Box<dyn Source + Send + Sync>>,
+    baked: RwLock<Option<Baked>>,
+    profiling: AtomicBool,
+    stats: Stats,
+}
+
+impl Node {
+    fn eval(&self, pos: (i32, i32)) -> Color {
+        let source = self.source.read().expect("lock");
+        if self.profiling.load(Ordering::Relaxed) {
+            self.stats.measure(1, || source.source(pos))
+        } else {
+            source.source(pos)
+        }
+    }
+
+    fn eval_row(&self, y: i32, xs: Range<i32>, out: &mut [Color]) {
+        let source = self.source.read().expect("lock");
+        if self.profiling.load(Ordering::Relaxed) {
+            let n = xs.len() as u64;
+            self.stats.measure(n, || source.source_row(y, xs, out));
+        } else {
+            source.source_row(y, xs, out);
+        }
+    }
+
+    fn bake(&self, area: Borders) {
+        let area = match self.borders() {
+            Some(borders) => Borders {
+                w: (area.w.0.max(borders.w.0), area.w.1.min(borders.w.1)),
+                h: (area.h.0.max(borders.h.0), area.h.1.min(borders.h.1)),
+            },
+            None => area,
+        };
+
+        let (w, _) = area.size();
+
+        #[cfg(feature = "parallel")]
+        let pixels = {
+            use rayon::prelude::*;
+            let positions: Vec<_> = area.positions().collect();
+            positions
+                .into_par_iter()
+                .map(|pos| self.eval(pos))
+                .collect()
+        };
+
+        #[cfg(not(feature = "parallel"))]
+        let pixels = area.positions().map(|pos| self.eval(pos)).collect();
+
+        *self.baked.write().expect("lock") = Some(Baked {
+            pixels,
+            area,
+            width: w,
+        });
+    }
+
+    fn clear(&self) {
+        *self.baked.write().expect("lock") = None;
+    }
+
+    fn borders(&self) -> Option<Borders> {
+        self.source.read().expect("lock").borders()
+    }
+}
+
+struct Baked {
+    pixels: Vec<Color>,
+    area: Borders,
+    width: u32,
+}
+
+/// A source reading a node of a [`Graph`].
+///
+/// Reads the baked pixels when the node is baked, otherwise evaluates the node.
+#[derive(Clone)]
+pub struct Tap(Arc<Node>);
+
+impl Source for Tap {
+    fn source(&self, (x, y): (i32, i32)) -> Color {
+        if let Some(baked) = &*self.0.baked.read().expect("lock") {
+            if baked.area.contains((x, y)) {
+                let (dx, dy) = (x - baked.area.w.0, y - baked.area.h.0);
+                return baked.pixels[dy as usize * baked.width as usize + dx as usize];
+            }
+        }
+
+        self.0.eval((x, y))
+    }
+
+    fn source_row(&self, y: i32, xs: Range<i32>, out: &mut [Color]) {
+        let baked = self.0.baked.read().expect("lock");
+        match &*baked {
+            Some(baked)
+                if baked.area.contains((xs.start, y)) && baked.area.contains((xs.end - 1, y)) =>
+            {
+                let start = (y - baked.area.h.0) as usize * baked.width as usize;
+                let (x0, x1) = (
+                    (xs.start - baked.area.w.0) as usize,
+                    (xs.end - baked.area.w.0) as usize,
+                );
+                out.copy_from_slice(&baked.pixels[start + x0..start + x1]);
+            }
+            Some(_) => {
+                drop(baked);
+                for (x, col) in xs.zip(out) {
+                    *col = self.source((x, y));
+                }
+            }
+            None => self.0.eval_row(y, xs, out),
+        }
+    }
+
+    fn borders(&self) -> Option<Borders> {
+        self.0.borders()
+    }
+
+    /// Prepares the node, unless it's being sampled at the moment.
+    fn prepare(&mut self, region: Borders) {
+        if let Ok(mut source) = self.0.source.try_write() {
+            source.prepare(region);
+        }
+    }
+
+    /// Reseeds the node and drops its baked pixels,
+    /// unless it's being sampled at the moment.
+    fn reseed(&mut self, seed: u32) {
+        if let Ok(mut source) = self.0.source.try_write() {
+            source.reseed(seed);
+            self.0.clear();
+        }
+    }
+}
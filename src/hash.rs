@@ -0,0 +1,18 @@
+/// Hashes a seed with integer coordinates into a pseudo-random value.
+pub(crate) fn hash(seed: u32, (x, y): (i32, i32)) -> u32 {
+    let mut h = seed ^ 0x9E37_79B9;
+    h = mix(h ^ x as u32);
+    h = mix(h ^ y as u32);
+    h
+}
+
+/// Hashes a seed with integer coordinates into a pseudo-random value in range `0..1`.
+pub(crate) fn unit(seed: u32, pos: (i32, i32)) -> f32 {
+    (hash(seed, pos) >> 8) as f32 / (1 << 24) as f32
+}
+
+fn mix(mut h: u32) -> u32 {
+    h = (h ^ (h >> 16)).wrapping_mul(0x7FEB_352D);
+    h = (h ^ (h >> 15)).wrapping_mul(0x846C_A68B);
+    h ^ (h >> 16)
+}
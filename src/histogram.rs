@@ -0,0 +1,49 @@
+use crate::source::{Borders, Source};
+
+/// Per-channel 256-bin histograms.
+#[derive(Clone)]
+pub struct Histogram {
+    pub r: [u32; 256],
+    pub g: [u32; 256],
+    pub b: [u32; 256],
+    pub a: [u32; 256],
+    pub luma: [u32; 256],
+}
+
+impl Histogram {
+    /// Returns the total number of counted pixels.
+    pub fn total(&self) -> u32 {
+        self.luma.iter().sum()
+    }
+}
+
+impl Default for Histogram {
+    fn default() -> Self {
+        Self {
+            r: [0; 256],
+            g: [0; 256],
+            b: [0; 256],
+            a: [0; 256],
+            luma: [0; 256],
+        }
+    }
+}
+
+/// Computes histograms of the source colors over a region.
+pub fn histogram<S>(source: S, region: Borders) -> Histogram
+where
+    S: Source,
+{
+    let mut hist = Histogram::default();
+    for pos in region.positions() {
+        let col = source.source(pos);
+        let [r, g, b, a] = col.into_byte_array();
+        hist.r[r as usize] += 1;
+        hist.g[g as usize] += 1;
+        hist.b[b as usize] += 1;
+        hist.a[a as usize] += 1;
+        hist.luma[(col.luminance() * 255.) as u8 as usize] += 1;
+    }
+
+    hist
+}
@@ -0,0 +1,122 @@
+use {
+    crate::{jpeg_segments, Image, ImageError},
+    image::{RgbImage, RgbaImage},
+    qcms::{DataType, Intent, Profile, Transform},
+};
+
+impl Image {
+    /// Decodes an image, converting its colors to sRGB with the embedded ICC profile.
+    ///
+    /// Profiles are read from PNG and JPEG files, images without one are assumed
+    /// to be sRGB already. Use [`Color::to_linear`](crate::Color::to_linear)
+    /// to composite the result in linear light.
+    ///
+    /// # Errors
+    /// Returns an [`ImageError`] if the image cannot be decoded
+    /// or the profile cannot be applied.
+    pub fn from_memory_icc(bytes: &[u8]) -> Result<Self, ImageError> {
+        let im = image::load_from_memory(bytes).map_err(ImageError::Decoding)?;
+        let im = Self::from_dynamic(im)?;
+        match embedded(bytes) {
+            Some(profile) => im.convert_icc(&profile),
+            None => Ok(im),
+        }
+    }
+
+    /// Converts colors of the image from the space of the ICC profile to sRGB.
+    ///
+    /// Gray images need a gray profile and turn into RGB ones,
+    /// other images need an RGB profile. 16-bit images aren't supported.
+    ///
+    /// # Errors
+    /// Returns an [`ImageError::Icc`] if the profile is invalid
+    /// or doesn't match the image colors and an [`ImageError::UnsupportedFormat`]
+    /// for 16-bit images.
+    pub fn convert_icc(self, profile: &[u8]) -> Result<Self, ImageError> {
+        const GRAY: &[u8] = b"GRAY";
+        const RGB: &[u8] = b"RGB ";
+
+        let space = profile.get(16..20).ok_or(ImageError::Icc)?;
+        let input = Profile::new_from_slice(profile, false).ok_or(ImageError::Icc)?;
+        if input.is_sRGB() {
+            return Ok(self);
+        }
+
+        // Gray images need a gray profile, other images need an RGB one
+        let output = Profile::new_sRGB();
+        let transform = |expected, from, to| {
+            if space != expected {
+                return Err(ImageError::Icc);
+            }
+
+            Transform::new_to(&input, &output, from, to, Intent::default()).ok_or(ImageError::Icc)
+        };
+
+        let im = match self {
+            Self::Gray(im) => {
+                let (w, h) = im.dimensions();
+                let mut rgb = RgbImage::new(w, h);
+                transform(GRAY, DataType::Gray8, DataType::RGB8)?.convert(&im, &mut rgb);
+                Self::Rgb(rgb)
+            }
+            Self::GrayAlpha(im) => {
+                let (w, h) = im.dimensions();
+                let mut rgba = RgbaImage::new(w, h);
+                transform(GRAY, DataType::GrayA8, DataType::RGBA8)?.convert(&im, &mut rgba);
+                Self::Rgba(rgba)
+            }
+            Self::Rgb(mut im) => {
+                transform(RGB, DataType::RGB8, DataType::RGB8)?.apply(&mut im);
+                Self::Rgb(im)
+            }
+            Self::Rgba(mut im) => {
+                transform(RGB, DataType::RGBA8, DataType::RGBA8)?.apply(&mut im);
+                Self::Rgba(im)
+            }
+            // There are no 16-bit transforms
+            Self::Gray16(_) => return Err(ImageError::UnsupportedFormat),
+            Self::Indexed {
+                mut palette,
+                indices,
+            } => {
+                transform(RGB, DataType::RGBA8, DataType::RGBA8)?.apply(palette.as_flattened_mut());
+                Self::Indexed { palette, indices }
+            }
+        };
+
+        Ok(im)
+    }
+}
+
+/// Reads the ICC profile embedded in a PNG or JPEG file.
+pub(crate) fn embedded(bytes: &[u8]) -> Option<Vec<u8>> {
+    const PNG: &[u8] = b"\x89PNG";
+    const ICC: &[u8] = b"ICC_PROFILE\0";
+
+    if bytes.starts_with(PNG) {
+        let reader = png::Decoder::new(bytes).read_info().ok()?;
+        return reader.info().icc_profile.as_deref().map(<[u8]>::to_vec);
+    }
+
+    // JPEG splits profiles into numbered APP2 chunks
+    let mut chunks: Vec<_> = jpeg_segments(bytes)
+        .filter(|&(marker, _)| marker == 0xE2)
+        .filter_map(|(_, segment)| match segment.strip_prefix(ICC)? {
+            [seq, _, chunk @ ..] => Some((*seq, chunk)),
+            _ => None,
+        })
+        .collect();
+
+    if chunks.is_empty() {
+        return None;
+    }
+
+    chunks.sort_by_key(|&(seq, _)| seq);
+    Some(
+        chunks
+            .into_iter()
+            .flat_map(|(_, chunk)| chunk)
+            .copied()
+            .collect(),
+    )
+}
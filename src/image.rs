@@ -1,13 +1,28 @@
 use {
-    crate::Color,
-    image::{DynamicImage, GrayAlphaImage, GrayImage, RgbImage, RgbaImage},
+    crate::{Color, Frame, Indexed},
+    image::{DynamicImage, GrayAlphaImage, GrayImage, ImageBuffer, Luma, RgbImage, RgbaImage},
+    std::{io::Read, ops::Range},
 };
 
+/// A 16-bit grayscale image, like a heightmap.
+pub type Gray16Image = ImageBuffer<Luma<u16>, Vec<u16>>;
+
 pub enum Image {
     Gray(GrayImage),
+
+    /// A 16-bit grayscale image, its values are sampled at full precision.
+    Gray16(Gray16Image),
     GrayAlpha(GrayAlphaImage),
     Rgb(RgbImage),
     Rgba(RgbaImage),
+
+    /// A paletted image, colors are looked up as the image is sampled.
+    ///
+    /// Indices out of the palette are transparent.
+    Indexed {
+        palette: Vec<[u8; 4]>,
+        indices: GrayImage,
+    },
 }
 
 impl Image {
@@ -18,6 +33,7 @@ impl Image {
     pub fn from_dynamic(im: DynamicImage) -> Result<Self, Error> {
         match im {
             DynamicImage::ImageLuma8(im) => Ok(im.into()),
+            DynamicImage::ImageLuma16(im) => Ok(im.into()),
             DynamicImage::ImageLumaA8(im) => Ok(im.into()),
             DynamicImage::ImageRgb8(im) => Ok(im.into()),
             DynamicImage::ImageRgba8(im) => Ok(im.into()),
@@ -25,12 +41,224 @@ impl Image {
         }
     }
 
+    /// Decodes an image with the most fitting loader of the crate.
+    ///
+    /// Paletted PNGs, and GIFs with the `gif` feature, keep their indices, 16-bit grayscale
+    /// images keep their precision and other formats are converted to RGBA. With the `icc`
+    /// feature, colors are converted to sRGB with the embedded ICC profile,
+    /// except for 16-bit grayscale images, which usually hold heights rather than colors.
+    ///
+    /// # Errors
+    /// Returns an [`Error`] if the image cannot be decoded or the profile cannot be applied.
+    pub fn from_memory(bytes: &[u8]) -> Result<Self, Error> {
+        let im = Self::decode(bytes)?;
+
+        #[cfg(feature = "icc")]
+        if let Some(profile) = crate::icc::embedded(bytes) {
+            if !matches!(im, Self::Gray16(_)) {
+                return im.convert_icc(&profile);
+            }
+        }
+
+        Ok(im)
+    }
+
+    /// Decodes an image without color management.
+    fn decode(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.starts_with(b"\x89PNG") {
+            match Self::from_indexed_png(bytes) {
+                Err(Error::UnsupportedFormat) => {}
+                res => return res,
+            }
+        }
+
+        #[cfg(feature = "gif")]
+        if bytes.starts_with(b"GIF8") {
+            return Self::from_indexed_gif(bytes);
+        }
+
+        let im = image::load_from_memory(bytes).map_err(Error::Decoding)?;
+        match im {
+            DynamicImage::ImageLuma8(_)
+            | DynamicImage::ImageLuma16(_)
+            | DynamicImage::ImageLumaA8(_)
+            | DynamicImage::ImageRgb8(_)
+            | DynamicImage::ImageRgba8(_) => Self::from_dynamic(im),
+            im => Ok(im.into_rgba8().into()),
+        }
+    }
+
+    /// Decodes a paletted PNG keeping its indices, without expanding it to RGBA.
+    ///
+    /// # Errors
+    /// Returns an [`Error::Png`] if decoding fails or
+    /// an [`Error::UnsupportedFormat`] if the PNG isn't paletted.
+    pub fn from_indexed_png<R>(r: R) -> Result<Self, Error>
+    where
+        R: Read,
+    {
+        use png::{BitDepth, ColorType, Decoder, Transformations};
+
+        let mut decoder = Decoder::new(r);
+        decoder.set_transformations(Transformations::IDENTITY);
+        let mut reader = decoder.read_info().map_err(Error::Png)?;
+        let info = reader.info();
+        if info.color_type != ColorType::Indexed {
+            return Err(Error::UnsupportedFormat);
+        }
+
+        let (width, height) = (info.width, info.height);
+        let bits = match info.bit_depth {
+            BitDepth::One => 1,
+            BitDepth::Two => 2,
+            BitDepth::Four => 4,
+            BitDepth::Eight => 8,
+            BitDepth::Sixteen => return Err(Error::UnsupportedFormat),
+        };
+
+        let alpha = info.trns.as_deref().unwrap_or_default();
+        let palette = info
+            .palette
+            .as_deref()
+            .ok_or(Error::UnsupportedFormat)?
+            .chunks_exact(3)
+            .enumerate()
+            .map(|(i, rgb)| {
+                [
+                    rgb[0],
+                    rgb[1],
+                    rgb[2],
+                    alpha.get(i).copied().unwrap_or(u8::MAX),
+                ]
+            })
+            .collect();
+
+        let mut buf = vec![0; reader.output_buffer_size()];
+        let frame = reader.next_frame(&mut buf).map_err(Error::Png)?;
+
+        // Rows of low bit depths pack several indices in a byte, the first one in high bits
+        let mask = ((1_u16 << bits) - 1) as u8;
+        let mut indices = Vec::with_capacity(width as usize * height as usize);
+        for row in buf.chunks(frame.line_size).take(height as usize) {
+            indices.extend((0..width as usize).map(|x| {
+                let bit = x * bits;
+                let shift = 8 - bits - bit % 8;
+                row[bit / 8] >> shift & mask
+            }));
+        }
+
+        let indices = GrayImage::from_raw(width, height, indices).ok_or(Error::InvalidSize)?;
+        Ok(Self::Indexed { palette, indices })
+    }
+
+    /// Decodes the first frame of a GIF keeping its indices, without expanding it to RGBA.
+    ///
+    /// The image has the size of the GIF screen. Pixels the frame doesn't cover are
+    /// transparent, unless the palette is full and has no transparent color.
+    ///
+    /// # Errors
+    /// Returns an [`Error::Gif`] if decoding fails or
+    /// an [`Error::UnsupportedFormat`] if the GIF has no frames or no palette.
+    #[cfg(feature = "gif")]
+    pub fn from_indexed_gif<R>(r: R) -> Result<Self, Error>
+    where
+        R: Read,
+    {
+        use gif::{ColorOutput, DecodeOptions};
+
+        let mut options = DecodeOptions::new();
+        options.set_color_output(ColorOutput::Indexed);
+        let mut decoder = options.read_info(r).map_err(Error::Gif)?;
+        let (width, height) = (decoder.width() as u32, decoder.height() as u32);
+        let global = decoder.global_palette().map(<[u8]>::to_vec);
+        let frame = decoder
+            .read_next_frame()
+            .map_err(Error::Gif)?
+            .ok_or(Error::UnsupportedFormat)?;
+
+        let palette: Vec<_> = frame
+            .palette
+            .as_deref()
+            .or(global.as_deref())
+            .ok_or(Error::UnsupportedFormat)?
+            .chunks_exact(3)
+            .enumerate()
+            .map(|(i, rgb)| {
+                let a = if frame.transparent == Some(i as u8) {
+                    0
+                } else {
+                    u8::MAX
+                };
+
+                [rgb[0], rgb[1], rgb[2], a]
+            })
+            .collect();
+
+        // Indices out of the palette are transparent too
+        let fill = frame
+            .transparent
+            .unwrap_or(palette.len().min(u8::MAX as usize) as u8);
+
+        let mut indices = GrayImage::from_pixel(width, height, Luma([fill]));
+        let (left, top) = (frame.left as u32, frame.top as u32);
+        let rows = frame.buffer.chunks(frame.width.max(1) as usize);
+        for (y, row) in (top..height).zip(rows) {
+            for (x, &idx) in (left..width).zip(row) {
+                indices.put_pixel(x, y, Luma([idx]));
+            }
+        }
+
+        Ok(Self::Indexed { palette, indices })
+    }
+
+    /// Creates an image from bytes of a canvas `ImageData`,
+    /// which are RGBA colors row by row.
+    ///
+    /// # Errors
+    /// Returns an [`Error::InvalidSize`] if the number of bytes isn't a multiple of the row size.
+    pub fn from_image_data(data: Vec<u8>, width: u32) -> Result<Self, Error> {
+        let row = width as usize * 4;
+        if row == 0 || !data.len().is_multiple_of(row) {
+            return Err(Error::InvalidSize);
+        }
+
+        let height = (data.len() / row) as u32;
+        RgbaImage::from_raw(width, height, data)
+            .map(Self::Rgba)
+            .ok_or(Error::InvalidSize)
+    }
+
+    /// Converts the image into bytes of a canvas `ImageData`,
+    /// which are RGBA colors row by row.
+    ///
+    /// The output of [`make`](crate::make) already has this layout in [`Frame::data`].
+    pub fn into_image_data(self) -> Vec<u8> {
+        let im = match self {
+            Self::Gray(im) => DynamicImage::from(im),
+            Self::Gray16(im) => DynamicImage::from(im),
+            Self::GrayAlpha(im) => DynamicImage::from(im),
+            Self::Rgb(im) => DynamicImage::from(im),
+            Self::Rgba(im) => return im.into_raw(),
+            Self::Indexed { palette, indices } => {
+                return indices
+                    .into_raw()
+                    .into_iter()
+                    .flat_map(|i| palette.get(i as usize).copied().unwrap_or_default())
+                    .collect();
+            }
+        };
+
+        im.into_rgba8().into_raw()
+    }
+
     pub fn size(&self) -> (u32, u32) {
         match self {
             Self::Gray(im) => (im.width(), im.height()),
+            Self::Gray16(im) => (im.width(), im.height()),
             Self::GrayAlpha(im) => (im.width(), im.height()),
             Self::Rgb(im) => (im.width(), im.height()),
             Self::Rgba(im) => (im.width(), im.height()),
+            Self::Indexed { indices, .. } => (indices.width(), indices.height()),
         }
     }
 
@@ -39,13 +267,72 @@ impl Image {
 
         let rgba = match self {
             Self::Gray(im) => im.get_pixel_checked(x, y).map(Pixel::to_rgba),
+            Self::Gray16(im) => return im.get_pixel_checked(x, y).map(|&Luma([v])| gray16(v)),
             Self::GrayAlpha(im) => im.get_pixel_checked(x, y).map(Pixel::to_rgba),
             Self::Rgb(im) => im.get_pixel_checked(x, y).map(Pixel::to_rgba),
             Self::Rgba(im) => im.get_pixel_checked(x, y).map(Pixel::to_rgba),
+            Self::Indexed { palette, indices } => indices
+                .get_pixel_checked(x, y)
+                .map(|&Luma([i])| Rgba(palette.get(i as usize).copied().unwrap_or_default())),
         };
 
         rgba.map(|Rgba(color)| Color::from_byte_array(color))
     }
+
+    /// Reads colors of the row `y` in the range `xs` into `out`.
+    ///
+    /// # Panics
+    /// Panics if the range is out of the image.
+    pub(crate) fn row(&self, y: u32, xs: Range<u32>, out: &mut [Color]) {
+        use image::{Pixel, Rgba};
+
+        fn read<P>(im: &image::ImageBuffer<P, Vec<u8>>, y: u32, xs: Range<u32>, out: &mut [Color])
+        where
+            P: Pixel<Subpixel = u8>,
+        {
+            for (x, col) in xs.zip(out) {
+                let Rgba(rgba) = im.get_pixel(x, y).to_rgba();
+                *col = Color::from_byte_array(rgba);
+            }
+        }
+
+        match self {
+            Self::Gray(im) => read(im, y, xs, out),
+            Self::Gray16(im) => {
+                for (x, col) in xs.zip(out) {
+                    let Luma([v]) = *im.get_pixel(x, y);
+                    *col = gray16(v);
+                }
+            }
+            Self::GrayAlpha(im) => read(im, y, xs, out),
+            Self::Rgb(im) => read(im, y, xs, out),
+            Self::Rgba(im) => read(im, y, xs, out),
+            Self::Indexed { palette, indices } => {
+                for (x, col) in xs.zip(out) {
+                    let [i] = indices.get_pixel(x, y).0;
+                    let rgba = palette.get(i as usize).copied().unwrap_or_default();
+                    *col = Color::from_byte_array(rgba);
+                }
+            }
+        }
+    }
+}
+
+/// Converts a 16-bit gray value to an opaque color without rounding to 8 bits.
+fn gray16(v: u16) -> Color {
+    let v = v as f32 / u16::MAX as f32;
+    Color {
+        r: v,
+        g: v,
+        b: v,
+        a: 1.,
+    }
+}
+
+impl From<Gray16Image> for Image {
+    fn from(v: Gray16Image) -> Self {
+        Self::Gray16(v)
+    }
 }
 
 impl From<GrayImage> for Image {
@@ -72,6 +359,23 @@ impl From<RgbaImage> for Image {
     }
 }
 
+impl From<Indexed> for Image {
+    fn from(v: Indexed) -> Self {
+        let (width, height) = v.size();
+        let (palette, indices) = v.into_parts();
+        Self::Indexed {
+            palette,
+            indices: GrayImage::from_raw(width, height, indices).expect("the buffer fits"),
+        }
+    }
+}
+
+impl From<Frame> for Image {
+    fn from(v: Frame) -> Self {
+        Self::Rgba(v.into())
+    }
+}
+
 impl TryFrom<DynamicImage> for Image {
     type Error = Error;
 
@@ -80,7 +384,41 @@ impl TryFrom<DynamicImage> for Image {
     }
 }
 
+/// Iterates over markers and payloads of the JPEG segments before the image data.
+///
+/// Yields nothing if the bytes aren't a JPEG file and stops at the first malformed segment.
+pub fn jpeg_segments(bytes: &[u8]) -> impl Iterator<Item = (u8, &[u8])> {
+    const START_OF_SCAN: u8 = 0xDA;
+
+    let mut rest = bytes.strip_prefix(&[0xFF, 0xD8]).unwrap_or_default();
+    std::iter::from_fn(move || {
+        let [0xFF, marker, hi, lo, data @ ..] = rest else {
+            return None;
+        };
+
+        let len = u16::from_be_bytes([*hi, *lo]) as usize;
+        let segment = data.get(..len.checked_sub(2)?)?;
+
+        // Image data starts after the start of scan
+        rest = if *marker == START_OF_SCAN {
+            &[]
+        } else {
+            &data[segment.len()..]
+        };
+
+        Some((*marker, segment))
+    })
+}
+
 #[derive(Debug)]
 pub enum Error {
     UnsupportedFormat,
+    InvalidSize,
+    Png(png::DecodingError),
+    #[cfg(feature = "gif")]
+    Gif(gif::DecodingError),
+    Decoding(image::ImageError),
+
+    /// An ICC profile is invalid or doesn't match the image colors.
+    Icc,
 }
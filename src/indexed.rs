@@ -0,0 +1,166 @@
+use {
+    crate::Frame,
+    std::{collections::HashMap, fs::File, io, io::BufWriter, path::Path},
+};
+
+/// An 8-bit paletted image.
+pub struct Indexed {
+    width: u32,
+    height: u32,
+    palette: Vec<[u8; 4]>,
+    indices: Vec<u8>,
+}
+
+impl Indexed {
+    /// The maximum number of colors in a palette.
+    pub const MAX_COLORS: usize = 256;
+
+    /// Quantizes a rendered image down to at most `max_colors` colors.
+    ///
+    /// If the image already has few enough distinct colors, the palette is exact.
+    /// Otherwise the colors are reduced with the NeuQuant algorithm.
+    ///
+    /// # Panics
+    /// Panics when `max_colors` is zero or greater than [`MAX_COLORS`](Self::MAX_COLORS).
+    pub fn quantize(im: &Frame, max_colors: usize) -> Self {
+        assert!(
+            (1..=Self::MAX_COLORS).contains(&max_colors),
+            "max colors must be in 1..=256",
+        );
+
+        let (width, height) = im.size();
+        match exact(im, max_colors) {
+            Some((palette, indices)) => Self {
+                width,
+                height,
+                palette,
+                indices,
+            },
+            None => {
+                use color_quant::NeuQuant;
+
+                const SAMPLE_FACTOR: i32 = 10;
+
+                let quant = NeuQuant::new(SAMPLE_FACTOR, max_colors, &im.data);
+                let palette = quant
+                    .color_map_rgba()
+                    .chunks_exact(4)
+                    .map(|c| [c[0], c[1], c[2], c[3]])
+                    .collect();
+
+                let indices = im.pixels().map(|p| quant.index_of(&p) as _).collect();
+
+                Self {
+                    width,
+                    height,
+                    palette,
+                    indices,
+                }
+            }
+        }
+    }
+
+    pub fn size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    pub fn palette(&self) -> &[[u8; 4]] {
+        &self.palette
+    }
+
+    pub fn indices(&self) -> &[u8] {
+        &self.indices
+    }
+
+    #[cfg(feature = "image")]
+    pub(crate) fn into_parts(self) -> (Vec<[u8; 4]>, Vec<u8>) {
+        (self.palette, self.indices)
+    }
+
+    /// Saves the image as an indexed PNG file.
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be created or the encoding fails.
+    pub fn save<P>(&self, path: P) -> io::Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        let file = File::create(path)?;
+        self.write_png(BufWriter::new(file))
+    }
+
+    /// Writes the image as an indexed PNG.
+    ///
+    /// The transparency chunk is emitted only when the palette has non-opaque colors.
+    ///
+    /// # Errors
+    /// Returns an error if the encoding fails.
+    pub fn write_png<W>(&self, w: W) -> io::Result<()>
+    where
+        W: io::Write,
+    {
+        use png::{BitDepth, ColorType, Encoder};
+
+        // Put transparent colors first so the tRNS chunk is as short as possible
+        let mut order: Vec<_> = (0..self.palette.len()).collect();
+        order.sort_by_key(|&i| self.palette[i][3] == u8::MAX);
+        let mut remap = vec![0; self.palette.len()];
+        for (new, &old) in order.iter().enumerate() {
+            remap[old] = new as u8;
+        }
+
+        let rgb: Vec<_> = order
+            .iter()
+            .flat_map(|&i| {
+                let [r, g, b, _] = self.palette[i];
+                [r, g, b]
+            })
+            .collect();
+
+        let trns: Vec<_> = order
+            .iter()
+            .map(|&i| self.palette[i][3])
+            .take_while(|&a| a != u8::MAX)
+            .collect();
+
+        let data: Vec<_> = self.indices.iter().map(|&i| remap[i as usize]).collect();
+
+        let mut encoder = Encoder::new(w, self.width, self.height);
+        encoder.set_color(ColorType::Indexed);
+        encoder.set_depth(BitDepth::Eight);
+        encoder.set_palette(rgb);
+        if !trns.is_empty() {
+            encoder.set_trns(trns);
+        }
+
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&data)?;
+        writer.finish()?;
+        Ok(())
+    }
+}
+
+fn exact(im: &Frame, max_colors: usize) -> Option<(Vec<[u8; 4]>, Vec<u8>)> {
+    let mut palette = vec![];
+    let mut lookup = HashMap::new();
+    let mut indices = Vec::with_capacity(im.data.len() / Frame::N_CHANNELS);
+    for p in im.pixels() {
+        let idx = match lookup.get(&p) {
+            Some(&idx) => idx,
+            None => {
+                if palette.len() == max_colors {
+                    return None;
+                }
+
+                let idx = palette.len() as u8;
+                palette.push(p);
+                lookup.insert(p, idx);
+                idx
+            }
+        };
+
+        indices.push(idx);
+    }
+
+    Some((palette, indices))
+}
@@ -1,10 +1,74 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+#[cfg(feature = "std")]
+mod animation;
+#[cfg(feature = "std")]
+pub mod annotate;
+#[cfg(feature = "std")]
+mod ansi;
 mod color;
+mod frame;
+#[cfg(feature = "std")]
+pub mod graph;
+mod hash;
+#[cfg(feature = "std")]
+mod histogram;
+#[cfg(feature = "icc")]
+mod icc;
+#[cfg(feature = "image")]
 mod image;
+#[cfg(feature = "std")]
+mod indexed;
+#[cfg(feature = "std")]
+pub mod lsystem;
 mod make;
+mod math;
+#[cfg(feature = "std")]
+pub mod metrics;
+#[cfg(feature = "image")]
+mod montage;
+#[cfg(feature = "tiff")]
+mod pages;
+#[cfg(feature = "std")]
+pub mod profile;
+#[cfg(feature = "std")]
+mod raw;
+mod sampling;
+#[cfg(feature = "std")]
+pub mod scene;
 pub mod source;
+#[cfg(feature = "std")]
+mod task;
+#[cfg(feature = "test-util")]
+pub mod testutil;
+
+pub use crate::{
+    color::{Color, LerpSpace},
+    frame::{FloatFrame, Frame},
+    make::{make, make_float, make_float_with, make_with, RenderOptions},
+    sampling::Sampling,
+};
 
+#[cfg(feature = "std")]
 pub use crate::{
-    color::Color,
-    image::{Error as ImageError, Image},
-    make::make,
+    animation::Animation,
+    ansi::{make_ansi, AnsiStyle},
+    histogram::{histogram, Histogram},
+    indexed::Indexed,
+    raw::RawFormat,
+    task::{make_async, RenderTask},
 };
+
+#[cfg(feature = "image")]
+pub use crate::{
+    image::{jpeg_segments, Error as ImageError, Gray16Image, Image},
+    montage::Montage,
+};
+
+#[cfg(feature = "parallel")]
+pub use crate::make::make_with_pool;
+
+#[cfg(feature = "tiff")]
+pub use crate::pages::TiffPages;
@@ -0,0 +1,227 @@
+//! L-systems and turtle graphics.
+//!
+//! An [`LSystem`] rewrites every symbol of a string by its rule at once, a number
+//! of times, starting from an axiom. The result is read by a [`Turtle`] as drawing
+//! commands and turned into a [`Path`], which can be stroked as any other path.
+//!
+//! ```text
+//! axiom F, rule F → F[+F]F[-F]F, angle 25.7°: a plant
+//! axiom F--F--F, rule F → F+F--F+F, angle 60°: the Koch snowflake
+//! ```
+
+use crate::source::{Path, Stroke};
+
+/// Rewriting rules applied to an axiom.
+pub struct LSystem {
+    axiom: String,
+    rules: Vec<(char, String)>,
+}
+
+impl LSystem {
+    /// The [`LSystem`] constructor.
+    pub fn new(axiom: &str) -> Self {
+        Self {
+            axiom: axiom.to_owned(),
+            rules: vec![],
+        }
+    }
+
+    /// Adds a rule replacing the symbol `from` by the string `to`.
+    ///
+    /// A later rule for the same symbol replaces the earlier one.
+    /// Symbols without rules are kept as they are.
+    pub fn rule(mut self, from: char, to: &str) -> Self {
+        self.rules.retain(|&(c, _)| c != from);
+        self.rules.push((from, to.to_owned()));
+        self
+    }
+
+    /// Rewrites the axiom the given number of `iterations`.
+    ///
+    /// The length usually grows exponentially with iterations,
+    /// so a handful of them is enough for most systems.
+    pub fn expand(&self, iterations: u32) -> String {
+        let mut current = self.axiom.clone();
+        for _ in 0..iterations {
+            let mut next = String::with_capacity(current.len() * 2);
+            for c in current.chars() {
+                match self.rules.iter().find(|&&(from, _)| from == c) {
+                    Some((_, to)) => next.push_str(to),
+                    None => next.push(c),
+                }
+            }
+
+            current = next;
+        }
+
+        current
+    }
+}
+
+/// Reads commands and draws lines.
+///
+/// Commands are:
+/// - drawing symbols, `F` and `G` by default, move forward by a step drawing a line,
+/// - moving symbols, `f` by default, move forward by a step without drawing,
+/// - `+` and `-` turn clockwise and counterclockwise on screen by the angle,
+/// - `|` turns around,
+/// - `[` and `]` save and restore the position and heading.
+///
+/// Other symbols are ignored.
+pub struct Turtle {
+    step: f32,
+    angle: f32,
+    heading: f32,
+    start: (f32, f32),
+    draws: String,
+    moves: String,
+}
+
+impl Turtle {
+    /// The [`Turtle`] constructor.
+    ///
+    /// The `step` is the length of a line in pixels and the `angle` is a turn in degrees.
+    pub fn new(step: f32, angle: f32) -> Self {
+        Self {
+            step,
+            angle: angle.to_radians(),
+            heading: -90_f32.to_radians(),
+            start: (0., 0.),
+            draws: "FG".to_owned(),
+            moves: "f".to_owned(),
+        }
+    }
+
+    /// Sets the start position. Defaults to the origin.
+    pub fn start(mut self, pos: (f32, f32)) -> Self {
+        self.start = pos;
+        self
+    }
+
+    /// Sets the start heading in degrees clockwise from the `x` axis.
+    /// Defaults to `-90`, pointing up.
+    pub fn heading(mut self, heading: f32) -> Self {
+        self.heading = heading.to_radians();
+        self
+    }
+
+    /// Sets the symbols that move forward drawing a line. Defaults to `FG`.
+    pub fn draws(mut self, symbols: &str) -> Self {
+        self.draws = symbols.to_owned();
+        self
+    }
+
+    /// Sets the symbols that move forward without drawing. Defaults to `f`.
+    pub fn moves(mut self, symbols: &str) -> Self {
+        self.moves = symbols.to_owned();
+        self
+    }
+
+    /// Draws the commands into a path.
+    pub fn path(&self, commands: &str) -> Path {
+        to_path(self.walk(commands), |p| p)
+    }
+
+    /// Draws the commands into a path scaled and centered to fit
+    /// the canvas of the `size` with the `margin` in pixels on every side.
+    ///
+    /// The step and the start position only matter in proportion to each other.
+    pub fn fit(&self, commands: &str, (w, h): (u32, u32), margin: f32) -> Path {
+        let lines = self.walk(commands);
+        if lines.is_empty() {
+            return Path::new();
+        }
+
+        let (mut x0, mut y0) = (f32::INFINITY, f32::INFINITY);
+        let (mut x1, mut y1) = (f32::NEG_INFINITY, f32::NEG_INFINITY);
+        for &(x, y) in lines.iter().flatten() {
+            (x0, y0) = (x0.min(x), y0.min(y));
+            (x1, y1) = (x1.max(x), y1.max(y));
+        }
+
+        let (aw, ah) = (w as f32 - margin * 2., h as f32 - margin * 2.);
+        let k = match (x1 - x0 > f32::EPSILON, y1 - y0 > f32::EPSILON) {
+            (true, true) => (aw / (x1 - x0)).min(ah / (y1 - y0)),
+            (true, false) => aw / (x1 - x0),
+            (false, true) => ah / (y1 - y0),
+            (false, false) => 1.,
+        };
+
+        let (cx, cy) = ((x0 + x1) * 0.5, (y0 + y1) * 0.5);
+        to_path(lines, |(x, y)| {
+            ((x - cx) * k + w as f32 * 0.5, (y - cy) * k + h as f32 * 0.5)
+        })
+    }
+
+    /// Draws the commands and strokes the path with the `width` and `paint`.
+    ///
+    /// # Panics
+    /// Panics when a `width` is less than or equal to zero.
+    pub fn stroke<P>(&self, commands: &str, width: f32, paint: P) -> Stroke<P> {
+        Stroke::new(&self.path(commands), width, paint)
+    }
+
+    /// Returns drawn polylines.
+    fn walk(&self, commands: &str) -> Vec<Vec<(f32, f32)>> {
+        let mut lines = vec![];
+        let mut line = vec![];
+        let mut stack = vec![];
+        let (mut pos, mut heading) = (self.start, self.heading);
+        for c in commands.chars() {
+            match c {
+                '+' => heading += self.angle,
+                '-' => heading -= self.angle,
+                '|' => heading += std::f32::consts::PI,
+                '[' => stack.push((pos, heading)),
+                ']' => {
+                    if let Some(state) = stack.pop() {
+                        (pos, heading) = state;
+                        flush(&mut lines, &mut line);
+                    }
+                }
+                c if self.draws.contains(c) => {
+                    if line.is_empty() {
+                        line.push(pos);
+                    }
+
+                    let (sin, cos) = heading.sin_cos();
+                    pos = (pos.0 + cos * self.step, pos.1 + sin * self.step);
+                    line.push(pos);
+                }
+                c if self.moves.contains(c) => {
+                    let (sin, cos) = heading.sin_cos();
+                    pos = (pos.0 + cos * self.step, pos.1 + sin * self.step);
+                    flush(&mut lines, &mut line);
+                }
+                _ => {}
+            }
+        }
+
+        flush(&mut lines, &mut line);
+        lines
+    }
+}
+
+/// Moves a drawn polyline into the list.
+fn flush(lines: &mut Vec<Vec<(f32, f32)>>, line: &mut Vec<(f32, f32)>) {
+    if line.len() > 1 {
+        lines.push(std::mem::take(line));
+    } else {
+        line.clear();
+    }
+}
+
+fn to_path<F>(lines: Vec<Vec<(f32, f32)>>, f: F) -> Path
+where
+    F: Fn((f32, f32)) -> (f32, f32),
+{
+    let mut path = Path::new();
+    for line in lines {
+        let mut points = line.into_iter().map(&f);
+        if let Some(first) = points.next() {
+            path = points.fold(path.move_to(first), Path::line_to);
+        }
+    }
+
+    path
+}
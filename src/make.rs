@@ -1,24 +1,330 @@
-use {crate::source::Source, image::RgbaImage};
+use {
+    crate::{
+        source::{Borders, Source},
+        Color, FloatFrame, Frame, Sampling,
+    },
+    alloc::{vec, vec::Vec},
+    core::sync::atomic::{AtomicBool, Ordering},
+};
 
-pub fn make<S>(source: S, (width, height): (u32, u32)) -> RgbaImage
+#[cfg(feature = "parallel")]
+use {
+    rayon::{ThreadPool, ThreadPoolBuilder},
+    std::sync::{Arc, Mutex},
+};
+
+/// Renders don't run on pools without the `parallel` feature.
+#[cfg(not(feature = "parallel"))]
+pub(crate) enum ThreadPool {}
+
+/// Options of a render.
+#[derive(Clone, Copy)]
+pub struct RenderOptions {
+    /// The seed mixed into seeds of stochastic sources, like noise, grain or scatter,
+    /// and of [jittered](Sampling::Jittered) sampling.
+    ///
+    /// The zero seed keeps sources as they are.
+    pub seed: u32,
+
+    /// Renders the same image for the same seed. Defaults to `true`.
+    ///
+    /// Every pixel only depends on its position and the seeds, so the output
+    /// doesn't depend on the number of threads either. When disabled, a random
+    /// value is mixed into the seed, so every render is different.
+    /// Without the `std` feature there's no random value to mix in.
+    pub deterministic: bool,
+
+    /// The number of threads to render with. Defaults to `0`,
+    /// which renders on the global thread pool.
+    ///
+    /// Other values render on a pool of that many threads, which bounds the CPU
+    /// usage of a render. The pool is built once per number of threads and reused
+    /// by later renders. Ignored without the `parallel` feature.
+    pub threads: usize,
+
+    /// The number of samples per pixel. Defaults to [`Sampling::Single`].
+    pub sampling: Sampling,
+
+    /// The color rendered pixels are composited over. Defaults to transparent.
+    ///
+    /// An opaque background gives an opaque image, as formats without alpha need.
+    pub background: Color,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            deterministic: true,
+            threads: 0,
+            sampling: Sampling::Single,
+            background: Color::default(),
+        }
+    }
+}
+
+impl RenderOptions {
+    /// Returns the seed this render uses.
+    fn render_seed(&self) -> u32 {
+        if self.deterministic {
+            return self.seed;
+        }
+
+        #[cfg(feature = "std")]
+        {
+            use std::{
+                collections::hash_map::RandomState,
+                hash::{BuildHasher, Hasher},
+            };
+
+            let mut hasher = RandomState::new().build_hasher();
+            hasher.write_u32(self.seed);
+            hasher.finish() as u32
+        }
+
+        #[cfg(not(feature = "std"))]
+        self.seed
+    }
+}
+
+pub fn make<S>(source: S, size: (u32, u32)) -> Frame
+where
+    S: Source + Sync,
+{
+    make_with(source, size, &RenderOptions::default())
+}
+
+/// Renders a source like [`make`] with the given options.
+///
+/// Shared sources behind a `&` reference, an [`Rc`](std::rc::Rc) or an
+/// [`Arc`](std::sync::Arc) aren't prepared or reseeded, see [`Source::reseed`].
+/// A `&mut` reference keeps the source and still lets the render do both.
+///
+/// # Panics
+/// Panics when the pool of [`threads`](RenderOptions::threads) can't be built,
+/// like on targets without threads.
+pub fn make_with<S>(source: S, size: (u32, u32), opts: &RenderOptions) -> Frame
+where
+    S: Source + Sync,
+{
+    render(source, size, opts, None, &AtomicBool::new(false))
+}
+
+/// Renders a source like [`make_with`] on the given thread pool.
+///
+/// The pool is used instead of the one of [`threads`](RenderOptions::threads),
+/// so services can share a bounded pool between their renders.
+#[cfg(feature = "parallel")]
+pub fn make_with_pool<S>(
+    source: S,
+    size: (u32, u32),
+    opts: &RenderOptions,
+    pool: &ThreadPool,
+) -> Frame
+where
+    S: Source + Sync,
+{
+    render(source, size, opts, Some(pool), &AtomicBool::new(false))
+}
+
+/// Renders a source into a [`FloatFrame`], keeping color components
+/// outside of the `0..=1` range.
+pub fn make_float<S>(source: S, size: (u32, u32)) -> FloatFrame
+where
+    S: Source + Sync,
+{
+    make_float_with(source, size, &RenderOptions::default())
+}
+
+/// Renders a source like [`make_float`] with the given options.
+///
+/// # Panics
+/// Panics when the pool of [`threads`](RenderOptions::threads) can't be built.
+pub fn make_float_with<S>(
+    source: S,
+    (width, height): (u32, u32),
+    opts: &RenderOptions,
+) -> FloatFrame
+where
+    S: Source + Sync,
+{
+    let data = render_data(
+        source,
+        (width, height),
+        opts,
+        None,
+        &AtomicBool::new(false),
+        |c| [c.r, c.g, c.b, c.a],
+    );
+
+    FloatFrame {
+        width,
+        height,
+        data,
+    }
+}
+
+/// Renders a source on the `pool`, or on the one of the options when it's `None`,
+/// skipping rows that start after the `cancel` flag is set.
+pub(crate) fn render<S>(
+    source: S,
+    (width, height): (u32, u32),
+    opts: &RenderOptions,
+    pool: Option<&ThreadPool>,
+    cancel: &AtomicBool,
+) -> Frame
+where
+    S: Source + Sync,
+{
+    let data = render_data(
+        source,
+        (width, height),
+        opts,
+        pool,
+        cancel,
+        Color::into_byte_array,
+    );
+
+    Frame {
+        width,
+        height,
+        data,
+    }
+}
+
+/// Renders a source into pixel components, converting colors with the `store` function.
+fn render_data<S, T>(
+    mut source: S,
+    (width, height): (u32, u32),
+    opts: &RenderOptions,
+    pool: Option<&ThreadPool>,
+    cancel: &AtomicBool,
+    store: fn(Color) -> [T; 4],
+) -> Vec<T>
 where
     S: Source + Sync,
+    T: Copy + Default + Send,
 {
-    use rayon::prelude::*;
+    const N_CHANNELS: usize = Frame::N_CHANNELS;
+
+    let mut data = vec![T::default(); width as usize * height as usize * N_CHANNELS];
+    if width == 0 || height == 0 {
+        return data;
+    }
+
+    let background = opts.background;
+    if background.is_visible() {
+        let components = store(background);
+        for chunk in data.chunks_mut(N_CHANNELS) {
+            chunk.copy_from_slice(&components);
+        }
+    }
+
+    let seed = opts.render_seed();
+    if seed != 0 {
+        source.reseed(seed);
+    }
+
+    // Pixels outside of the source borders are transparent and only show the background,
+    // so only pixels that cover them are sampled
+    let factor = opts.sampling.factor() as i32;
+    let mut area = Borders {
+        w: (0, width as i32 - 1),
+        h: (0, height as i32 - 1),
+    };
+
+    if let Some(borders) = source.borders() {
+        area = Borders {
+            w: (
+                area.w.0.max(borders.w.0.div_euclid(factor)),
+                area.w.1.min(borders.w.1.div_euclid(factor)),
+            ),
+            h: (
+                area.h.0.max(borders.h.0.div_euclid(factor)),
+                area.h.1.min(borders.h.1.div_euclid(factor)),
+            ),
+        };
+
+        if area.w.0 > area.w.1 || area.h.0 > area.h.1 {
+            return data;
+        }
+    }
+
+    source.prepare(Borders {
+        w: (area.w.0 * factor, (area.w.1 + 1) * factor - 1),
+        h: (area.h.0 * factor, (area.h.1 + 1) * factor - 1),
+    });
+
+    // Render whole rows, so sources can sample them in one call
+    let render_row = |(y, row): (usize, &mut [T])| {
+        let y = y as i32;
+        if y < area.h.0 || y > area.h.1 || cancel.load(Ordering::Relaxed) {
+            return;
+        }
+
+        let xs = area.w.0..area.w.1 + 1;
+        let row = &mut row[xs.start as usize * N_CHANNELS..xs.end as usize * N_CHANNELS];
+        let mut colors = vec![Color::default(); xs.len()];
+        opts.sampling.row(&source, y, xs, &mut colors, seed);
+        for (chunk, color) in row.chunks_mut(N_CHANNELS).zip(colors) {
+            let color = if background.is_visible() {
+                background.overlay(color)
+            } else {
+                color
+            };
+
+            chunk.copy_from_slice(&store(color));
+        }
+    };
+
+    let rows = &mut data;
+
+    #[cfg(feature = "parallel")]
+    {
+        use rayon::prelude::*;
+
+        let mut render = || {
+            rows.par_chunks_mut(width as usize * N_CHANNELS)
+                .enumerate()
+                .for_each(render_row);
+        };
+
+        match (pool, opts.threads) {
+            (Some(pool), _) => pool.install(render),
+            (None, 0) => render(),
+            (None, n) => thread_pool(n).install(render),
+        }
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    {
+        _ = pool;
+        rows.chunks_mut(width as usize * N_CHANNELS)
+            .enumerate()
+            .for_each(render_row);
+    }
+
+    data
+}
 
-    const N_CHANNELS: usize = 4;
+/// Returns the pool of `threads` threads, built on first use.
+///
+/// # Panics
+/// Panics when the pool can't be built.
+#[cfg(feature = "parallel")]
+fn thread_pool(threads: usize) -> Arc<ThreadPool> {
+    static POOLS: Mutex<Vec<(usize, Arc<ThreadPool>)>> = Mutex::new(Vec::new());
 
-    let mut buf = vec![0; width as usize * height as usize * N_CHANNELS];
+    let mut pools = POOLS.lock().expect("lock");
+    if let Some((_, pool)) = pools.iter().find(|(n, _)| *n == threads) {
+        return Arc::clone(pool);
+    }
 
-    (0..)
-        .zip(buf.chunks_mut(N_CHANNELS))
-        .par_bridge()
-        .for_each(|(n, chunk)| {
-            let x = n % width;
-            let y = n / height;
-            let color = source.source((x as _, y as _)).into_byte_array();
-            chunk.copy_from_slice(&color);
-        });
+    let pool = match ThreadPoolBuilder::new().num_threads(threads).build() {
+        Ok(pool) => Arc::new(pool),
+        Err(err) => panic!("failed to build a pool of {threads} threads: {err}"),
+    };
 
-    RgbaImage::from_raw(width, height, buf).expect("the buffer fits")
+    pools.push((threads, Arc::clone(&pool)));
+    pool
 }
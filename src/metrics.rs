@@ -0,0 +1,188 @@
+#[cfg(feature = "image")]
+use crate::Image;
+use crate::{
+    source::{Borders, Source},
+    Color,
+};
+
+/// Computes the peak signal-to-noise ratio between two images in decibels.
+///
+/// Returns [`f32::INFINITY`] for identical images.
+///
+/// # Panics
+/// Panics when the images have different sizes.
+#[cfg(feature = "image")]
+pub fn psnr(a: &Image, b: &Image) -> f32 {
+    psnr_region(a, b, image_region(a, b))
+}
+
+/// Computes the peak signal-to-noise ratio between two sources over a region.
+pub fn psnr_region<A, B>(a: A, b: B, region: Borders) -> f32
+where
+    A: Source,
+    B: Source,
+{
+    let mut sum = 0.;
+    let mut n = 0;
+    for pos in region.positions() {
+        let a = a.source(pos);
+        let b = b.source(pos);
+        for (a, b) in [(a.r, b.r), (a.g, b.g), (a.b, b.b), (a.a, b.a)] {
+            let d = (a - b) as f64;
+            sum += d * d;
+        }
+
+        n += 4;
+    }
+
+    if n == 0 || sum == 0. {
+        return f32::INFINITY;
+    }
+
+    let mse = sum / n as f64;
+    (-10. * mse.log10()) as f32
+}
+
+/// Computes the mean structural similarity index between two images.
+///
+/// The result is in range `-1..=1`, where `1` means identical images.
+///
+/// # Panics
+/// Panics when the images have different sizes.
+#[cfg(feature = "image")]
+pub fn ssim(a: &Image, b: &Image) -> f32 {
+    ssim_region(a, b, image_region(a, b))
+}
+
+/// Computes the mean structural similarity index between two sources over a region.
+///
+/// The index is computed on luminance of colors composited over black,
+/// using 8×8 windows with a stride of 4 pixels.
+pub fn ssim_region<A, B>(a: A, b: B, region: Borders) -> f32
+where
+    A: Source,
+    B: Source,
+{
+    const WINDOW: usize = 8;
+    const STRIDE: usize = 4;
+    const C1: f64 = 0.01 * 0.01;
+    const C2: f64 = 0.03 * 0.03;
+
+    let luma = |col: Color| (col.luminance() * col.a) as f64;
+    let la: Vec<_> = region.positions().map(|pos| luma(a.source(pos))).collect();
+    let lb: Vec<_> = region.positions().map(|pos| luma(b.source(pos))).collect();
+
+    let (w, h) = region.size();
+    let (w, h) = (w as usize, h as usize);
+    let (ww, wh) = (WINDOW.min(w), WINDOW.min(h));
+    if ww == 0 || wh == 0 {
+        return 1.;
+    }
+
+    let mut total = 0.;
+    let mut windows = 0;
+    for y in (0..=h - wh).step_by(STRIDE) {
+        for x in (0..=w - ww).step_by(STRIDE) {
+            let idx = || (y..y + wh).flat_map(|y| (x..x + ww).map(move |x| y * w + x));
+            let n = (ww * wh) as f64;
+            let ma = idx().map(|i| la[i]).sum::<f64>() / n;
+            let mb = idx().map(|i| lb[i]).sum::<f64>() / n;
+            let (mut va, mut vb, mut cov) = (0., 0., 0.);
+            for i in idx() {
+                let da = la[i] - ma;
+                let db = lb[i] - mb;
+                va += da * da;
+                vb += db * db;
+                cov += da * db;
+            }
+
+            va /= n;
+            vb /= n;
+            cov /= n;
+
+            total += ((2. * ma * mb + C1) * (2. * cov + C2))
+                / ((ma * ma + mb * mb + C1) * (va + vb + C2));
+
+            windows += 1;
+        }
+    }
+
+    (total / windows as f64) as f32
+}
+
+/// Color differences across one axis of a tile.
+#[derive(Clone, Copy, Debug)]
+pub struct Seam {
+    /// The mean difference between the last and the first lines,
+    /// which meet where copies of the tile touch.
+    pub edge: f32,
+
+    /// The mean difference between neighboring lines inside the tile.
+    pub inner: f32,
+}
+
+impl Seam {
+    /// Returns how much stronger the seam is than the texture itself.
+    ///
+    /// Values near `1` or below mean the tile wraps smoothly,
+    /// larger values mean a visible seam.
+    pub fn ratio(self) -> f32 {
+        if self.edge <= f32::EPSILON {
+            0.
+        } else {
+            self.edge / self.inner
+        }
+    }
+}
+
+/// Measures how seamlessly a region of a source tiles,
+/// returning the seams between columns and between rows.
+///
+/// Differences are the mean absolute differences of color channels.
+pub fn seams<S>(source: S, region: Borders) -> (Seam, Seam)
+where
+    S: Source,
+{
+    let diff = |a: Color, b: Color| {
+        ((a.r - b.r).abs() + (a.g - b.g).abs() + (a.b - b.b).abs() + (a.a - b.a).abs()) / 4.
+    };
+
+    let mean = |pairs: &mut dyn Iterator<Item = ((i32, i32), (i32, i32))>| {
+        let (sum, n) = pairs.fold((0., 0), |(sum, n), (a, b)| {
+            (sum + diff(source.source(a), source.source(b)) as f64, n + 1)
+        });
+
+        if n == 0 {
+            0.
+        } else {
+            (sum / n as f64) as f32
+        }
+    };
+
+    let Borders {
+        w: (x0, x1),
+        h: (y0, y1),
+    } = region;
+
+    let columns = Seam {
+        edge: mean(&mut (y0..=y1).map(|y| ((x1, y), (x0, y)))),
+        inner: mean(&mut (y0..=y1).flat_map(|y| (x0..x1).map(move |x| ((x, y), (x + 1, y))))),
+    };
+
+    let rows = Seam {
+        edge: mean(&mut (x0..=x1).map(|x| ((x, y1), (x, y0)))),
+        inner: mean(&mut (y0..y1).flat_map(|y| (x0..=x1).map(move |x| ((x, y), (x, y + 1))))),
+    };
+
+    (columns, rows)
+}
+
+#[cfg(feature = "image")]
+fn image_region(a: &Image, b: &Image) -> Borders {
+    assert_eq!(a.size(), b.size(), "images must have the same size");
+    let (w, h) = a.size();
+    Borders {
+        w: (0, w as i32 - 1),
+        h: (0, h as i32 - 1),
+    }
+}
@@ -0,0 +1,119 @@
+use crate::{
+    source::{Align, Fit, FitMode, Framed, Grid, Source, Text, VStack},
+    Color, Image,
+};
+
+type Tile = Box<dyn Source + Send + Sync>;
+
+/// Builds a contact sheet from many images.
+///
+/// Images are fitted into cells of the same size, optionally framed and labeled,
+/// and arranged into a [`Grid`].
+pub struct Montage {
+    tiles: Vec<(Image, Option<String>)>,
+    cols: u32,
+    cell: (u32, u32),
+    gutter: u32,
+    mode: FitMode,
+    border: Option<(u32, Color)>,
+    label: (u32, Color),
+}
+
+impl Montage {
+    const LABEL_SPACING: u32 = 4;
+
+    /// The [`Montage`] constructor.
+    ///
+    /// Images are fitted with [`FitMode::Contain`] and labeled with white text by default.
+    ///
+    /// # Panics
+    /// Panics when `cols` is zero.
+    pub fn new(cols: u32, cell: (u32, u32)) -> Self {
+        assert!(cols > 0, "cols cannot be zero");
+        Self {
+            tiles: vec![],
+            cols,
+            cell,
+            gutter: 0,
+            mode: FitMode::Contain,
+            border: None,
+            label: (1, Color::from_u32(0xFFFFFFFF)),
+        }
+    }
+
+    /// Sets the space between cells.
+    pub fn gutter(mut self, gutter: u32) -> Self {
+        self.gutter = gutter;
+        self
+    }
+
+    /// Sets how images are fitted into cells.
+    pub fn fit(mut self, mode: FitMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Draws a frame of given thickness and color inside every cell.
+    pub fn border(mut self, thickness: u32, color: Color) -> Self {
+        self.border = Some((thickness, color));
+        self
+    }
+
+    /// Sets the label text scale and color.
+    pub fn label_style(mut self, scale: u32, color: Color) -> Self {
+        self.label = (scale.max(1), color);
+        self
+    }
+
+    pub fn push(mut self, image: Image) -> Self {
+        self.tiles.push((image, None));
+        self
+    }
+
+    /// Adds an image with a label under it.
+    pub fn push_labeled<L>(mut self, image: Image, label: L) -> Self
+    where
+        L: Into<String>,
+    {
+        self.tiles.push((image, Some(label.into())));
+        self
+    }
+
+    /// Composites the montage into a single source placed at the origin.
+    pub fn build(self) -> Grid<Tile> {
+        let (scale, color) = self.label;
+        let labeled = self.tiles.iter().any(|(_, label)| label.is_some());
+        let label_height = if labeled {
+            Self::LABEL_SPACING + Text::GLYPH_SIZE * scale
+        } else {
+            0
+        };
+
+        let (w, h) = self.cell;
+        let mut grid = Grid::new(self.cols, (w, h + label_height))
+            .gutter(self.gutter)
+            .fit(FitMode::Keep)
+            .align(Align::Center, Align::Start);
+
+        let tiles = self.tiles.into_iter().map(|(image, label)| {
+            let fit = Fit::new(image, self.cell, self.mode);
+            let tile: Tile = match self.border {
+                Some((thickness, color)) => Box::new(Framed::new(fit, thickness, color).inside()),
+                None => Box::new(fit),
+            };
+
+            match label {
+                Some(label) => Box::new(
+                    VStack::new(Self::LABEL_SPACING)
+                        .align(Align::Center)
+                        .push(tile)
+                        .push(Box::new(Text::new(&label, scale, color))),
+                ),
+                None => tile,
+            }
+        });
+
+        grid.extend(tiles);
+        grid
+    }
+}
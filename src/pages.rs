@@ -0,0 +1,148 @@
+use {
+    crate::Frame,
+    std::{
+        fs::File,
+        io::{self, BufWriter, Seek, Write},
+        path::Path,
+    },
+    tiff::{
+        encoder::{
+            colortype::RGBA8, compression::Lzw, DirectoryEncoder, TiffEncoder, TiffKindStandard,
+        },
+        tags::Tag,
+        TiffError, TiffResult,
+    },
+};
+
+// Tags the `tiff` crate doesn't name
+const PAGE_NAME: u16 = 285;
+const PAGE_NUMBER: u16 = 297;
+
+/// Stores frames as pages of a single TIFF file,
+/// like frames of an animation or taps of a graph.
+///
+/// Pages are compressed losslessly and can be of different sizes.
+#[derive(Default)]
+pub struct TiffPages {
+    pages: Vec<(Frame, Option<String>)>,
+}
+
+impl TiffPages {
+    /// The [`TiffPages`] constructor.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a page.
+    ///
+    /// # Panics
+    /// Panics when the frame is empty.
+    pub fn push(mut self, frame: Frame) -> Self {
+        assert!(frame.width > 0 && frame.height > 0, "frame cannot be empty");
+
+        self.pages.push((frame, None));
+        self
+    }
+
+    /// Adds a page with a name, which viewers may show as its title.
+    ///
+    /// # Panics
+    /// Panics when the frame is empty.
+    pub fn push_named<N>(mut self, frame: Frame, name: N) -> Self
+    where
+        N: Into<String>,
+    {
+        self = self.push(frame);
+        if let Some((_, page_name)) = self.pages.last_mut() {
+            *page_name = Some(name.into());
+        }
+
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.pages.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pages.is_empty()
+    }
+
+    /// Saves the pages as a TIFF file.
+    ///
+    /// # Errors
+    /// Returns an error if there are no pages or more than 65535 of them,
+    /// the file cannot be created or the encoding fails.
+    pub fn save<P>(&self, path: P) -> io::Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        let file = File::create(path)?;
+        let mut w = BufWriter::new(file);
+        self.write(&mut w)?;
+        w.flush()
+    }
+
+    /// Writes the pages as a TIFF, see [`save`](Self::save).
+    ///
+    /// # Errors
+    /// Returns an error if there are no pages or more than 65535 of them,
+    /// or the encoding fails.
+    pub fn write<W>(&self, w: W) -> io::Result<()>
+    where
+        W: Write + Seek,
+    {
+        if self.pages.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "a tiff without pages",
+            ));
+        }
+
+        // Page numbers are 16-bit
+        let Ok(total) = u16::try_from(self.pages.len()) else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "a tiff with more than 65535 pages",
+            ));
+        };
+
+        let mut encoder = TiffEncoder::new(w).map_err(into_io)?;
+        for (n, (frame, name)) in self.pages.iter().enumerate() {
+            let mut image = encoder
+                .new_image_with_compression::<RGBA8, _>(frame.width, frame.height, Lzw)
+                .map_err(into_io)?;
+
+            write_tags(image.encoder(), (n as u16, total), name.as_deref()).map_err(into_io)?;
+            image.write_data(&frame.data).map_err(into_io)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Marks the image as a page of many, with unassociated alpha.
+fn write_tags<W>(
+    dir: &mut DirectoryEncoder<W, TiffKindStandard>,
+    (n, total): (u16, u16),
+    name: Option<&str>,
+) -> TiffResult<()>
+where
+    W: Write + Seek,
+{
+    dir.write_tag(Tag::NewSubfileType, 2_u32)?;
+    dir.write_tag(Tag::ExtraSamples, 2_u16)?;
+    dir.write_tag(Tag::Unknown(PAGE_NUMBER), &[n, total][..])?;
+    if let Some(name) = name {
+        dir.write_tag(Tag::Unknown(PAGE_NAME), name)?;
+    }
+
+    Ok(())
+}
+
+fn into_io(err: TiffError) -> io::Error {
+    match err {
+        TiffError::IoError(err) => err,
+        err => io::Error::other(err),
+    }
+}
@@ -0,0 +1,232 @@
+//! Opt-in timing of sources.
+//!
+//! Wrap sources of interest in [`Timed`] sharing one [`Profile`], render as usual
+//! and print the [`Report`] to see where the render time goes. Nodes of a
+//! [`Graph`](crate::graph::Graph) are timed with
+//! [`make_profiled`](crate::graph::Graph::make_profiled) without wrapping.
+//!
+//! The time of a source includes the time of the sources it samples,
+//! the own time excludes the nested timed sources.
+
+use {
+    crate::{
+        source::{Borders, Source},
+        Color,
+    },
+    std::{
+        cell::RefCell,
+        cmp::Reverse,
+        fmt,
+        ops::Range,
+        sync::{
+            atomic::{AtomicU64, Ordering},
+            Arc, Mutex,
+        },
+        time::{Duration, Instant},
+    },
+};
+
+/// A collection of timed sources.
+#[derive(Clone, Default)]
+pub struct Profile {
+    entries: Arc<Mutex<Vec<Arc<Entry>>>>,
+}
+
+impl Profile {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the time spent in timed sources so far.
+    pub fn report(&self) -> Report {
+        let entries = self.entries.lock().expect("lock");
+        Report::new(entries.iter().map(|entry| entry.line()))
+    }
+
+    /// Resets the time of all timed sources.
+    pub fn reset(&self) {
+        for entry in &*self.entries.lock().expect("lock") {
+            entry.stats.reset();
+        }
+    }
+
+    fn entry(&self, name: &str) -> Arc<Entry> {
+        let entry = Arc::new(Entry {
+            name: name.to_owned(),
+            stats: Stats::default(),
+        });
+
+        self.entries.lock().expect("lock").push(Arc::clone(&entry));
+        entry
+    }
+}
+
+struct Entry {
+    name: String,
+    stats: Stats,
+}
+
+impl Entry {
+    fn line(&self) -> Line {
+        self.stats.line(&self.name)
+    }
+}
+
+/// Measures the time spent sampling a source.
+pub struct Timed<S> {
+    source: S,
+    entry: Arc<Entry>,
+}
+
+impl<S> Timed<S> {
+    /// The [`Timed`] constructor.
+    ///
+    /// The `name` identifies the source in the report of the `profile`.
+    pub fn new(source: S, name: &str, profile: &Profile) -> Self {
+        Self {
+            source,
+            entry: profile.entry(name),
+        }
+    }
+}
+
+impl<S> Source for Timed<S>
+where
+    S: Source,
+{
+    fn source(&self, pos: (i32, i32)) -> Color {
+        self.entry.stats.measure(1, || self.source.source(pos))
+    }
+
+    fn borders(&self) -> Option<Borders> {
+        self.source.borders()
+    }
+
+    fn prepare(&mut self, region: Borders) {
+        self.source.prepare(region);
+    }
+
+    fn reseed(&mut self, seed: u32) {
+        self.source.reseed(seed);
+    }
+
+    fn source_row(&self, y: i32, xs: Range<i32>, out: &mut [Color]) {
+        let n = xs.len() as u64;
+        self.entry
+            .stats
+            .measure(n, || self.source.source_row(y, xs, out));
+    }
+}
+
+thread_local! {
+    /// The time of nested measurements of every measurement in progress.
+    static NESTED: RefCell<Vec<Duration>> = const { RefCell::new(vec![]) };
+}
+
+/// Time counters of a source.
+#[derive(Default)]
+pub(crate) struct Stats {
+    samples: AtomicU64,
+    total: AtomicU64,
+    own: AtomicU64,
+}
+
+impl Stats {
+    /// Runs `f` sampling `n` pixels and adds its time.
+    pub(crate) fn measure<F, T>(&self, n: u64, f: F) -> T
+    where
+        F: FnOnce() -> T,
+    {
+        NESTED.with_borrow_mut(|nested| nested.push(Duration::ZERO));
+        let start = Instant::now();
+        let res = f();
+        let total = start.elapsed();
+        let nested = NESTED.with_borrow_mut(|nested| {
+            let own = nested.pop().unwrap_or_default();
+            if let Some(parent) = nested.last_mut() {
+                *parent += total;
+            }
+
+            own
+        });
+
+        let ns = |d: Duration| d.as_nanos() as u64;
+        self.samples.fetch_add(n, Ordering::Relaxed);
+        self.total.fetch_add(ns(total), Ordering::Relaxed);
+        self.own
+            .fetch_add(ns(total.saturating_sub(nested)), Ordering::Relaxed);
+
+        res
+    }
+
+    pub(crate) fn reset(&self) {
+        self.samples.store(0, Ordering::Relaxed);
+        self.total.store(0, Ordering::Relaxed);
+        self.own.store(0, Ordering::Relaxed);
+    }
+
+    pub(crate) fn line(&self, name: &str) -> Line {
+        let time = |v: &AtomicU64| Duration::from_nanos(v.load(Ordering::Relaxed));
+        Line {
+            name: name.to_owned(),
+            samples: self.samples.load(Ordering::Relaxed),
+            total: time(&self.total),
+            own: time(&self.own),
+        }
+    }
+}
+
+/// Time spent in timed sources, sorted by the own time.
+///
+/// Times are summed over all threads, so they can exceed the wall time of a render.
+pub struct Report {
+    lines: Vec<Line>,
+}
+
+impl Report {
+    pub(crate) fn new<I>(lines: I) -> Self
+    where
+        I: IntoIterator<Item = Line>,
+    {
+        let mut lines: Vec<_> = lines.into_iter().collect();
+        lines.sort_by_key(|line| Reverse(line.own));
+        Self { lines }
+    }
+
+    pub fn lines(&self) -> &[Line] {
+        &self.lines
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let width = self.lines.iter().map(|line| line.name.len()).max();
+        let width = width.unwrap_or_default().max(4);
+        writeln!(
+            f,
+            "{:width$}  {:>12}  {:>12}  {:>12}",
+            "name", "samples", "total", "own",
+        )?;
+
+        for line in &self.lines {
+            writeln!(
+                f,
+                "{:width$}  {:>12}  {:>12.3?}  {:>12.3?}",
+                line.name, line.samples, line.total, line.own,
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Time spent in a source.
+pub struct Line {
+    pub name: String,
+    /// The number of sampled pixels.
+    pub samples: u64,
+    /// The time including nested timed sources.
+    pub total: Duration,
+    /// The time excluding nested timed sources.
+    pub own: Duration,
+}
@@ -0,0 +1,183 @@
+use {
+    crate::{FloatFrame, Frame},
+    std::io::{self, Read, Write},
+};
+
+const MAGIC: &[u8; 4] = b"NIED";
+const VERSION: u8 = 1;
+
+/// The sample type of a raw dump.
+///
+/// A dump is a 16 bytes header followed by RGBA samples row by row. The header holds
+/// the `NIED` magic, the version `1`, the number of bytes per sample, two zero bytes
+/// and the width and height as little-endian `u32`. Samples are little-endian too,
+/// integer samples span their whole range.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RawFormat {
+    U8,
+    U16,
+    F32,
+}
+
+impl RawFormat {
+    /// Returns the number of bytes of a sample.
+    pub fn sample_size(self) -> usize {
+        match self {
+            Self::U8 => 1,
+            Self::U16 => 2,
+            Self::F32 => 4,
+        }
+    }
+
+    fn from_sample_size(size: u8) -> Option<Self> {
+        match size {
+            1 => Some(Self::U8),
+            2 => Some(Self::U16),
+            4 => Some(Self::F32),
+            _ => None,
+        }
+    }
+}
+
+impl Frame {
+    /// Writes the frame as a raw dump of the format.
+    ///
+    /// # Errors
+    /// Returns an error if writing fails.
+    pub fn dump_raw<W>(&self, w: W, format: RawFormat) -> io::Result<()>
+    where
+        W: Write,
+    {
+        match format {
+            RawFormat::U8 => dump(w, self.size(), format, &self.data),
+            _ => {
+                let samples = self.data.iter().map(|&v| v as f32 / 255.);
+                dump(w, self.size(), format, &encode(samples, format))
+            }
+        }
+    }
+
+    /// Reads a frame from a raw dump of any format, rounding samples to bytes.
+    ///
+    /// # Errors
+    /// Returns an error if reading fails or the dump is malformed.
+    pub fn load_raw<R>(r: R) -> io::Result<Self>
+    where
+        R: Read,
+    {
+        let (format, (width, height), bytes) = load(r)?;
+        let data = match format {
+            RawFormat::U8 => bytes,
+            _ => decode(&bytes, format)
+                .map(|v| (v * 255.).round() as u8)
+                .collect(),
+        };
+
+        Ok(Self {
+            width,
+            height,
+            data,
+        })
+    }
+}
+
+impl FloatFrame {
+    /// Writes the frame as a raw dump of the format.
+    ///
+    /// Integer formats clamp components to the `0..=1` range.
+    ///
+    /// # Errors
+    /// Returns an error if writing fails.
+    pub fn dump_raw<W>(&self, w: W, format: RawFormat) -> io::Result<()>
+    where
+        W: Write,
+    {
+        let samples = self.data.iter().copied();
+        dump(w, self.size(), format, &encode(samples, format))
+    }
+
+    /// Reads a frame from a raw dump of any format.
+    ///
+    /// # Errors
+    /// Returns an error if reading fails or the dump is malformed.
+    pub fn load_raw<R>(r: R) -> io::Result<Self>
+    where
+        R: Read,
+    {
+        let (format, (width, height), bytes) = load(r)?;
+        Ok(Self {
+            width,
+            height,
+            data: decode(&bytes, format).collect(),
+        })
+    }
+}
+
+fn dump<W>(mut w: W, (width, height): (u32, u32), format: RawFormat, bytes: &[u8]) -> io::Result<()>
+where
+    W: Write,
+{
+    let mut header = [0; 16];
+    header[..4].copy_from_slice(MAGIC);
+    header[4] = VERSION;
+    header[5] = format.sample_size() as u8;
+    header[8..12].copy_from_slice(&width.to_le_bytes());
+    header[12..].copy_from_slice(&height.to_le_bytes());
+    w.write_all(&header)?;
+    w.write_all(bytes)
+}
+
+fn load<R>(mut r: R) -> io::Result<(RawFormat, (u32, u32), Vec<u8>)>
+where
+    R: Read,
+{
+    let invalid = |msg| io::Error::new(io::ErrorKind::InvalidData, msg);
+
+    let mut header = [0; 16];
+    r.read_exact(&mut header)?;
+    if &header[..4] != MAGIC {
+        return Err(invalid("not a raw frame"));
+    }
+
+    if header[4] != VERSION {
+        return Err(invalid("unsupported raw frame version"));
+    }
+
+    let format =
+        RawFormat::from_sample_size(header[5]).ok_or_else(|| invalid("unknown sample format"))?;
+    let width = u32::from_le_bytes([header[8], header[9], header[10], header[11]]);
+    let height = u32::from_le_bytes([header[12], header[13], header[14], header[15]]);
+    let len = (width as u64) * (height as u64) * (Frame::N_CHANNELS * format.sample_size()) as u64;
+
+    // Read no more than the data, without trusting the size to allocate it upfront
+    let mut bytes = vec![];
+    r.take(len).read_to_end(&mut bytes)?;
+    if (bytes.len() as u64) < len {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+
+    Ok((format, (width, height), bytes))
+}
+
+fn encode<I>(samples: I, format: RawFormat) -> Vec<u8>
+where
+    I: Iterator<Item = f32>,
+{
+    match format {
+        RawFormat::U8 => samples.map(|v| (v * 255.).round() as u8).collect(),
+        RawFormat::U16 => samples
+            .flat_map(|v| ((v * 65535.).round() as u16).to_le_bytes())
+            .collect(),
+        RawFormat::F32 => samples.flat_map(f32::to_le_bytes).collect(),
+    }
+}
+
+fn decode(bytes: &[u8], format: RawFormat) -> impl Iterator<Item = f32> + '_ {
+    bytes
+        .chunks_exact(format.sample_size())
+        .map(move |b| match format {
+            RawFormat::U8 => b[0] as f32 / 255.,
+            RawFormat::U16 => u16::from_le_bytes([b[0], b[1]]) as f32 / 65535.,
+            RawFormat::F32 => f32::from_le_bytes([b[0], b[1], b[2], b[3]]),
+        })
+}
@@ -0,0 +1,208 @@
+use {
+    crate::{hash, source::Source, Color},
+    alloc::{vec, vec::Vec},
+    core::ops::Range,
+};
+
+/// How many samples of a source make an output pixel.
+///
+/// With supersampling the source is sampled at [`factor`](Self::factor) times
+/// the output resolution, so it must be built at that scale: a pixel at `(x, y)`
+/// averages samples in the block from `(x * factor, y * factor)` to the next pixel.
+/// Samples are averaged in linear light, weighted by their alpha.
+#[derive(Clone, Copy, Default)]
+pub enum Sampling {
+    /// One sample per pixel.
+    #[default]
+    Single,
+
+    /// A regular grid of `n × n` samples per pixel.
+    Grid(u32),
+
+    /// Four samples on a grid rotated by about 27°, in a `4 × 4` block per pixel.
+    ///
+    /// Near horizontal and vertical edges get as many gradation steps as
+    /// with a `4 × 4` grid, for the cost of a `2 × 2` one.
+    RotatedGrid,
+
+    /// Samples corners of each pixel first and only samples the `n × n` grid
+    /// in pixels whose corner colors differ by more than the `threshold`
+    /// in any channel.
+    ///
+    /// Gives the quality of a grid for a fraction of the cost on mostly flat images,
+    /// but may miss details smaller than a pixel that don't touch its corners.
+    Adaptive { n: u32, threshold: f32 },
+
+    /// A number of `samples` at seeded random positions in the `n × n` block of each pixel.
+    ///
+    /// Turns regular aliasing patterns into noise, which suits procedural sources
+    /// and stochastic effects. Positions depend on the pixel and the seed of the
+    /// render, see [`RenderOptions::seed`](crate::RenderOptions::seed), so renders
+    /// with the same seed are identical.
+    Jittered { n: u32, samples: u32 },
+}
+
+impl Sampling {
+    /// Returns how many times the source resolution is larger than the output one.
+    ///
+    /// # Panics
+    /// Panics when a grid size is zero.
+    pub fn factor(self) -> u32 {
+        match self {
+            Self::Single => 1,
+            Self::Grid(n) => {
+                assert!(n > 0, "grid size cannot be zero");
+                n
+            }
+            Self::RotatedGrid => 4,
+            Self::Adaptive { n, .. } => Self::Grid(n).factor(),
+            Self::Jittered { n, samples } => {
+                assert!(samples > 0, "the number of samples cannot be zero");
+                Self::Grid(n).factor()
+            }
+        }
+    }
+
+    /// Samples output pixels `xs` of the row `y` into `out`.
+    pub(crate) fn row<S>(self, source: &S, y: i32, xs: Range<i32>, out: &mut [Color], seed: u32)
+    where
+        S: Source + ?Sized,
+    {
+        match self {
+            Self::Single => source.source_row(y, xs, out),
+            Self::Grid(n) => {
+                let n = n as i32;
+                let mut sums = vec![Average::default(); out.len()];
+                let mut samples = vec![Color::default(); out.len() * n as usize];
+                for j in 0..n {
+                    source.source_row(y * n + j, xs.start * n..xs.end * n, &mut samples);
+                    for (sum, block) in sums.iter_mut().zip(samples.chunks(n as usize)) {
+                        for &col in block {
+                            sum.add(col);
+                        }
+                    }
+                }
+
+                for (col, sum) in out.iter_mut().zip(sums) {
+                    *col = sum.color();
+                }
+            }
+            Self::RotatedGrid => {
+                const POINTS: [(i32, i32); 4] = [(1, 0), (3, 1), (0, 2), (2, 3)];
+
+                for (x, col) in xs.clone().zip(out) {
+                    let mut sum = Average::default();
+                    for (i, j) in POINTS {
+                        sum.add(source.source((x * 4 + i, y * 4 + j)));
+                    }
+
+                    *col = sum.color();
+                }
+            }
+            Self::Adaptive { n, threshold } => {
+                let n = n as i32;
+                let corners = |y| -> Vec<_> {
+                    (xs.start..=xs.end)
+                        .map(|x| source.source((x * n, y * n)))
+                        .collect()
+                };
+
+                let top = corners(y);
+                let bottom = corners(y + 1);
+                for (k, (x, col)) in xs.clone().zip(out).enumerate() {
+                    let quad = [top[k], top[k + 1], bottom[k], bottom[k + 1]];
+                    let mut sum = Average::default();
+                    if spread(quad) <= threshold {
+                        for col in quad {
+                            sum.add(col);
+                        }
+                    } else {
+                        for j in 0..n {
+                            for i in 0..n {
+                                sum.add(source.source((x * n + i, y * n + j)));
+                            }
+                        }
+                    }
+
+                    *col = sum.color();
+                }
+            }
+            Self::Jittered { n, samples } => {
+                for (x, col) in xs.clone().zip(out) {
+                    let pixel = hash::hash(seed, (x, y));
+                    let mut sum = Average::default();
+                    for k in 0..samples as i32 {
+                        let i = hash::hash(pixel, (k, 0)) % n;
+                        let j = hash::hash(pixel, (k, 1)) % n;
+                        let (i, j) = (i as i32, j as i32);
+                        sum.add(source.source((x * n as i32 + i, y * n as i32 + j)));
+                    }
+
+                    *col = sum.color();
+                }
+            }
+        }
+    }
+}
+
+/// Returns the largest difference of a channel between the colors.
+fn spread(cols: [Color; 4]) -> f32 {
+    let range = |f: fn(Color) -> f32| {
+        let (min, max) = cols
+            .iter()
+            .map(|&col| f(col))
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), v| {
+                (min.min(v), max.max(v))
+            });
+
+        max - min
+    };
+
+    range(|c| c.r)
+        .max(range(|c| c.g))
+        .max(range(|c| c.b))
+        .max(range(|c| c.a))
+}
+
+/// A sum of samples in premultiplied linear light.
+#[derive(Clone, Copy, Default)]
+struct Average {
+    sum: Color,
+    n: u32,
+    first: Color,
+    uniform: bool,
+}
+
+impl Average {
+    fn add(&mut self, col: Color) {
+        let same = |a: Color, b: Color| a.r == b.r && a.g == b.g && a.b == b.b && a.a == b.a;
+        if self.n == 0 {
+            self.first = col;
+            self.uniform = true;
+        } else if self.uniform && !same(self.first, col) {
+            self.uniform = false;
+        }
+
+        let a = col.a;
+        let mut lin = col.to_linear() * a;
+        lin.a = a;
+        self.sum += lin;
+        self.n += 1;
+    }
+
+    fn color(self) -> Color {
+        // Flat areas are kept exact, without the round trip through linear light
+        if self.uniform {
+            return self.first;
+        }
+
+        if self.n == 0 || self.sum.a <= f32::EPSILON {
+            return Color::default();
+        }
+
+        let a = self.sum.a;
+        let mut col = self.sum * (1. / a);
+        col.a = a / self.n as f32;
+        col.to_srgb()
+    }
+}
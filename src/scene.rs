@@ -0,0 +1,700 @@
+//! A text format for render pipelines.
+//!
+//! A scene is a list of lines. `name = op [inputs...] [param=value...]` defines
+//! a node by applying an [`Op`] to nodes defined above, `size WxH` sets the canvas
+//! size for the nodes below and `output name` selects the rendered node,
+//! the last defined one by default. A word starting with `#` begins a comment,
+//! values with spaces are put in double quotes.
+//!
+//! ```text
+//! size 256x256
+//! clouds = noise scale=40 octaves=4
+//! soft = blur clouds radius=4
+//! tinted = duotone soft shadows=#102040 highlights=#f0e0c0
+//! ```
+//!
+//! Available ops are listed by [`ops`], each one describes its inputs and parameters.
+
+use {
+    crate::{
+        source::{
+            Blur, DiffMode, Expr, Fbm, Filter, GaussianBlur, GradientMap, Grain, Metric, Noise,
+            Offset, Perlin, Scale, Solarize, Source, Vignette, Worley, WorleyOutput,
+        },
+        Color, Frame,
+    },
+    std::{collections::HashMap, ops::RangeInclusive, sync::Arc},
+};
+
+/// A node of a scene, shared by the nodes using it.
+pub type Shared = Arc<dyn Source + Send + Sync>;
+
+/// An operation creating a source from inputs and parameters.
+pub struct Op {
+    pub name: &'static str,
+    pub summary: &'static str,
+
+    /// The allowed number of input nodes, [`usize::MAX`] means no limit.
+    pub inputs: RangeInclusive<usize>,
+    pub params: &'static [Param],
+    build: fn(&Args, Vec<Shared>) -> Result<Shared, SceneError>,
+}
+
+/// A parameter of an [`Op`].
+pub struct Param {
+    pub name: &'static str,
+    pub kind: Kind,
+
+    /// The default value in the scene syntax, empty for required parameters.
+    pub default: &'static str,
+    pub doc: &'static str,
+}
+
+/// A type of parameter values.
+#[derive(Clone, Copy, Debug)]
+pub enum Kind {
+    /// An integer.
+    Int,
+
+    /// A number.
+    Float,
+
+    /// A color as `#rrggbb` or `#rrggbbaa`.
+    Color,
+
+    /// Two integers as `x,y`.
+    Pair,
+
+    /// A flag, `true` or `false`.
+    Bool,
+
+    /// Any text.
+    Text,
+}
+
+/// Returns all available ops.
+pub fn ops() -> &'static [Op] {
+    OPS
+}
+
+/// Returns the op with the given name.
+pub fn op(name: &str) -> Option<&'static Op> {
+    OPS.iter().find(|op| op.name == name)
+}
+
+/// A parsed scene.
+pub struct Scene {
+    size: (u32, u32),
+    nodes: HashMap<String, Shared>,
+    output: Shared,
+}
+
+impl Scene {
+    /// The default canvas size.
+    pub const DEFAULT_SIZE: (u32, u32) = (256, 256);
+
+    /// Parses a scene and builds its nodes.
+    ///
+    /// # Errors
+    /// Returns a [`SceneError`] with the line number, starting from one,
+    /// if a line is invalid or the scene has no nodes.
+    pub fn parse(text: &str) -> Result<Self, SceneError> {
+        let mut size = Self::DEFAULT_SIZE;
+        let mut nodes = HashMap::new();
+        let mut output = None;
+        for (n, line) in text.lines().enumerate() {
+            let n = n + 1;
+            let tokens = tokenize(line).ok_or(SceneError::Syntax(n))?;
+            match tokens.as_slice() {
+                [] => {}
+                [cmd, value] if cmd == "size" => {
+                    size = parse_size(value).ok_or(SceneError::Syntax(n))?
+                }
+                [cmd, name] if cmd == "output" => {
+                    let node = nodes.get(name).ok_or_else(|| SceneError::UnknownNode {
+                        line: n,
+                        name: name.clone(),
+                    })?;
+
+                    output = Some(Arc::clone(node));
+                }
+                [name, eq, op, rest @ ..] if eq == "=" => {
+                    let node = build(n, op, rest, &nodes, size)?;
+                    output = Some(Arc::clone(&node));
+                    nodes.insert(name.clone(), node);
+                }
+                _ => return Err(SceneError::Syntax(n)),
+            }
+        }
+
+        Ok(Self {
+            size,
+            nodes,
+            output: output.ok_or(SceneError::Empty)?,
+        })
+    }
+
+    /// Returns the canvas size set by the last `size` line.
+    pub fn size(&self) -> (u32, u32) {
+        self.size
+    }
+
+    /// Returns the node with the given name.
+    pub fn node(&self, name: &str) -> Option<&Shared> {
+        self.nodes.get(name)
+    }
+
+    /// Returns the output node.
+    pub fn output(&self) -> &Shared {
+        &self.output
+    }
+
+    /// Renders the output node over the canvas.
+    pub fn render(&self) -> Frame {
+        crate::make(&self.output, self.size)
+    }
+}
+
+#[derive(Debug)]
+pub enum SceneError {
+    /// A line doesn't follow the scene syntax.
+    Syntax(usize),
+
+    /// An op with the name doesn't exist.
+    UnknownOp { line: usize, name: String },
+
+    /// A node with the name isn't defined above.
+    UnknownNode { line: usize, name: String },
+
+    /// An op got a wrong number of inputs.
+    Inputs { line: usize, op: String },
+
+    /// An op has no parameter with the name.
+    UnknownParam { line: usize, name: String },
+
+    /// A required parameter is missing.
+    Missing { line: usize, name: String },
+
+    /// A parameter value is invalid.
+    Value { line: usize, name: String },
+
+    /// An op failed to load its data.
+    Load { line: usize, message: String },
+
+    /// The scene has no nodes.
+    Empty,
+}
+
+/// Parameters of an op applied on a line.
+struct Args<'a> {
+    line: usize,
+    op: &'static Op,
+    values: HashMap<&'a str, &'a str>,
+    size: (u32, u32),
+}
+
+impl Args<'_> {
+    fn raw(&self, name: &str) -> Result<&str, SceneError> {
+        let param = self
+            .op
+            .params
+            .iter()
+            .find(|param| param.name == name)
+            .expect("the op declares the parameter");
+
+        match self.values.get(name) {
+            Some(value) => Ok(value),
+            None if !param.default.is_empty() => Ok(param.default),
+            None => Err(SceneError::Missing {
+                line: self.line,
+                name: name.to_owned(),
+            }),
+        }
+    }
+
+    fn parse<T>(&self, name: &str, f: impl FnOnce(&str) -> Option<T>) -> Result<T, SceneError> {
+        f(self.raw(name)?).ok_or_else(|| SceneError::Value {
+            line: self.line,
+            name: name.to_owned(),
+        })
+    }
+
+    fn int<T>(&self, name: &str) -> Result<T, SceneError>
+    where
+        T: std::str::FromStr,
+    {
+        self.parse(name, |v| v.parse().ok())
+    }
+
+    /// Parses a finite float, so constructors never get a NaN or an infinity.
+    fn float(&self, name: &str) -> Result<f32, SceneError> {
+        self.parse(name, |v| v.parse().ok().filter(|v: &f32| v.is_finite()))
+    }
+
+    fn color(&self, name: &str) -> Result<Color, SceneError> {
+        self.parse(name, parse_color)
+    }
+
+    fn pair(&self, name: &str) -> Result<(i32, i32), SceneError> {
+        self.parse(name, |v| {
+            let (x, y) = v.split_once(',')?;
+            Some((x.trim().parse().ok()?, y.trim().parse().ok()?))
+        })
+    }
+
+    fn bool(&self, name: &str) -> Result<bool, SceneError> {
+        self.parse(name, |v| v.parse().ok())
+    }
+
+    fn text(&self, name: &str) -> Result<&str, SceneError> {
+        self.raw(name)
+    }
+
+    fn invalid(&self, name: &str) -> SceneError {
+        SceneError::Value {
+            line: self.line,
+            name: name.to_owned(),
+        }
+    }
+}
+
+fn build(
+    line: usize,
+    name: &str,
+    tokens: &[String],
+    nodes: &HashMap<String, Shared>,
+    size: (u32, u32),
+) -> Result<Shared, SceneError> {
+    let op = op(name).ok_or_else(|| SceneError::UnknownOp {
+        line,
+        name: name.to_owned(),
+    })?;
+
+    let mut inputs = vec![];
+    let mut values = HashMap::new();
+    for token in tokens {
+        match token.split_once('=') {
+            Some((key, value)) => {
+                if !op.params.iter().any(|param| param.name == key) {
+                    return Err(SceneError::UnknownParam {
+                        line,
+                        name: key.to_owned(),
+                    });
+                }
+
+                values.insert(key, value);
+            }
+            None => {
+                let node = nodes.get(token).ok_or_else(|| SceneError::UnknownNode {
+                    line,
+                    name: token.clone(),
+                })?;
+
+                inputs.push(Arc::clone(node));
+            }
+        }
+    }
+
+    if !op.inputs.contains(&inputs.len()) {
+        return Err(SceneError::Inputs {
+            line,
+            op: name.to_owned(),
+        });
+    }
+
+    let args = Args {
+        line,
+        op,
+        values,
+        size,
+    };
+
+    (op.build)(&args, inputs)
+}
+
+/// Splits a line into tokens, keeping quoted text whole. Returns `None` on an unclosed quote.
+fn tokenize(line: &str) -> Option<Vec<String>> {
+    let mut tokens = vec![];
+    let mut token = String::new();
+    let mut quoted = false;
+    let mut started = false;
+    for c in line.chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                started = true;
+            }
+            '#' if !quoted && !started => break,
+            c if c.is_whitespace() && !quoted => {
+                if started {
+                    tokens.push(std::mem::take(&mut token));
+                    started = false;
+                }
+            }
+            c => {
+                token.push(c);
+                started = true;
+            }
+        }
+    }
+
+    if quoted {
+        return None;
+    }
+
+    if started {
+        tokens.push(token);
+    }
+
+    Some(tokens)
+}
+
+fn parse_size(value: &str) -> Option<(u32, u32)> {
+    let (w, h) = value.split_once('x')?;
+    Some((w.parse().ok()?, h.parse().ok()?))
+}
+
+fn parse_color(value: &str) -> Option<Color> {
+    let hex = value.strip_prefix('#')?;
+    let v = u32::from_str_radix(hex, 16).ok()?;
+    match hex.len() {
+        6 => Some(Color::from_u32(v << 8 | 0xFF)),
+        8 => Some(Color::from_u32(v)),
+        _ => None,
+    }
+}
+
+fn single(inputs: Vec<Shared>) -> Shared {
+    let [input] = <[Shared; 1]>::try_from(inputs).ok().expect("one input");
+    input
+}
+
+static OPS: &[Op] = &[
+    Op {
+        name: "color",
+        summary: "Fills the plane with a color.",
+        inputs: 0..=0,
+        params: &[Param {
+            name: "color",
+            kind: Kind::Color,
+            default: "#ffffff",
+            doc: "The fill color.",
+        }],
+        build: |args, _| Ok(Arc::new(args.color("color")?)),
+    },
+    Op {
+        name: "expr",
+        summary: "Computes colors with math expressions, see the Expr source.",
+        inputs: 0..=0,
+        params: &[
+            Param {
+                name: "text",
+                kind: Kind::Text,
+                default: "",
+                doc: "One, three or four comma separated expressions over x, y, w, h and t.",
+            },
+            Param {
+                name: "time",
+                kind: Kind::Float,
+                default: "0",
+                doc: "The value of t.",
+            },
+        ],
+        build: |args, _| {
+            let expr = Expr::new(args.text("text")?).map_err(|err| SceneError::Load {
+                line: args.line,
+                message: format!("{err:?}"),
+            })?;
+
+            Ok(Arc::new(expr.size(args.size).time(args.float("time")?)))
+        },
+    },
+    Op {
+        name: "noise",
+        summary: "Fractal Perlin noise.",
+        inputs: 0..=0,
+        params: &[
+            Param {
+                name: "seed",
+                kind: Kind::Int,
+                default: "0",
+                doc: "The noise seed.",
+            },
+            Param {
+                name: "scale",
+                kind: Kind::Float,
+                default: "32",
+                doc: "The size of a noise cell in pixels.",
+            },
+            Param {
+                name: "octaves",
+                kind: Kind::Int,
+                default: "1",
+                doc: "The number of summed octaves.",
+            },
+        ],
+        build: |args, _| {
+            let octaves: u8 = args.int("octaves")?;
+            let scale = args.float("scale")?;
+            if octaves == 0 {
+                return Err(args.invalid("octaves"));
+            }
+
+            if scale <= f32::EPSILON {
+                return Err(args.invalid("scale"));
+            }
+
+            let field = Fbm::new(Perlin::new(args.int("seed")?), octaves);
+            Ok(Arc::new(Noise::new(field, scale)))
+        },
+    },
+    Op {
+        name: "cells",
+        summary: "Cellular Worley noise.",
+        inputs: 0..=0,
+        params: &[
+            Param {
+                name: "seed",
+                kind: Kind::Int,
+                default: "0",
+                doc: "The noise seed.",
+            },
+            Param {
+                name: "scale",
+                kind: Kind::Float,
+                default: "32",
+                doc: "The size of a cell in pixels.",
+            },
+            Param {
+                name: "edges",
+                kind: Kind::Bool,
+                default: "false",
+                doc: "Shows cell edges instead of distances to cell centers.",
+            },
+        ],
+        build: |args, _| {
+            let scale = args.float("scale")?;
+            if scale <= f32::EPSILON {
+                return Err(args.invalid("scale"));
+            }
+
+            let output = if args.bool("edges")? {
+                WorleyOutput::F2MinusF1
+            } else {
+                WorleyOutput::F1
+            };
+
+            let field = Worley::new(args.int("seed")?, Metric::Euclidean, output);
+            Ok(Arc::new(Noise::new(field, scale)))
+        },
+    },
+    #[cfg(feature = "image")]
+    Op {
+        name: "image",
+        summary: "Loads an image file.",
+        inputs: 0..=0,
+        params: &[Param {
+            name: "path",
+            kind: Kind::Text,
+            default: "",
+            doc: "The path of the image file.",
+        }],
+        build: |args, _| {
+            let load = |path| {
+                let bytes = std::fs::read(path).map_err(|err| err.to_string())?;
+                crate::Image::from_memory(&bytes).map_err(|err| format!("{err:?}"))
+            };
+
+            load(args.text("path")?)
+                .map(|im| -> Shared { Arc::new(im) })
+                .map_err(|message| SceneError::Load {
+                    line: args.line,
+                    message,
+                })
+        },
+    },
+    Op {
+        name: "layers",
+        summary: "Overlays inputs, the first one is on top.",
+        inputs: 1..=usize::MAX,
+        params: &[],
+        build: |_, inputs| Ok(Arc::new(inputs)),
+    },
+    Op {
+        name: "offset",
+        summary: "Moves the input.",
+        inputs: 1..=1,
+        params: &[Param {
+            name: "by",
+            kind: Kind::Pair,
+            default: "0,0",
+            doc: "The offset in pixels.",
+        }],
+        build: |args, inputs| Ok(Arc::new(Offset::new(single(inputs), args.pair("by")?))),
+    },
+    Op {
+        name: "scale",
+        summary: "Scales the input with linear filtering.",
+        inputs: 1..=1,
+        params: &[Param {
+            name: "factor",
+            kind: Kind::Float,
+            default: "1",
+            doc: "The scale factor, values above one enlarge the input.",
+        }],
+        build: |args, inputs| {
+            let factor = args.float("factor")?;
+            if factor <= f32::EPSILON {
+                return Err(args.invalid("factor"));
+            }
+
+            Ok(Arc::new(Scale::new(single(inputs), factor, Filter::Linear)))
+        },
+    },
+    Op {
+        name: "blur",
+        summary: "Disk blur.",
+        inputs: 1..=1,
+        params: &[Param {
+            name: "radius",
+            kind: Kind::Int,
+            default: "2",
+            doc: "The blur radius in pixels.",
+        }],
+        build: |args, inputs| Ok(Arc::new(Blur::new(single(inputs), args.int("radius")?))),
+    },
+    Op {
+        name: "gaussian",
+        summary: "Gaussian blur.",
+        inputs: 1..=1,
+        params: &[Param {
+            name: "sigma",
+            kind: Kind::Float,
+            default: "2",
+            doc: "The standard deviation in pixels.",
+        }],
+        build: |args, inputs| {
+            let sigma = args.float("sigma")?;
+            if sigma <= f32::EPSILON {
+                return Err(args.invalid("sigma"));
+            }
+
+            Ok(Arc::new(GaussianBlur::new(single(inputs), sigma)))
+        },
+    },
+    Op {
+        name: "grain",
+        summary: "Overlays film grain.",
+        inputs: 1..=1,
+        params: &[
+            Param {
+                name: "seed",
+                kind: Kind::Int,
+                default: "0",
+                doc: "The grain seed.",
+            },
+            Param {
+                name: "intensity",
+                kind: Kind::Float,
+                default: "0.1",
+                doc: "The grain intensity.",
+            },
+        ],
+        build: |args, inputs| {
+            let grain = Grain::new(single(inputs), args.int("seed")?);
+            Ok(Arc::new(grain.intensity(args.float("intensity")?)))
+        },
+    },
+    Op {
+        name: "vignette",
+        summary: "Darkens or lightens the canvas toward the corners.",
+        inputs: 1..=1,
+        params: &[Param {
+            name: "amount",
+            kind: Kind::Float,
+            default: "0.5",
+            doc: "Positive values darken, negative ones lighten.",
+        }],
+        build: |args, inputs| {
+            let vignette = Vignette::new(single(inputs), args.float("amount")?);
+            Ok(Arc::new(vignette.canvas(args.size)))
+        },
+    },
+    Op {
+        name: "solarize",
+        summary: "Inverts tones above a threshold.",
+        inputs: 1..=1,
+        params: &[Param {
+            name: "threshold",
+            kind: Kind::Float,
+            default: "0.5",
+            doc: "The luminance above which tones are inverted.",
+        }],
+        build: |args, inputs| {
+            Ok(Arc::new(Solarize::new(
+                single(inputs),
+                args.float("threshold")?,
+            )))
+        },
+    },
+    Op {
+        name: "duotone",
+        summary: "Maps luminance to a gradient between two colors.",
+        inputs: 1..=1,
+        params: &[
+            Param {
+                name: "shadows",
+                kind: Kind::Color,
+                default: "#000000",
+                doc: "The color of the darkest tones.",
+            },
+            Param {
+                name: "highlights",
+                kind: Kind::Color,
+                default: "#ffffff",
+                doc: "The color of the lightest tones.",
+            },
+        ],
+        build: |args, inputs| {
+            Ok(Arc::new(GradientMap::duotone(
+                single(inputs),
+                args.color("shadows")?,
+                args.color("highlights")?,
+            )))
+        },
+    },
+    Op {
+        name: "diff",
+        summary: "Shows the difference between two inputs.",
+        inputs: 2..=2,
+        params: &[
+            Param {
+                name: "amplify",
+                kind: Kind::Float,
+                default: "1",
+                doc: "The multiplier of differences.",
+            },
+            Param {
+                name: "mode",
+                kind: Kind::Text,
+                default: "channels",
+                doc: "Either channels or heat.",
+            },
+        ],
+        build: |args, inputs| {
+            let mode = match args.text("mode")? {
+                "channels" => DiffMode::Channels,
+                "heat" => DiffMode::Heat,
+                _ => return Err(args.invalid("mode")),
+            };
+
+            let amplify = args.float("amplify")?;
+            if amplify < 0. {
+                return Err(args.invalid("amplify"));
+            }
+
+            let [a, b] = <[Shared; 2]>::try_from(inputs).ok().expect("two inputs");
+            Ok(Arc::new(crate::source::Diff::new(a, b, amplify, mode)))
+        },
+    },
+];
@@ -1,4 +1,106 @@
-use crate::{Color, Image};
+mod adjust;
+mod barcode;
+#[cfg(feature = "std")]
+mod cache;
+mod convolve;
+mod decor;
+mod depth;
+mod diff;
+#[cfg(feature = "std")]
+mod distance;
+#[cfg(feature = "std")]
+mod equalize;
+mod expr;
+mod filter;
+#[cfg(feature = "fonts")]
+mod font;
+mod grain;
+mod key;
+mod layout;
+mod mapping;
+mod material;
+mod morph;
+mod noise;
+mod path;
+mod pattern;
+mod plasma;
+mod polar;
+mod pyramid;
+#[cfg(feature = "qr")]
+mod qr;
+mod reaction;
+mod relief;
+mod scatter;
+mod sky;
+mod stylize;
+#[cfg(feature = "svg")]
+mod svg;
+mod text;
+mod vignette;
+
+pub use self::{
+    adjust::{Balance, Channel, Channels, GradientMap, Solarize, Temperature, WhiteBalance},
+    barcode::{Barcode, BarcodeError},
+    convolve::{Convolve, GaussianBlur, Kernel},
+    decor::{Corner, Framed, RoundedCorners},
+    depth::{DepthSource, Flat, WithDepth, ZComposite},
+    diff::{Diff, DiffMode},
+    expr::{Expr, ExprError},
+    filter::{Bilateral, Kuwahara, KuwaharaKind, OilPaint},
+    grain::Grain,
+    key::{ChromaKey, ReplaceColor},
+    layout::{Align, Fit, FitMode, Grid, HStack, Insets, NineSlice, SliceMode, VStack},
+    mapping::{Affine, Mapped, Mapping, Then, Warp},
+    material::{Marble, Wood},
+    morph::{Close, Dilate, Element, Erode, MorphKey, Open},
+    noise::{BlueNoise, DomainWarp, Fbm, Field, Metric, Noise, Perlin, Worley, WorleyOutput},
+    path::{Cap, Fill, FillRule, GradientStroke, Path, Stroke},
+    pattern::{
+        Bricks, Dots, HexGrid, HexStyle, PatternFill, Penrose, Repeat, Rhombille, Stripes,
+        TileStyle, Truchet, TruchetStyle,
+    },
+    plasma::Plasma,
+    polar::{Polar, Unpolar},
+    pyramid::Pyramid,
+    reaction::{GrayScott, ReactionDiffusion},
+    relief::{Hillshade, Light, Lit, NormalMap},
+    scatter::Scatter,
+    sky::{Sky, Starfield},
+    stylize::{Dot, Halftone, Hatch, HatchStyle},
+    text::{Text, TextAlign},
+    vignette::Vignette,
+};
+
+#[cfg(feature = "std")]
+pub use self::{
+    cache::TileCache,
+    distance::{DistanceField, DistanceMode},
+    equalize::{Equalization, Equalize},
+};
+
+#[cfg(feature = "fonts")]
+pub use self::font::{Antialias, Font, FontError, FontText, Hinting, SubpixelOrder};
+
+#[cfg(feature = "qr")]
+pub use {
+    self::qr::{QrCode, QrLevel},
+    qrcode::types::QrError,
+};
+
+#[cfg(feature = "svg")]
+pub use self::svg::{Svg, SvgError};
+
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::Float;
+#[cfg(feature = "std")]
+use crate::profile::{Profile, Timed};
+#[cfg(feature = "image")]
+use crate::Image;
+use {
+    crate::{Color, LerpSpace},
+    alloc::{boxed::Box, rc::Rc, sync::Arc, vec, vec::Vec},
+    core::ops::Range,
+};
 
 pub trait Source {
     fn source(&self, pos: (i32, i32)) -> Color;
@@ -6,8 +108,285 @@ pub trait Source {
     fn borders(&self) -> Option<Borders> {
         None
     }
+
+    /// Called before sampling with the region that is going to be rendered.
+    ///
+    /// Sources can build acceleration structures here for exactly that region.
+    /// Wrappers pass the region they will sample to their inner sources.
+    fn prepare(&mut self, region: Borders) {
+        _ = region;
+    }
+
+    /// Called before rendering with the seed of the render.
+    ///
+    /// Stochastic sources mix it into their own seeds, so the same pipeline can render
+    /// different but reproducible variations. Wrappers pass it to their inner sources.
+    fn reseed(&mut self, seed: u32) {
+        _ = seed;
+    }
+
+    /// Samples a fractional position where one output pixel covers `footprint`
+    /// source pixels, as [`Scale`] does when minifying.
+    ///
+    /// Sources with prefiltered levels, like [`Pyramid`], override this to avoid aliasing.
+    fn sample_scaled(&self, pos: (f32, f32), footprint: f32, filter: Filter) -> Color {
+        _ = footprint;
+        sample(self, filter, pos)
+    }
+
+    /// Samples positions `xs` of the row `y` into `out`, which has the same length as the range.
+    ///
+    /// Sources that can fill a whole row faster than sampling every pixel override this.
+    fn source_row(&self, y: i32, xs: Range<i32>, out: &mut [Color]) {
+        for (x, col) in xs.zip(out) {
+            *col = self.source((x, y));
+        }
+    }
 }
 
+/// A boxed source of any type.
+///
+/// Lets pipelines be assembled at runtime, when the nesting of combinators
+/// isn't known at compile time. Combinators wrap sources with the [`SourceExt`]
+/// methods, and [`boxed`](SourceExt::boxed) erases the result.
+pub type DynSource = Box<dyn Source + Send + Sync>;
+
+/// Extra methods for all sources.
+pub trait SourceExt: Source + Sized {
+    /// Moves the source into an [`Arc`], so it can be cheaply cloned and
+    /// used by several layers, also from other threads.
+    fn shared(self) -> Arc<Self> {
+        Arc::new(self)
+    }
+
+    /// Erases the source type.
+    fn boxed(self) -> DynSource
+    where
+        Self: Send + Sync + 'static,
+    {
+        Box::new(self)
+    }
+
+    /// Wraps the source in an [`Offset`].
+    fn offset(self, offset: (i32, i32)) -> Offset<Self> {
+        Offset::new(self, offset)
+    }
+
+    /// Wraps the source in a [`Scale`].
+    fn scale(self, factor: f32, filter: Filter) -> Scale<Self> {
+        Scale::new(self, factor, filter)
+    }
+
+    /// Wraps the source in a [`Blur`].
+    fn blur(self, radius: u8) -> Blur<Self> {
+        Blur::new(self, radius)
+    }
+
+    /// Wraps the source in a [`Mapped`].
+    fn mapped<M>(self, mapping: M) -> Mapped<Self, M>
+    where
+        M: Mapping,
+    {
+        Mapped::new(self, mapping)
+    }
+
+    /// Wraps the source in a [`Clip`].
+    fn clip(self) -> Clip<Self> {
+        Clip::new(self)
+    }
+
+    /// Wraps the source in a [`WhiteBalance`].
+    fn white_balance(self, mode: Balance) -> WhiteBalance<Self> {
+        WhiteBalance::new(self, mode)
+    }
+
+    /// Wraps the source in a [`Temperature`].
+    fn temperature(self, kelvin: f32, tint: f32) -> Temperature<Self> {
+        Temperature::new(self, kelvin, tint)
+    }
+
+    /// Wraps the source in a [`GradientMap`].
+    fn gradient_map(self, stops: Vec<(f32, Color)>) -> GradientMap<Self> {
+        GradientMap::new(self, stops)
+    }
+
+    /// Wraps the source in a [`Solarize`].
+    fn solarize(self, threshold: f32) -> Solarize<Self> {
+        Solarize::new(self, threshold)
+    }
+
+    /// Wraps the source in a [`Channels`].
+    fn channels(self, channel: Channel) -> Channels<Self> {
+        Channels::new(self, channel)
+    }
+
+    /// Wraps the source in a [`ChromaKey`].
+    fn chroma_key(self, key: Color, tolerance: f32) -> ChromaKey<Self> {
+        ChromaKey::new(self, key, tolerance)
+    }
+
+    /// Wraps the source in a [`ReplaceColor`].
+    fn replace_color(self, from: Color, to: Color, tolerance: f32) -> ReplaceColor<Self> {
+        ReplaceColor::new(self, from, to, tolerance)
+    }
+
+    /// Wraps the source in a [`PatternFill`].
+    fn pattern_fill(self, seed: u32) -> PatternFill<Self> {
+        PatternFill::new(self, seed)
+    }
+
+    /// Wraps the source in a [`Repeat`].
+    fn repeat(self) -> Repeat<Self> {
+        Repeat::new(self)
+    }
+
+    /// Wraps the source in a [`Bilateral`].
+    fn bilateral(self, spatial: f32, range: f32) -> Bilateral<Self> {
+        Bilateral::new(self, spatial, range)
+    }
+
+    /// Wraps the source in a [`Kuwahara`].
+    fn kuwahara(self, radius: u8, kind: KuwaharaKind) -> Kuwahara<Self> {
+        Kuwahara::new(self, radius, kind)
+    }
+
+    /// Wraps the source in an [`OilPaint`].
+    fn oil_paint(self, radius: u8, levels: u8) -> OilPaint<Self> {
+        OilPaint::new(self, radius, levels)
+    }
+
+    /// Wraps the source in a [`Polar`].
+    fn polar(self, center: (f32, f32), period: f32) -> Polar<Self> {
+        Polar::new(self, center, period)
+    }
+
+    /// Wraps the source in an [`Unpolar`].
+    fn unpolar(self, center: (f32, f32), period: f32) -> Unpolar<Self> {
+        Unpolar::new(self, center, period)
+    }
+
+    /// Wraps the source in a [`Halftone`].
+    fn halftone(self, period: f32, shape: Dot) -> Halftone<Self> {
+        Halftone::new(self, period, shape)
+    }
+
+    /// Wraps the source in a [`Hatch`].
+    fn hatch(self, spacing: f32, style: HatchStyle) -> Hatch<Self> {
+        Hatch::new(self, spacing, style)
+    }
+
+    /// Wraps the source in a [`TileCache`].
+    #[cfg(feature = "std")]
+    fn tile_cache(self, tile: u32, capacity: usize) -> TileCache<Self> {
+        TileCache::new(self, tile, capacity)
+    }
+
+    /// Wraps the source in a [`Dilate`].
+    fn dilate(self, radius: u8, element: Element, key: MorphKey) -> Dilate<Self> {
+        Dilate::new(self, radius, element, key)
+    }
+
+    /// Wraps the source in an [`Erode`].
+    fn erode(self, radius: u8, element: Element, key: MorphKey) -> Erode<Self> {
+        Erode::new(self, radius, element, key)
+    }
+
+    /// Wraps the source in an [`Open`].
+    fn open(self, radius: u8, element: Element, key: MorphKey) -> Open<Self> {
+        Open::new(self, radius, element, key)
+    }
+
+    /// Wraps the source in a [`Close`].
+    fn close(self, radius: u8, element: Element, key: MorphKey) -> Close<Self> {
+        Close::new(self, radius, element, key)
+    }
+
+    /// Wraps the source in a [`Vignette`].
+    fn vignette(self, amount: f32) -> Vignette<Self> {
+        Vignette::new(self, amount)
+    }
+
+    /// Wraps the source in a [`Convolve`].
+    fn convolve(self, kernel: Kernel) -> Convolve<Self> {
+        Convolve::new(self, kernel)
+    }
+
+    /// Wraps the source in a [`GaussianBlur`].
+    fn gaussian_blur(self, sigma: f32) -> GaussianBlur<Self> {
+        GaussianBlur::new(self, sigma)
+    }
+
+    /// Wraps the source in a [`Scatter`].
+    fn scatter(self, region: Borders, count: u32, seed: u32) -> Scatter<Self> {
+        Scatter::new(self, region, count, seed)
+    }
+
+    /// Wraps the source in a [`Grain`].
+    fn grain(self, seed: u32) -> Grain<Self> {
+        Grain::new(self, seed)
+    }
+
+    /// Wraps the source in a [`NineSlice`].
+    fn nine_slice(self, insets: Insets, size: (u32, u32), mode: SliceMode) -> NineSlice<Self> {
+        NineSlice::new(self, insets, size, mode)
+    }
+
+    /// Wraps the source in a [`Fit`].
+    fn fit(self, size: (u32, u32), mode: FitMode) -> Fit<Self> {
+        Fit::new(self, size, mode)
+    }
+
+    /// Wraps the source in a [`NormalMap`].
+    fn normal_map(self, strength: f32) -> NormalMap<Self> {
+        NormalMap::new(self, strength)
+    }
+
+    /// Wraps the source in a [`Hillshade`].
+    fn hillshade(self, azimuth: f32, altitude: f32) -> Hillshade<Self> {
+        Hillshade::new(self, azimuth, altitude)
+    }
+
+    /// Wraps the source in a [`RoundedCorners`].
+    fn rounded_corners(self, radius: f32) -> RoundedCorners<Self> {
+        RoundedCorners::new(self, radius)
+    }
+
+    /// Wraps the source in an [`Equalize`].
+    #[cfg(feature = "std")]
+    fn equalize(self, mode: Equalization) -> Equalize<Self> {
+        Equalize::new(self, mode)
+    }
+
+    /// Wraps the source in a [`Framed`].
+    fn framed<P>(self, thickness: u32, paint: P) -> Framed<Self, P> {
+        Framed::new(self, thickness, paint)
+    }
+
+    /// Compares the source with another one in a [`Diff`].
+    fn diff<B>(self, other: B, amplify: f32, mode: DiffMode) -> Diff<Self, B> {
+        Diff::new(self, other, amplify, mode)
+    }
+
+    /// Builds a [`DistanceField`] of the source.
+    #[cfg(feature = "std")]
+    fn distance_field(self, spread: f32, mode: DistanceMode) -> DistanceField<Self> {
+        DistanceField::new(self, spread, mode)
+    }
+
+    /// Builds a [`Pyramid`] of the source.
+    fn pyramid(self) -> Pyramid {
+        Pyramid::new(self)
+    }
+
+    /// Wraps the source in a [`Timed`] to measure it in the `profile`.
+    #[cfg(feature = "std")]
+    fn timed(self, name: &str, profile: &Profile) -> Timed<Self> {
+        Timed::new(self, name, profile)
+    }
+}
+
+impl<S> SourceExt for S where S: Source {}
+
 #[derive(Clone, Copy)]
 pub struct Borders {
     pub w: (i32, i32),
@@ -23,8 +402,75 @@ impl Borders {
 
         x >= x0 && x <= x1 && y >= y0 && y <= y1
     }
+
+    pub fn size(self) -> (u32, u32) {
+        let Self {
+            w: (x0, x1),
+            h: (y0, y1),
+        } = self;
+
+        let len = |a: i32, b: i32| (b as i64 - a as i64 + 1).max(0) as u32;
+        (len(x0, x1), len(y0, y1))
+    }
+
+    /// Returns an iterator over all positions inside the borders, row by row.
+    pub fn positions(self) -> impl Iterator<Item = (i32, i32)> {
+        let Self {
+            w: (x0, x1),
+            h: (y0, y1),
+        } = self;
+
+        (y0..=y1).flat_map(move |y| (x0..=x1).map(move |x| (x, y)))
+    }
+
+    /// Grows the borders by `d` on every side.
+    pub fn expand(self, d: i32) -> Self {
+        Self {
+            w: (self.w.0.wrapping_sub(d), self.w.1.wrapping_add(d)),
+            h: (self.h.0.wrapping_sub(d), self.h.1.wrapping_add(d)),
+        }
+    }
+
+    /// Moves the borders by the `offset`.
+    pub fn shift(self, (dx, dy): (i32, i32)) -> Self {
+        Self {
+            w: (self.w.0.wrapping_add(dx), self.w.1.wrapping_add(dx)),
+            h: (self.h.0.wrapping_add(dy), self.h.1.wrapping_add(dy)),
+        }
+    }
+
+    pub fn union(self, rhs: Self) -> Self {
+        Self {
+            w: (self.w.0.min(rhs.w.0), self.w.1.max(rhs.w.1)),
+            h: (self.h.0.min(rhs.h.0), self.h.1.max(rhs.h.1)),
+        }
+    }
+
+    /// Checks that a source is transparent at every position inside these borders
+    /// which is outside the borders the source reports.
+    ///
+    /// Rendering skips everything outside the reported borders, so this is a debugging
+    /// aid for source implementations. Sources without borders always pass.
+    ///
+    /// # Errors
+    /// Returns the first visible position outside the reported borders.
+    pub fn validate<S>(self, source: &S) -> Result<(), (i32, i32)>
+    where
+        S: Source + ?Sized,
+    {
+        let Some(reported) = source.borders() else {
+            return Ok(());
+        };
+
+        self.positions()
+            .filter(|&pos| !reported.contains(pos))
+            .find(|&pos| source.source(pos).is_visible())
+            .map_or(Ok(()), Err)
+    }
 }
 
+/// A shared reference can't [prepare](Source::prepare) or [reseed](Source::reseed)
+/// the source, so both are skipped. Pass a `&mut` reference to keep them.
 impl<S> Source for &S
 where
     S: Source + ?Sized,
@@ -36,6 +482,43 @@ where
     fn borders(&self) -> Option<Borders> {
         S::borders(self)
     }
+
+    fn sample_scaled(&self, pos: (f32, f32), footprint: f32, filter: Filter) -> Color {
+        S::sample_scaled(self, pos, footprint, filter)
+    }
+
+    fn source_row(&self, y: i32, xs: Range<i32>, out: &mut [Color]) {
+        S::source_row(self, y, xs, out);
+    }
+}
+
+impl<S> Source for &mut S
+where
+    S: Source + ?Sized,
+{
+    fn source(&self, pos: (i32, i32)) -> Color {
+        S::source(self, pos)
+    }
+
+    fn borders(&self) -> Option<Borders> {
+        S::borders(self)
+    }
+
+    fn sample_scaled(&self, pos: (f32, f32), footprint: f32, filter: Filter) -> Color {
+        S::sample_scaled(self, pos, footprint, filter)
+    }
+
+    fn prepare(&mut self, region: Borders) {
+        S::prepare(self, region);
+    }
+
+    fn reseed(&mut self, seed: u32) {
+        S::reseed(self, seed);
+    }
+
+    fn source_row(&self, y: i32, xs: Range<i32>, out: &mut [Color]) {
+        S::source_row(self, y, xs, out);
+    }
 }
 
 impl<S> Source for Box<S>
@@ -49,6 +532,92 @@ where
     fn borders(&self) -> Option<Borders> {
         S::borders(self)
     }
+
+    fn sample_scaled(&self, pos: (f32, f32), footprint: f32, filter: Filter) -> Color {
+        S::sample_scaled(self, pos, footprint, filter)
+    }
+
+    fn prepare(&mut self, region: Borders) {
+        S::prepare(self, region);
+    }
+
+    fn reseed(&mut self, seed: u32) {
+        S::reseed(self, seed);
+    }
+
+    fn source_row(&self, y: i32, xs: Range<i32>, out: &mut [Color]) {
+        S::source_row(self, y, xs, out);
+    }
+}
+
+impl<S> Source for Rc<S>
+where
+    S: Source + ?Sized,
+{
+    fn source(&self, pos: (i32, i32)) -> Color {
+        S::source(self, pos)
+    }
+
+    fn borders(&self) -> Option<Borders> {
+        S::borders(self)
+    }
+
+    fn sample_scaled(&self, pos: (f32, f32), footprint: f32, filter: Filter) -> Color {
+        S::sample_scaled(self, pos, footprint, filter)
+    }
+
+    /// Prepares the source only when it isn't shared.
+    fn prepare(&mut self, region: Borders) {
+        if let Some(source) = Rc::get_mut(self) {
+            source.prepare(region);
+        }
+    }
+
+    /// Reseeds the source only when it isn't shared.
+    fn reseed(&mut self, seed: u32) {
+        if let Some(source) = Rc::get_mut(self) {
+            source.reseed(seed);
+        }
+    }
+
+    fn source_row(&self, y: i32, xs: Range<i32>, out: &mut [Color]) {
+        S::source_row(self, y, xs, out);
+    }
+}
+
+impl<S> Source for Arc<S>
+where
+    S: Source + ?Sized,
+{
+    fn source(&self, pos: (i32, i32)) -> Color {
+        S::source(self, pos)
+    }
+
+    fn borders(&self) -> Option<Borders> {
+        S::borders(self)
+    }
+
+    fn sample_scaled(&self, pos: (f32, f32), footprint: f32, filter: Filter) -> Color {
+        S::sample_scaled(self, pos, footprint, filter)
+    }
+
+    /// Prepares the source only when it isn't shared.
+    fn prepare(&mut self, region: Borders) {
+        if let Some(source) = Arc::get_mut(self) {
+            source.prepare(region);
+        }
+    }
+
+    /// Reseeds the source only when it isn't shared.
+    fn reseed(&mut self, seed: u32) {
+        if let Some(source) = Arc::get_mut(self) {
+            source.reseed(seed);
+        }
+    }
+
+    fn source_row(&self, y: i32, xs: Range<i32>, out: &mut [Color]) {
+        S::source_row(self, y, xs, out);
+    }
 }
 
 impl<S> Source for [S]
@@ -67,6 +636,29 @@ where
 
         res
     }
+
+    /// Layers are bounded by the union of their borders, if every layer has them.
+    fn borders(&self) -> Option<Borders> {
+        self.iter()
+            .map(S::borders)
+            .reduce(|a, b| Some(a?.union(b?)))
+            .unwrap_or(Some(Borders {
+                w: (0, -1),
+                h: (0, -1),
+            }))
+    }
+
+    fn prepare(&mut self, region: Borders) {
+        for source in self {
+            source.prepare(region);
+        }
+    }
+
+    fn reseed(&mut self, seed: u32) {
+        for source in self {
+            source.reseed(seed);
+        }
+    }
 }
 
 impl<S> Source for Vec<S>
@@ -76,14 +668,31 @@ where
     fn source(&self, pos: (i32, i32)) -> Color {
         self.as_slice().source(pos)
     }
+
+    fn borders(&self) -> Option<Borders> {
+        self.as_slice().borders()
+    }
+
+    fn prepare(&mut self, region: Borders) {
+        self.as_mut_slice().prepare(region);
+    }
+
+    fn reseed(&mut self, seed: u32) {
+        self.as_mut_slice().reseed(seed);
+    }
 }
 
 impl Source for Color {
     fn source(&self, _: (i32, i32)) -> Self {
         *self
     }
+
+    fn source_row(&self, _: i32, _: Range<i32>, out: &mut [Color]) {
+        out.fill(*self);
+    }
 }
 
+#[cfg(feature = "image")]
 impl Source for Image {
     fn source(&self, (x, y): (i32, i32)) -> Color {
         match (x.try_into(), y.try_into()) {
@@ -92,6 +701,19 @@ impl Source for Image {
         }
     }
 
+    fn source_row(&self, y: i32, xs: Range<i32>, out: &mut [Color]) {
+        out.fill(Color::default());
+
+        let (w, h) = self.size();
+        let (x0, x1) = (xs.start.max(0), xs.end.min(w as i32));
+        if y < 0 || y >= h as i32 || x0 >= x1 {
+            return;
+        }
+
+        let out = &mut out[(x0 - xs.start) as usize..(x1 - xs.start) as usize];
+        self.row(y as u32, x0 as u32..x1 as u32, out);
+    }
+
     fn borders(&self) -> Option<Borders> {
         let (w, h) = self.size();
         Some(Borders {
@@ -101,6 +723,83 @@ impl Source for Image {
     }
 }
 
+/// Skips sampling of a source outside its borders.
+///
+/// Positions outside the borders are transparent without calling the source,
+/// which saves time for expensive bounded sources sampled over a larger area.
+/// The borders are read once, on construction.
+pub struct Clip<S> {
+    source: S,
+    borders: Option<Borders>,
+}
+
+impl<S> Clip<S>
+where
+    S: Source,
+{
+    pub fn new(source: S) -> Self {
+        let borders = source.borders();
+        Self { source, borders }
+    }
+}
+
+impl<S> Source for Clip<S>
+where
+    S: Source,
+{
+    fn source(&self, pos: (i32, i32)) -> Color {
+        match self.borders {
+            Some(borders) if !borders.contains(pos) => Color::default(),
+            _ => self.source.source(pos),
+        }
+    }
+
+    fn source_row(&self, y: i32, xs: Range<i32>, out: &mut [Color]) {
+        let Some(Borders {
+            w: (x0, x1),
+            h: (y0, y1),
+        }) = self.borders
+        else {
+            return self.source.source_row(y, xs, out);
+        };
+
+        out.fill(Color::default());
+        let (start, end) = (xs.start.max(x0), xs.end.min(x1.saturating_add(1)));
+        if y < y0 || y > y1 || start >= end {
+            return;
+        }
+
+        let out = &mut out[(start - xs.start) as usize..(end - xs.start) as usize];
+        self.source.source_row(y, start..end, out);
+    }
+
+    fn borders(&self) -> Option<Borders> {
+        self.borders
+    }
+
+    fn sample_scaled(&self, (x, y): (f32, f32), footprint: f32, filter: Filter) -> Color {
+        // Filters read around the position, as far as the footprint
+        let reach = footprint.max(1.).ceil() as i32 + 1;
+        match self.borders {
+            Some(borders) if !borders.expand(reach).contains((x as i32, y as i32)) => {
+                Color::default()
+            }
+            _ => self.source.sample_scaled((x, y), footprint, filter),
+        }
+    }
+
+    fn prepare(&mut self, region: Borders) {
+        self.source.prepare(region);
+    }
+
+    fn reseed(&mut self, seed: u32) {
+        self.source.reseed(seed);
+    }
+}
+
+/// Moves a source by whole pixels.
+///
+/// Positions are moved exactly and whole rows of the source are read as they are.
 pub struct Offset<S> {
     source: S,
     offset: (i32, i32),
@@ -110,6 +809,15 @@ impl<S> Offset<S> {
     pub fn new(source: S, offset: (i32, i32)) -> Self {
         Self { source, offset }
     }
+
+    /// Turns the offset into a [`Mapped`] source with an [`Affine::offset`] transform,
+    /// followed by the `next` one, as a single matrix.
+    pub fn transform(self, next: Affine) -> Mapped<S, Affine> {
+        let (dx, dy) = self.offset;
+        Mapped::new(self.source, Affine::offset((dx as f32, dy as f32)))
+            .filter(Filter::Near)
+            .transform(next)
+    }
 }
 
 impl<S> Source for Offset<S>
@@ -117,50 +825,60 @@ where
     S: Source,
 {
     fn source(&self, (x, y): (i32, i32)) -> Color {
-        self.source.source({
-            let (dx, dy) = self.offset;
-            (x.wrapping_sub(dx), y.wrapping_sub(dy))
-        })
+        let (dx, dy) = self.offset;
+        self.source.source((x.wrapping_sub(dx), y.wrapping_sub(dy)))
     }
 
     fn borders(&self) -> Option<Borders> {
-        self.source.borders().map(
-            |Borders {
-                 w: (x0, x1),
-                 h: (y0, y1),
-             }| {
-                let (dx, dy) = self.offset;
-                Borders {
-                    w: (x0.wrapping_sub(dx), x1.wrapping_sub(dx)),
-                    h: (y0.wrapping_sub(dy), y1.wrapping_sub(dy)),
-                }
-            },
-        )
+        self.source.borders().map(|b| b.shift(self.offset))
     }
-}
 
-pub struct Scale<S> {
-    source: S,
-    factor: f32,
-    filter: Filter,
+    fn prepare(&mut self, region: Borders) {
+        let (dx, dy) = self.offset;
+        self.source
+            .prepare(region.shift((dx.wrapping_neg(), dy.wrapping_neg())));
+    }
+
+    fn reseed(&mut self, seed: u32) {
+        self.source.reseed(seed);
+    }
+
+    fn source_row(&self, y: i32, xs: Range<i32>, out: &mut [Color]) {
+        let (dx, dy) = self.offset;
+        let xs = xs.start.wrapping_sub(dx)..xs.end.wrapping_sub(dx);
+        self.source.source_row(y.wrapping_sub(dy), xs, out);
+    }
 }
 
+/// Scales a source around the origin.
+///
+/// This is a [`Mapped`] source with an [`Affine::scale`] transform,
+/// except that a pixel `x` samples the source at `x / factor`, at its corner
+/// rather than its center.
+pub struct Scale<S>(Mapped<S, Affine>);
+
 impl<S> Scale<S> {
     /// The [`Scale`] constructor.
     ///
     /// # Panics
     /// Panics when a `factor` is less than or equal to zero.
     pub fn new(source: S, factor: f32, filter: Filter) -> Self {
-        assert!(
-            factor > f32::EPSILON,
-            "factor cannot be less than or equal to zero"
-        );
+        Self(Mapped::new(source, Affine::scale(factor)).filter(filter))
+    }
 
-        Self {
-            source,
-            factor: 1. / factor,
-            filter,
-        }
+    /// Sets the space the linear filter interpolates in. Defaults to [`LerpSpace::Srgb`].
+    ///
+    /// Other spaces sample the source directly, without its
+    /// [`sample_scaled`](Source::sample_scaled) implementation.
+    pub fn space(self, space: LerpSpace) -> Self {
+        Self(self.0.space(space))
+    }
+
+    /// Applies the scale first, then the `next` transform, as a single matrix.
+    ///
+    /// The result samples at pixel centers, like other mapped sources.
+    pub fn transform(self, next: Affine) -> Mapped<S, Affine> {
+        self.0.transform(next)
     }
 }
 
@@ -169,76 +887,110 @@ where
     S: Source,
 {
     fn source(&self, (x, y): (i32, i32)) -> Color {
-        use std::cmp::Ordering;
-
-        fn linear_points(v: f32) -> (i32, i32, f32) {
-            let a = v as i32;
-            let f = v.abs().fract();
-            match f.total_cmp(&0.5) {
-                Ordering::Less => (a, a.wrapping_sub(1), 0.5 - f),
-                Ordering::Equal => (a, a, 0.),
-                Ordering::Greater => (a, a.wrapping_add(1), f - 0.5),
+        self.0.sample((x as f32, y as f32))
+    }
+
+    fn prepare(&mut self, region: Borders) {
+        // Linear filtering reads one more pixel on every side
+        let factor = self.0.mapping.footprint();
+        let map = |v: i32| (factor * v as f32).floor() as i32;
+        self.0.source.prepare(
+            Borders {
+                w: (map(region.w.0), map(region.w.1)),
+                h: (map(region.h.0), map(region.h.1)),
             }
+            .expand(1),
+        );
+    }
+
+    fn borders(&self) -> Option<Borders> {
+        // A sample reads source pixels around the floored position: one for
+        // the nearest filter and two for the linear one. Minified sources may
+        // sample a downscaled level, which reads as far as the footprint times more
+        let reach = match self.0.filter {
+            Filter::Near => 1.,
+            Filter::Linear => 2.,
+        };
+
+        let factor = self.0.mapping.footprint();
+        let pad = reach * factor.max(1.);
+        let map = |(a, b): (i32, i32)| {
+            (
+                ((a as f32 - pad) / factor).floor() as _,
+                ((b as f32 + pad) / factor).ceil() as _,
+            )
+        };
+
+        self.0.source.borders().map(|Borders { w, h }| Borders {
+            w: map(w),
+            h: map(h),
+        })
+    }
+
+    fn reseed(&mut self, seed: u32) {
+        self.0.reseed(seed);
+    }
+}
+
+/// Samples a source at a fractional position with the given filter.
+fn sample<S>(source: &S, filter: Filter, pos: (f32, f32)) -> Color
+where
+    S: Source + ?Sized,
+{
+    sample_in(source, filter, LerpSpace::Srgb, pos)
+}
+
+/// Samples a source like [`sample`], interpolating in the given space.
+fn sample_in<S>(source: &S, filter: Filter, space: LerpSpace, (x, y): (f32, f32)) -> Color
+where
+    S: Source + ?Sized,
+{
+    use core::cmp::Ordering;
+
+    fn linear_points(v: f32) -> (i32, i32, f32) {
+        let a = v.floor() as i32;
+        let f = v - v.floor();
+        match f.total_cmp(&0.5) {
+            Ordering::Less => (a, a.wrapping_sub(1), 0.5 - f),
+            Ordering::Equal => (a, a, 0.),
+            Ordering::Greater => (a, a.wrapping_add(1), f - 0.5),
         }
+    }
+
+    match filter {
+        Filter::Near => source.source((x.floor() as _, y.floor() as _)),
+        Filter::Linear => {
+            let (x0, x1, xt) = linear_points(x);
+            let (y0, y1, yt) = linear_points(y);
 
-        let x = self.factor * x as f32;
-        let y = self.factor * y as f32;
-
-        match self.filter {
-            Filter::Near => self.source.source((x as _, y as _)),
-            Filter::Linear => {
-                let (x0, x1, xt) = linear_points(x);
-                let (y0, y1, yt) = linear_points(y);
-
-                match (x0 == x1, y0 == y1) {
-                    (true, true) => self.source.source((x0, y0)),
-                    (false, true) => {
-                        let a = self.source.source((x0, y0));
-                        let b = self.source.source((x1, y0));
-                        a.lerp(b, xt)
-                    }
-                    (true, false) => {
-                        let a = self.source.source((x0, y0));
-                        let b = self.source.source((x0, y1));
-                        a.lerp(b, yt)
-                    }
-                    (false, false) => {
-                        let c0 = self
-                            .source
-                            .source((x0, y0))
-                            .lerp(self.source.source((x1, y0)), xt);
-
-                        let c1 = self
-                            .source
-                            .source((x0, y1))
-                            .lerp(self.source.source((x1, y1)), xt);
-
-                        c0.lerp(c1, yt)
-                    }
+            match (x0 == x1, y0 == y1) {
+                (true, true) => source.source((x0, y0)),
+                (false, true) => {
+                    let a = source.source((x0, y0));
+                    let b = source.source((x1, y0));
+                    a.lerp_in(b, xt, space)
+                }
+                (true, false) => {
+                    let a = source.source((x0, y0));
+                    let b = source.source((x0, y1));
+                    a.lerp_in(b, yt, space)
+                }
+                (false, false) => {
+                    let c0 = source
+                        .source((x0, y0))
+                        .lerp_in(source.source((x1, y0)), xt, space);
+                    let c1 = source
+                        .source((x0, y1))
+                        .lerp_in(source.source((x1, y1)), xt, space);
+
+                    c0.lerp_in(c1, yt, space)
                 }
             }
         }
     }
-
-    fn borders(&self) -> Option<Borders> {
-        self.source.borders().map(
-            |Borders {
-                 w: (x0, x1),
-                 h: (y0, y1),
-             }| Borders {
-                w: (
-                    (x0 as f32 / self.factor) as _,
-                    (x1 as f32 / self.factor) as _,
-                ),
-                h: (
-                    (y0 as f32 / self.factor) as _,
-                    (y1 as f32 / self.factor) as _,
-                ),
-            },
-        )
-    }
 }
 
+#[derive(Clone, Copy)]
 pub enum Filter {
     Near,
     Linear,
@@ -247,6 +999,42 @@ pub enum Filter {
 pub struct Blur<S> {
     source: S,
     radius: i32,
+    weighted: bool,
+    baked: Option<Baked>,
+}
+
+/// Source pixels read from memory instead of sampling them again.
+struct Baked {
+    area: Borders,
+    pixels: Vec<Color>,
+}
+
+impl Baked {
+    /// The most pixels baked, larger regions are sampled as is.
+    const LIMIT: u64 = 1 << 24;
+
+    fn get(&self, (x, y): (i32, i32)) -> Option<Color> {
+        if !self.area.contains((x, y)) {
+            return None;
+        }
+
+        let (w, _) = self.area.size();
+        let (dx, dy) = (x - self.area.w.0, y - self.area.h.0);
+        Some(self.pixels[dy as usize * w as usize + dx as usize])
+    }
+
+    fn row(&self, y: i32, xs: Range<i32>) -> Option<&[Color]> {
+        if xs.is_empty()
+            || !self.area.contains((xs.start, y))
+            || !self.area.contains((xs.end - 1, y))
+        {
+            return None;
+        }
+
+        let (w, _) = self.area.size();
+        let start = (y - self.area.h.0) as usize * w as usize + (xs.start - self.area.w.0) as usize;
+        Some(&self.pixels[start..start + xs.len()])
+    }
 }
 
 impl<S> Blur<S> {
@@ -254,6 +1042,65 @@ impl<S> Blur<S> {
         Self {
             source,
             radius: radius as i32,
+            weighted: true,
+            baked: None,
+        }
+    }
+
+    /// Sets whether colors are weighted by their alpha. Enabled by default.
+    ///
+    /// Weighted colors of fully transparent pixels don't contribute, so blurred
+    /// edges over transparency keep their color instead of fading to black.
+    pub fn weighted(mut self, weighted: bool) -> Self {
+        self.weighted = weighted;
+        self
+    }
+
+    /// Reads a source pixel, from baked pixels when they cover it.
+    fn read(&self, pos: (i32, i32)) -> Color
+    where
+        S: Source,
+    {
+        match self.baked.as_ref().and_then(|baked| baked.get(pos)) {
+            Some(col) => col,
+            None => self.source.source(pos),
+        }
+    }
+
+    /// Reads a source row, from baked pixels when they cover it.
+    fn read_row(&self, y: i32, xs: Range<i32>, out: &mut [Color])
+    where
+        S: Source,
+    {
+        match self
+            .baked
+            .as_ref()
+            .and_then(|baked| baked.row(y, xs.clone()))
+        {
+            Some(row) => out.copy_from_slice(row),
+            None => self.source.source_row(y, xs, out),
+        }
+    }
+
+    /// Returns the average color of the sums of `i` samples.
+    fn average(&self, [r, g, b, a]: [f64; 4], i: i32) -> Color {
+        if i == 0 {
+            return Color::default();
+        }
+
+        // Weighted sums hold premultiplied colors, so they're divided by the alpha sum
+        let k = 1. / i as f64;
+        let c = match self.weighted {
+            true if a > f64::EPSILON => 1. / a,
+            true => 0.,
+            false => k,
+        };
+
+        Color {
+            r: (r * c) as f32,
+            g: (g * c) as f32,
+            b: (b * c) as f32,
+            a: (a * k) as f32,
         }
     }
 }
@@ -263,31 +1110,85 @@ where
     S: Source,
 {
     fn source(&self, (x, y): (i32, i32)) -> Color {
-        let mut col = Color::default();
-
         if let Some(borders) = self.borders() {
             if !borders.contains((x, y)) {
-                return col;
+                return Color::default();
             }
         }
 
         let radius = self.radius;
         let rsqr = radius * radius;
+        let mut sum = [0.; 4];
         let mut i = 0;
         for dy in -radius..radius {
             for dx in -radius..radius {
                 if dx * dx + dy * dy < rsqr {
-                    col += self.source.source((x + dx, y + dy));
+                    let col = self.read((x + dx, y + dy));
+                    let w = if self.weighted { col.a as f64 } else { 1. };
+                    sum[0] += col.r as f64 * w;
+                    sum[1] += col.g as f64 * w;
+                    sum[2] += col.b as f64 * w;
+                    sum[3] += col.a as f64;
                     i += 1;
                 }
             }
         }
 
-        if i > 0 {
-            col *= 1. / i as f32;
+        self.average(sum, i)
+    }
+
+    /// Blurs the row with a sliding window.
+    ///
+    /// Every row of the disk is a span of the source row, so it's summed with
+    /// prefix sums in constant time, giving O(r) work per pixel instead of O(r²).
+    fn source_row(&self, y: i32, xs: Range<i32>, out: &mut [Color]) {
+        let radius = self.radius;
+        let rsqr = radius * radius;
+        let span = xs.start.wrapping_sub(radius)..xs.end.wrapping_add(radius);
+        let mut line = vec![Color::default(); span.len()];
+        let mut prefix = vec![[0.; 4]; span.len() + 1];
+        let mut sums = vec![[0.; 4]; out.len()];
+        let mut i = 0;
+        for dy in -radius..radius {
+            let rest = rsqr - dy * dy;
+            if rest <= 0 {
+                continue;
+            }
+
+            // The disk row is `dx * dx < rest` limited to `-radius..radius`
+            let half = (rest - 1).isqrt();
+            let (lo, hi) = (radius - half, radius + half.min(radius - 1) + 1);
+            i += hi - lo;
+
+            self.read_row(y + dy, span.clone(), &mut line);
+            for (n, col) in line.iter().enumerate() {
+                let [r, g, b, a] = prefix[n];
+                let w = if self.weighted { col.a as f64 } else { 1. };
+                prefix[n + 1] = [
+                    r + col.r as f64 * w,
+                    g + col.g as f64 * w,
+                    b + col.b as f64 * w,
+                    a + col.a as f64,
+                ];
+            }
+
+            for (n, sum) in sums.iter_mut().enumerate() {
+                let (a, b) = (prefix[n + lo as usize], prefix[n + hi as usize]);
+                for c in 0..4 {
+                    sum[c] += b[c] - a[c];
+                }
+            }
         }
 
-        col
+        let borders = self.borders();
+        for ((x, col), sum) in xs.zip(out).zip(sums) {
+            let outside = borders.is_some_and(|borders| !borders.contains((x, y)));
+            *col = if outside {
+                Color::default()
+            } else {
+                self.average(sum, i)
+            };
+        }
     }
 
     fn borders(&self) -> Option<Borders> {
@@ -301,4 +1202,37 @@ where
             },
         )
     }
+
+    /// Bakes the source region the blur reads, since every source pixel
+    /// is read by all rows of the disk covering it.
+    fn prepare(&mut self, region: Borders) {
+        let mut area = region.expand(self.radius);
+        self.source.prepare(area);
+        if let Some(borders) = self.source.borders() {
+            area = Borders {
+                w: (area.w.0.max(borders.w.0), area.w.1.min(borders.w.1)),
+                h: (area.h.0.max(borders.h.0), area.h.1.min(borders.h.1)),
+            };
+        }
+
+        self.baked = None;
+        let (w, h) = area.size();
+        if w == 0 || h == 0 || w as u64 * h as u64 > Baked::LIMIT {
+            return;
+        }
+
+        let mut pixels = vec![Color::default(); w as usize * h as usize];
+        let xs = area.w.0..area.w.0 + w as i32;
+        for (y, row) in (area.h.0..).zip(pixels.chunks_mut(w as usize)) {
+            self.source.source_row(y, xs.clone(), row);
+        }
+
+        self.baked = Some(Baked { area, pixels });
+    }
+
+    /// Reseeds the source and drops the baked pixels.
+    fn reseed(&mut self, seed: u32) {
+        self.source.reseed(seed);
+        self.baked = None;
+    }
 }
@@ -0,0 +1,409 @@
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::Float;
+use {
+    crate::{
+        math::{lerp, smoothstep},
+        source::{Borders, Source},
+        Color, LerpSpace,
+    },
+    alloc::{vec, vec::Vec},
+    core::ops::Range,
+};
+
+/// Scales color channels to neutralize a color cast.
+pub struct WhiteBalance<S> {
+    source: S,
+    gains: Color,
+}
+
+impl<S> WhiteBalance<S>
+where
+    S: Source,
+{
+    /// The [`WhiteBalance`] constructor.
+    ///
+    /// # Panics
+    /// Panics when the [`Balance::GrayWorld`] mode is used with a source with no borders.
+    pub fn new(source: S, mode: Balance) -> Self {
+        let neutral = match mode {
+            Balance::GrayWorld => {
+                let borders = source.borders().expect("the source must have borders");
+                let mut sum = Color::default();
+                let mut n = 0.;
+                for pos in borders.positions() {
+                    let col = source.source(pos);
+                    if col.is_visible() {
+                        sum += col * col.a;
+                        n += col.a;
+                    }
+                }
+
+                if n > 0. {
+                    sum * (1. / n)
+                } else {
+                    sum
+                }
+            }
+            Balance::Point(pos) => source.source(pos),
+            Balance::Neutral(col) => col,
+        };
+
+        let gray = (neutral.r + neutral.g + neutral.b) / 3.;
+        let gain = |v: f32| if v > f32::EPSILON { gray / v } else { 1. };
+        let gains = Color {
+            r: gain(neutral.r),
+            g: gain(neutral.g),
+            b: gain(neutral.b),
+            a: 1.,
+        };
+
+        Self { source, gains }
+    }
+}
+
+impl<S> Source for WhiteBalance<S>
+where
+    S: Source,
+{
+    fn source(&self, pos: (i32, i32)) -> Color {
+        (self.source.source(pos) * self.gains).clamp()
+    }
+
+    fn borders(&self) -> Option<Borders> {
+        self.source.borders()
+    }
+
+    fn prepare(&mut self, region: Borders) {
+        self.source.prepare(region);
+    }
+
+    fn reseed(&mut self, seed: u32) {
+        self.source.reseed(seed);
+    }
+}
+
+pub enum Balance {
+    /// Assumes the average color of the source over its borders is gray.
+    GrayWorld,
+
+    /// Makes the source color at the given position neutral.
+    Point((i32, i32)),
+
+    /// Makes the given color neutral.
+    Neutral(Color),
+}
+
+/// Shifts colors as if the scene was lit by a light of the given temperature.
+pub struct Temperature<S> {
+    source: S,
+    gains: Color,
+}
+
+impl<S> Temperature<S> {
+    /// The [`Temperature`] constructor.
+    ///
+    /// A `kelvin` of 6500 is neutral, lower values warm the image up and higher values cool it down.
+    /// A `tint` in range `-1..=1` shifts colors toward green when negative and toward magenta when positive.
+    ///
+    /// # Panics
+    /// Panics when a `kelvin` is not in range `1000..=40000`.
+    pub fn new(source: S, kelvin: f32, tint: f32) -> Self {
+        const NEUTRAL: f32 = 6500.;
+        const TINT_STRENGTH: f32 = 0.25;
+
+        assert!(
+            (1000. ..=40000.).contains(&kelvin),
+            "kelvin must be in range 1000..=40000",
+        );
+
+        let light = blackbody(kelvin);
+        let neutral = blackbody(NEUTRAL);
+        let mut gains = Color {
+            r: light.r / neutral.r,
+            g: light.g / neutral.g * (1. - tint.clamp(-1., 1.) * TINT_STRENGTH),
+            b: light.b / neutral.b,
+            a: 1.,
+        };
+
+        // Keep the overall brightness
+        let luma = gains.luminance();
+        if luma > f32::EPSILON {
+            gains *= 1. / luma;
+            gains.a = 1.;
+        }
+
+        Self { source, gains }
+    }
+}
+
+impl<S> Source for Temperature<S>
+where
+    S: Source,
+{
+    fn source(&self, pos: (i32, i32)) -> Color {
+        (self.source.source(pos) * self.gains).clamp()
+    }
+
+    fn borders(&self) -> Option<Borders> {
+        self.source.borders()
+    }
+
+    fn prepare(&mut self, region: Borders) {
+        self.source.prepare(region);
+    }
+
+    fn reseed(&mut self, seed: u32) {
+        self.source.reseed(seed);
+    }
+}
+
+/// Approximates the color of a black body radiator.
+fn blackbody(kelvin: f32) -> Color {
+    let t = kelvin / 100.;
+    let r = if t <= 66. {
+        255.
+    } else {
+        329.69873 * (t - 60.).powf(-0.13320476)
+    };
+
+    let g = if t <= 66. {
+        99.4708 * t.ln() - 161.11957
+    } else {
+        288.12216 * (t - 60.).powf(-0.07551485)
+    };
+
+    let b = if t >= 66. {
+        255.
+    } else if t <= 19. {
+        0.
+    } else {
+        138.51773 * (t - 10.).ln() - 305.0448
+    };
+
+    Color {
+        r: r.clamp(1., 255.) / 255.,
+        g: g.clamp(1., 255.) / 255.,
+        b: b.clamp(1., 255.) / 255.,
+        a: 1.,
+    }
+}
+
+/// Interpolates a color at the position of sorted non-empty stops.
+pub(super) fn ramp(stops: &[(f32, Color)], t: f32, space: LerpSpace) -> Color {
+    let idx = stops.partition_point(|&(p, _)| p <= t);
+    match (idx.checked_sub(1).map(|i| stops[i]), stops.get(idx)) {
+        (Some((p0, c0)), Some(&(p1, c1))) => c0.lerp_in(c1, (t - p0) / (p1 - p0), space),
+        (Some((_, c)), None) | (None, Some(&(_, c))) => c,
+        (None, None) => unreachable!("stops cannot be empty"),
+    }
+}
+
+/// Maps the luminance of a source through a color ramp.
+pub struct GradientMap<S> {
+    source: S,
+    stops: Vec<(f32, Color)>,
+    space: LerpSpace,
+}
+
+impl<S> GradientMap<S> {
+    /// The [`GradientMap`] constructor.
+    ///
+    /// Each stop is a luminance position in range `0..=1` paired with its color.
+    /// Stops are sorted by position.
+    ///
+    /// # Panics
+    /// Panics when `stops` is empty.
+    pub fn new(source: S, mut stops: Vec<(f32, Color)>) -> Self {
+        assert!(!stops.is_empty(), "stops cannot be empty");
+        stops.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+        Self {
+            source,
+            stops,
+            space: LerpSpace::Srgb,
+        }
+    }
+
+    /// Sets the space stops are interpolated in. Defaults to [`LerpSpace::Srgb`].
+    pub fn space(mut self, space: LerpSpace) -> Self {
+        self.space = space;
+        self
+    }
+
+    /// Maps shadows to the first color and highlights to the second one.
+    pub fn duotone(source: S, shadows: Color, highlights: Color) -> Self {
+        Self::new(source, vec![(0., shadows), (1., highlights)])
+    }
+
+    /// Maps shadows, midtones and highlights to the given colors.
+    pub fn tritone(source: S, shadows: Color, midtones: Color, highlights: Color) -> Self {
+        Self::new(
+            source,
+            vec![(0., shadows), (0.5, midtones), (1., highlights)],
+        )
+    }
+
+    fn map(&self, col: Color) -> Color {
+        let res = ramp(&self.stops, col.luminance(), self.space);
+        Color {
+            a: res.a * col.a,
+            ..res
+        }
+    }
+}
+
+impl<S> Source for GradientMap<S>
+where
+    S: Source,
+{
+    fn source(&self, pos: (i32, i32)) -> Color {
+        self.map(self.source.source(pos))
+    }
+
+    fn borders(&self) -> Option<Borders> {
+        self.source.borders()
+    }
+
+    fn source_row(&self, y: i32, xs: Range<i32>, out: &mut [Color]) {
+        self.source.source_row(y, xs, out);
+        for col in out {
+            *col = self.map(*col);
+        }
+    }
+
+    fn prepare(&mut self, region: Borders) {
+        self.source.prepare(region);
+    }
+
+    fn reseed(&mut self, seed: u32) {
+        self.source.reseed(seed);
+    }
+}
+
+/// Inverts channel values above a threshold, like the darkroom solarization.
+pub struct Solarize<S> {
+    source: S,
+    threshold: f32,
+    smooth: f32,
+}
+
+impl<S> Solarize<S> {
+    pub fn new(source: S, threshold: f32) -> Self {
+        Self {
+            source,
+            threshold: threshold.clamp(0., 1.),
+            smooth: 0.,
+        }
+    }
+
+    /// Sets the width of a smooth transition around the threshold. Defaults to `0`.
+    pub fn smooth(mut self, width: f32) -> Self {
+        self.smooth = width.max(0.);
+        self
+    }
+
+    fn channel(&self, v: f32) -> f32 {
+        let half = self.smooth * 0.5;
+        let t = smoothstep(self.threshold - half, self.threshold + half, v);
+        lerp(v, 1. - v, t)
+    }
+}
+
+impl<S> Source for Solarize<S>
+where
+    S: Source,
+{
+    fn source(&self, pos: (i32, i32)) -> Color {
+        let col = self.source.source(pos);
+        Color {
+            r: self.channel(col.r),
+            g: self.channel(col.g),
+            b: self.channel(col.b),
+            a: col.a,
+        }
+    }
+
+    fn borders(&self) -> Option<Borders> {
+        self.source.borders()
+    }
+
+    fn prepare(&mut self, region: Borders) {
+        self.source.prepare(region);
+    }
+
+    fn reseed(&mut self, seed: u32) {
+        self.source.reseed(seed);
+    }
+}
+
+/// Shows a single channel of a source as a grayscale plate.
+///
+/// Color channels are shown as their value, ink channels of the naive
+/// [CMYK conversion](Color::to_cmyk) as a printed plate, darker where there is more ink.
+/// The alpha of the source is kept, except for the [`Channel::Alpha`] plate.
+pub struct Channels<S> {
+    source: S,
+    channel: Channel,
+}
+
+impl<S> Channels<S> {
+    pub fn new(source: S, channel: Channel) -> Self {
+        Self { source, channel }
+    }
+}
+
+impl<S> Source for Channels<S>
+where
+    S: Source,
+{
+    fn source(&self, pos: (i32, i32)) -> Color {
+        let col = self.source.source(pos);
+        let [c, m, y, k] = col.to_cmyk();
+        let (v, a) = match self.channel {
+            Channel::Red => (col.r, col.a),
+            Channel::Green => (col.g, col.a),
+            Channel::Blue => (col.b, col.a),
+            Channel::Alpha => (col.a, 1.),
+            Channel::Cyan => (1. - c, col.a),
+            Channel::Magenta => (1. - m, col.a),
+            Channel::Yellow => (1. - y, col.a),
+            Channel::Key => (1. - k, col.a),
+        };
+
+        Color {
+            r: v,
+            g: v,
+            b: v,
+            a,
+        }
+    }
+
+    /// The [`Channel::Alpha`] plate is opaque everywhere, so it has no borders.
+    fn borders(&self) -> Option<Borders> {
+        match self.channel {
+            Channel::Alpha => None,
+            _ => self.source.borders(),
+        }
+    }
+
+    fn prepare(&mut self, region: Borders) {
+        self.source.prepare(region);
+    }
+
+    fn reseed(&mut self, seed: u32) {
+        self.source.reseed(seed);
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Channel {
+    Red,
+    Green,
+    Blue,
+    Alpha,
+    Cyan,
+    Magenta,
+    Yellow,
+
+    /// The black ink.
+    Key,
+}
@@ -0,0 +1,368 @@
+use {
+    crate::{
+        source::{Borders, Source},
+        Color,
+    },
+    alloc::{vec, vec::Vec},
+};
+
+/// A linear barcode rendered as vertical bars.
+pub struct Barcode {
+    modules: Vec<bool>,
+    module: u32,
+    height: u32,
+    quiet: u32,
+    dark: Color,
+    light: Color,
+}
+
+impl Barcode {
+    /// Encodes printable ASCII text as a Code 128 barcode.
+    ///
+    /// Runs of digits are packed in pairs to keep the code short.
+    ///
+    /// # Errors
+    /// Returns a [`BarcodeError::InvalidChar`] if the text has a non printable ASCII character
+    /// or a [`BarcodeError::InvalidLength`] if the text is empty.
+    pub fn code128(text: &str) -> Result<Self, BarcodeError> {
+        const CODE_B: u8 = 100;
+        const CODE_C: u8 = 99;
+        const START_B: u8 = 104;
+        const START_C: u8 = 105;
+
+        if let Some(c) = text.chars().find(|c| !matches!(c, ' '..='~')) {
+            return Err(BarcodeError::InvalidChar(c));
+        }
+
+        let bytes = text.as_bytes();
+        if bytes.is_empty() {
+            return Err(BarcodeError::InvalidLength);
+        }
+
+        let digits = |from: usize| {
+            bytes[from..]
+                .iter()
+                .take_while(|b| b.is_ascii_digit())
+                .count()
+        };
+
+        let start_c = digits(0) >= 4 || digits(0) == bytes.len() && bytes.len().is_multiple_of(2);
+        let mut values = vec![if start_c { START_C } else { START_B }];
+        let mut packed = start_c;
+        let mut i = 0;
+        while i < bytes.len() {
+            if packed {
+                if digits(i) >= 2 {
+                    values.push((bytes[i] - b'0') * 10 + bytes[i + 1] - b'0');
+                    i += 2;
+                    continue;
+                }
+
+                values.push(CODE_B);
+                packed = false;
+            } else if digits(i) >= 4 {
+                // Pack an even number of digits, an odd leading one stays in Code B
+                if !digits(i).is_multiple_of(2) {
+                    values.push(bytes[i] - b' ');
+                    i += 1;
+                }
+
+                values.push(CODE_C);
+                packed = true;
+            } else {
+                values.push(bytes[i] - b' ');
+                i += 1;
+            }
+        }
+
+        let checksum = values
+            .iter()
+            .enumerate()
+            .map(|(n, &v)| n.max(1) as u32 * v as u32)
+            .sum::<u32>()
+            % 103;
+
+        values.push(checksum as u8);
+
+        let mut modules = vec![];
+        for v in values {
+            push_widths(&mut modules, &CODE128[v as usize], true);
+        }
+
+        push_widths(&mut modules, &CODE128_STOP, true);
+        Ok(Self::new(modules, 10))
+    }
+
+    /// Encodes digits as an EAN-13 barcode.
+    ///
+    /// The check digit is computed when 12 digits are given
+    /// and validated when all 13 digits are given.
+    ///
+    /// # Errors
+    /// Returns a [`BarcodeError::InvalidChar`] if the text has a non digit character,
+    /// a [`BarcodeError::InvalidLength`] if there are not 12 or 13 digits
+    /// or a [`BarcodeError::Checksum`] if the check digit is wrong.
+    pub fn ean13(digits: &str) -> Result<Self, BarcodeError> {
+        if let Some(c) = digits.chars().find(|c| !c.is_ascii_digit()) {
+            return Err(BarcodeError::InvalidChar(c));
+        }
+
+        let mut digits: Vec<_> = digits.bytes().map(|b| b - b'0').collect();
+        let sum: u32 = digits
+            .iter()
+            .take(12)
+            .enumerate()
+            .map(|(i, &d)| if i % 2 == 0 { d as u32 } else { d as u32 * 3 })
+            .sum();
+
+        let check = ((10 - sum % 10) % 10) as u8;
+        match digits.len() {
+            12 => digits.push(check),
+            13 if digits[12] == check => {}
+            13 => return Err(BarcodeError::Checksum),
+            _ => return Err(BarcodeError::InvalidLength),
+        }
+
+        let mut modules = vec![];
+        push_widths(&mut modules, &[1, 1, 1], true);
+
+        let parity = EAN_PARITY[digits[0] as usize];
+        for (i, &d) in digits[1..7].iter().enumerate() {
+            let widths = EAN_DIGITS[d as usize];
+            if parity & (0b100000 >> i) == 0 {
+                push_widths(&mut modules, &widths, false);
+            } else {
+                let [a, b, c, d] = widths;
+                push_widths(&mut modules, &[d, c, b, a], false);
+            }
+        }
+
+        push_widths(&mut modules, &[1, 1, 1, 1, 1], false);
+        for &d in &digits[7..] {
+            push_widths(&mut modules, &EAN_DIGITS[d as usize], true);
+        }
+
+        push_widths(&mut modules, &[1, 1, 1], true);
+        Ok(Self::new(modules, 11))
+    }
+
+    fn new(modules: Vec<bool>, quiet: u32) -> Self {
+        Self {
+            modules,
+            module: 2,
+            height: 64,
+            quiet,
+            dark: Color::from_u32(0x000000FF),
+            light: Color::from_u32(0xFFFFFFFF),
+        }
+    }
+
+    /// Sets the width of the narrowest bar in pixels. Defaults to `2`.
+    ///
+    /// # Panics
+    /// Panics when a `size` is zero.
+    pub fn module(mut self, size: u32) -> Self {
+        assert!(size > 0, "module size cannot be zero");
+        self.module = size;
+        self
+    }
+
+    /// Sets the bar height in pixels. Defaults to `64`.
+    pub fn height(mut self, height: u32) -> Self {
+        self.height = height;
+        self
+    }
+
+    /// Sets the width of the quiet zone on both sides in modules.
+    /// Defaults to the minimum of the symbology, which is `10` for Code 128 and `11` for EAN-13.
+    pub fn quiet_zone(mut self, modules: u32) -> Self {
+        self.quiet = modules;
+        self
+    }
+
+    /// Sets the bar colors. Defaults to black on white.
+    pub fn colors(mut self, dark: Color, light: Color) -> Self {
+        self.dark = dark;
+        self.light = light;
+        self
+    }
+
+    /// Returns the size of the code with the quiet zone in pixels.
+    pub fn size(&self) -> (u32, u32) {
+        let modules = self.modules.len() as u32 + self.quiet * 2;
+        (modules * self.module, self.height)
+    }
+}
+
+impl Source for Barcode {
+    fn source(&self, (x, y): (i32, i32)) -> Color {
+        let (w, h) = self.size();
+        match (u32::try_from(x), u32::try_from(y)) {
+            (Ok(x), Ok(y)) if x < w && y < h => {
+                let m = (x / self.module).wrapping_sub(self.quiet) as usize;
+                if self.modules.get(m).copied().unwrap_or_default() {
+                    self.dark
+                } else {
+                    self.light
+                }
+            }
+            _ => Color::default(),
+        }
+    }
+
+    fn borders(&self) -> Option<Borders> {
+        let (w, h) = self.size();
+        Some(Borders {
+            w: (0, w as i32 - 1),
+            h: (0, h as i32 - 1),
+        })
+    }
+}
+
+#[derive(Debug)]
+pub enum BarcodeError {
+    InvalidChar(char),
+    InvalidLength,
+    Checksum,
+}
+
+/// Pushes alternating bars and spaces of the given widths.
+fn push_widths(modules: &mut Vec<bool>, widths: &[u8], bar_first: bool) {
+    let mut bar = bar_first;
+    for &w in widths {
+        modules.extend((0..w).map(|_| bar));
+        bar = !bar;
+    }
+}
+
+/// Bar and space widths of Code 128 symbols by value.
+const CODE128: [[u8; 6]; 106] = [
+    [2, 1, 2, 2, 2, 2],
+    [2, 2, 2, 1, 2, 2],
+    [2, 2, 2, 2, 2, 1],
+    [1, 2, 1, 2, 2, 3],
+    [1, 2, 1, 3, 2, 2],
+    [1, 3, 1, 2, 2, 2],
+    [1, 2, 2, 2, 1, 3],
+    [1, 2, 2, 3, 1, 2],
+    [1, 3, 2, 2, 1, 2],
+    [2, 2, 1, 2, 1, 3],
+    [2, 2, 1, 3, 1, 2],
+    [2, 3, 1, 2, 1, 2],
+    [1, 1, 2, 2, 3, 2],
+    [1, 2, 2, 1, 3, 2],
+    [1, 2, 2, 2, 3, 1],
+    [1, 1, 3, 2, 2, 2],
+    [1, 2, 3, 1, 2, 2],
+    [1, 2, 3, 2, 2, 1],
+    [2, 2, 3, 2, 1, 1],
+    [2, 2, 1, 1, 3, 2],
+    [2, 2, 1, 2, 3, 1],
+    [2, 1, 3, 2, 1, 2],
+    [2, 2, 3, 1, 1, 2],
+    [3, 1, 2, 1, 3, 1],
+    [3, 1, 1, 2, 2, 2],
+    [3, 2, 1, 1, 2, 2],
+    [3, 2, 1, 2, 2, 1],
+    [3, 1, 2, 2, 1, 2],
+    [3, 2, 2, 1, 1, 2],
+    [3, 2, 2, 2, 1, 1],
+    [2, 1, 2, 1, 2, 3],
+    [2, 1, 2, 3, 2, 1],
+    [2, 3, 2, 1, 2, 1],
+    [1, 1, 1, 3, 2, 3],
+    [1, 3, 1, 1, 2, 3],
+    [1, 3, 1, 3, 2, 1],
+    [1, 1, 2, 3, 1, 3],
+    [1, 3, 2, 1, 1, 3],
+    [1, 3, 2, 3, 1, 1],
+    [2, 1, 1, 3, 1, 3],
+    [2, 3, 1, 1, 1, 3],
+    [2, 3, 1, 3, 1, 1],
+    [1, 1, 2, 1, 3, 3],
+    [1, 1, 2, 3, 3, 1],
+    [1, 3, 2, 1, 3, 1],
+    [1, 1, 3, 1, 2, 3],
+    [1, 1, 3, 3, 2, 1],
+    [1, 3, 3, 1, 2, 1],
+    [3, 1, 3, 1, 2, 1],
+    [2, 1, 1, 3, 3, 1],
+    [2, 3, 1, 1, 3, 1],
+    [2, 1, 3, 1, 1, 3],
+    [2, 1, 3, 3, 1, 1],
+    [2, 1, 3, 1, 3, 1],
+    [3, 1, 1, 1, 2, 3],
+    [3, 1, 1, 3, 2, 1],
+    [3, 3, 1, 1, 2, 1],
+    [3, 1, 2, 1, 1, 3],
+    [3, 1, 2, 3, 1, 1],
+    [3, 3, 2, 1, 1, 1],
+    [3, 1, 4, 1, 1, 1],
+    [2, 2, 1, 4, 1, 1],
+    [4, 3, 1, 1, 1, 1],
+    [1, 1, 1, 2, 2, 4],
+    [1, 1, 1, 4, 2, 2],
+    [1, 2, 1, 1, 2, 4],
+    [1, 2, 1, 4, 2, 1],
+    [1, 4, 1, 1, 2, 2],
+    [1, 4, 1, 2, 2, 1],
+    [1, 1, 2, 2, 1, 4],
+    [1, 1, 2, 4, 1, 2],
+    [1, 2, 2, 1, 1, 4],
+    [1, 2, 2, 4, 1, 1],
+    [1, 4, 2, 1, 1, 2],
+    [1, 4, 2, 2, 1, 1],
+    [2, 4, 1, 2, 1, 1],
+    [2, 2, 1, 1, 1, 4],
+    [4, 1, 3, 1, 1, 1],
+    [2, 4, 1, 1, 1, 2],
+    [1, 3, 4, 1, 1, 1],
+    [1, 1, 1, 2, 4, 2],
+    [1, 2, 1, 1, 4, 2],
+    [1, 2, 1, 2, 4, 1],
+    [1, 1, 4, 2, 1, 2],
+    [1, 2, 4, 1, 1, 2],
+    [1, 2, 4, 2, 1, 1],
+    [4, 1, 1, 2, 1, 2],
+    [4, 2, 1, 1, 1, 2],
+    [4, 2, 1, 2, 1, 1],
+    [2, 1, 2, 1, 4, 1],
+    [2, 1, 4, 1, 2, 1],
+    [4, 1, 2, 1, 2, 1],
+    [1, 1, 1, 1, 4, 3],
+    [1, 1, 1, 3, 4, 1],
+    [1, 3, 1, 1, 4, 1],
+    [1, 1, 4, 1, 1, 3],
+    [1, 1, 4, 3, 1, 1],
+    [4, 1, 1, 1, 1, 3],
+    [4, 1, 1, 3, 1, 1],
+    [1, 1, 3, 1, 4, 1],
+    [1, 1, 4, 1, 3, 1],
+    [3, 1, 1, 1, 4, 1],
+    [4, 1, 1, 1, 3, 1],
+    [2, 1, 1, 4, 1, 2],
+    [2, 1, 1, 2, 1, 4],
+    [2, 1, 1, 2, 3, 2],
+];
+
+const CODE128_STOP: [u8; 7] = [2, 3, 3, 1, 1, 1, 2];
+
+/// Space and bar widths of EAN left-hand odd parity digits.
+const EAN_DIGITS: [[u8; 4]; 10] = [
+    [3, 2, 1, 1],
+    [2, 2, 2, 1],
+    [2, 1, 2, 2],
+    [1, 4, 1, 1],
+    [1, 1, 3, 2],
+    [1, 2, 3, 1],
+    [1, 1, 1, 4],
+    [1, 3, 1, 2],
+    [1, 2, 1, 3],
+    [3, 1, 1, 2],
+];
+
+/// Even parity positions of the left half by the first digit, the high bit goes first.
+const EAN_PARITY: [u8; 10] = [
+    0b000000, 0b001011, 0b001101, 0b001110, 0b010011, 0b011001, 0b011100, 0b010101, 0b010110,
+    0b011010,
+];
@@ -0,0 +1,184 @@
+use {
+    crate::{
+        source::{Borders, Source},
+        Color,
+    },
+    std::{
+        collections::HashMap,
+        ops::Range,
+        sync::{Arc, Mutex},
+    },
+};
+
+/// Keeps rendered square tiles of a source between renders.
+///
+/// Tiles are rendered on first access and the least recently used ones are dropped
+/// once there are more than `capacity` of them. Panning a view over the cache
+/// with [`Offset`](crate::source::Offset) only renders the tiles that come into view,
+/// after editing the source only the changed region needs to be invalidated.
+pub struct TileCache<S> {
+    source: S,
+    tile: u32,
+    capacity: usize,
+    tiles: Mutex<Tiles>,
+}
+
+#[derive(Default)]
+struct Tiles {
+    map: HashMap<(i32, i32), Tile>,
+    clock: u64,
+}
+
+struct Tile {
+    pixels: Arc<[Color]>,
+    used: u64,
+}
+
+impl<S> TileCache<S> {
+    /// The [`TileCache`] constructor.
+    ///
+    /// The `tile` is a side of a tile in pixels.
+    ///
+    /// # Panics
+    /// Panics when a `tile` or a `capacity` is zero.
+    pub fn new(source: S, tile: u32, capacity: usize) -> Self {
+        assert!(tile > 0, "tile cannot be zero");
+        assert!(capacity > 0, "capacity cannot be zero");
+
+        Self {
+            source,
+            tile,
+            capacity,
+            tiles: Mutex::default(),
+        }
+    }
+
+    /// Returns the source to edit it.
+    ///
+    /// Changed regions must be [invalidated](Self::invalidate) after that.
+    pub fn source_mut(&mut self) -> &mut S {
+        &mut self.source
+    }
+
+    /// Drops the tiles that intersect the region.
+    pub fn invalidate(&self, region: Borders) {
+        let (x0, y0) = self.tile_of((region.w.0, region.h.0));
+        let (x1, y1) = self.tile_of((region.w.1, region.h.1));
+        let mut tiles = self.tiles.lock().expect("lock");
+        tiles
+            .map
+            .retain(|&(x, y), _| x < x0 || x > x1 || y < y0 || y > y1);
+    }
+
+    /// Drops all tiles.
+    pub fn clear(&self) {
+        self.tiles.lock().expect("lock").map.clear();
+    }
+
+    /// Returns the number of cached tiles.
+    pub fn len(&self) -> usize {
+        self.tiles.lock().expect("lock").map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn tile_of(&self, (x, y): (i32, i32)) -> (i32, i32) {
+        let tile = self.tile as i32;
+        (x.div_euclid(tile), y.div_euclid(tile))
+    }
+}
+
+impl<S> TileCache<S>
+where
+    S: Source,
+{
+    fn get(&self, key: (i32, i32)) -> Arc<[Color]> {
+        {
+            let mut tiles = self.tiles.lock().expect("lock");
+            tiles.clock += 1;
+            let clock = tiles.clock;
+            if let Some(tile) = tiles.map.get_mut(&key) {
+                tile.used = clock;
+                return Arc::clone(&tile.pixels);
+            }
+        }
+
+        // Render without holding the lock, so other tiles can be rendered meanwhile
+        let tile = self.tile as i32;
+        let (x0, y0) = (key.0 * tile, key.1 * tile);
+        let mut pixels = vec![Color::default(); (tile * tile) as usize];
+        for (y, row) in (y0..).zip(pixels.chunks_mut(tile as usize)) {
+            self.source.source_row(y, x0..x0 + tile, row);
+        }
+
+        let pixels: Arc<[Color]> = pixels.into();
+        let mut tiles = self.tiles.lock().expect("lock");
+        if tiles.map.len() >= self.capacity && !tiles.map.contains_key(&key) {
+            let oldest = tiles.map.iter().min_by_key(|(_, tile)| tile.used);
+            if let Some((&oldest, _)) = oldest {
+                tiles.map.remove(&oldest);
+            }
+        }
+
+        let used = tiles.clock;
+        let tile = Tile {
+            pixels: Arc::clone(&pixels),
+            used,
+        };
+
+        tiles.map.insert(key, tile);
+        pixels
+    }
+}
+
+impl<S> Source for TileCache<S>
+where
+    S: Source,
+{
+    fn source(&self, pos: (i32, i32)) -> Color {
+        let tile = self.tile as i32;
+        let key = self.tile_of(pos);
+        let (x, y) = (pos.0 - key.0 * tile, pos.1 - key.1 * tile);
+        self.get(key)[(y * tile + x) as usize]
+    }
+
+    fn source_row(&self, y: i32, xs: Range<i32>, out: &mut [Color]) {
+        let tile = self.tile as i32;
+        let mut x = xs.start;
+        let mut out = out;
+        while x < xs.end {
+            let key = self.tile_of((x, y));
+            let (lx, ly) = (x - key.0 * tile, y - key.1 * tile);
+            let n = ((tile - lx) as usize).min(out.len());
+            let pixels = self.get(key);
+            let start = (ly * tile + lx) as usize;
+            let (head, rest) = out.split_at_mut(n);
+            head.copy_from_slice(&pixels[start..start + n]);
+            out = rest;
+            x += n as i32;
+        }
+    }
+
+    fn borders(&self) -> Option<Borders> {
+        self.source.borders()
+    }
+
+    fn prepare(&mut self, region: Borders) {
+        // Tiles are rendered whole
+        let tile = self.tile as i32;
+        let (x0, y0) = self.tile_of((region.w.0, region.h.0));
+        let (x1, y1) = self.tile_of((region.w.1, region.h.1));
+        self.source.prepare(Borders {
+            w: (x0 * tile, (x1 + 1) * tile - 1),
+            h: (y0 * tile, (y1 + 1) * tile - 1),
+        });
+    }
+
+    /// Reseeds the source and drops all tiles rendered with the previous seed.
+    fn reseed(&mut self, seed: u32) {
+        self.source.reseed(seed);
+        self.clear();
+    }
+}
@@ -0,0 +1,344 @@
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::Float;
+use {
+    crate::{
+        source::{Borders, Source},
+        Color,
+    },
+    alloc::vec::Vec,
+};
+
+/// Kernels larger than this number of weights are applied with an FFT
+/// when the `fft` feature is enabled and the source has borders.
+#[cfg(feature = "fft")]
+const FFT_THRESHOLD: usize = 32 * 32;
+
+/// A grid of convolution weights, centered in the middle.
+#[derive(Clone)]
+pub struct Kernel {
+    weights: Vec<f32>,
+    size: (u32, u32),
+}
+
+impl Kernel {
+    /// The [`Kernel`] constructor.
+    ///
+    /// Weights go row by row, the center is at `size / 2`.
+    ///
+    /// # Panics
+    /// Panics when any of `size` is zero or the number of weights doesn't match it.
+    pub fn new(size: (u32, u32), weights: Vec<f32>) -> Self {
+        let (w, h) = size;
+        assert!(w > 0 && h > 0, "kernel size cannot be zero");
+        assert_eq!(
+            weights.len(),
+            w as usize * h as usize,
+            "the number of weights must match the kernel size",
+        );
+
+        Self { weights, size }
+    }
+
+    /// Creates a normalized Gaussian kernel with a radius of three `sigma`.
+    ///
+    /// # Panics
+    /// Panics when a `sigma` is less than or equal to zero.
+    pub fn gaussian(sigma: f32) -> Self {
+        assert!(
+            sigma > f32::EPSILON,
+            "sigma cannot be less than or equal to zero",
+        );
+
+        let radius = (sigma * 3.).ceil() as i32;
+        let side = radius as u32 * 2 + 1;
+        let weights = (-radius..=radius)
+            .flat_map(|y| (-radius..=radius).map(move |x| (x, y)))
+            .map(|(x, y)| (-((x * x + y * y) as f32) / (2. * sigma * sigma)).exp())
+            .collect();
+
+        Self::new((side, side), weights).normalized()
+    }
+
+    /// Creates a normalized disk kernel, which blurs like an out of focus lens.
+    pub fn disk(radius: u32) -> Self {
+        let r = radius as i32;
+        let side = radius * 2 + 1;
+        let rsqr = (r as f32 + 0.5).powi(2);
+        let weights = (-r..=r)
+            .flat_map(|y| (-r..=r).map(move |x| (x, y)))
+            .map(|(x, y)| {
+                if ((x * x + y * y) as f32) < rsqr {
+                    1.
+                } else {
+                    0.
+                }
+            })
+            .collect();
+
+        Self::new((side, side), weights).normalized()
+    }
+
+    /// Scales weights so they sum up to one, if the sum isn't zero.
+    pub fn normalized(mut self) -> Self {
+        let sum: f32 = self.weights.iter().sum();
+        if sum.abs() > f32::EPSILON {
+            for w in &mut self.weights {
+                *w /= sum;
+            }
+        }
+
+        self
+    }
+
+    pub fn size(&self) -> (u32, u32) {
+        self.size
+    }
+
+    fn center(&self) -> (i32, i32) {
+        let (w, h) = self.size;
+        ((w / 2) as i32, (h / 2) as i32)
+    }
+
+    /// Returns borders of a source after the convolution.
+    fn grow(&self, borders: Borders) -> Borders {
+        let (w, h) = self.size;
+        let (cx, cy) = self.center();
+        Borders {
+            w: (
+                borders.w.0.wrapping_sub(w as i32 - 1 - cx),
+                borders.w.1.wrapping_add(cx),
+            ),
+            h: (
+                borders.h.0.wrapping_sub(h as i32 - 1 - cy),
+                borders.h.1.wrapping_add(cy),
+            ),
+        }
+    }
+}
+
+/// Convolves a source with a [`Kernel`].
+///
+/// Each channel is convolved separately. With the `fft` feature large kernels over
+/// bounded sources are applied once, on construction, in the frequency domain.
+pub struct Convolve<S> {
+    source: S,
+    kernel: Kernel,
+    baked: Option<Vec<Color>>,
+}
+
+impl<S> Convolve<S>
+where
+    S: Source,
+{
+    pub fn new(source: S, kernel: Kernel) -> Self {
+        let baked = Self::bake(&source, &kernel);
+        Self {
+            source,
+            kernel,
+            baked,
+        }
+    }
+
+    /// Convolves the whole source in the frequency domain
+    /// when the kernel is large enough.
+    fn bake(source: &S, kernel: &Kernel) -> Option<Vec<Color>> {
+        #[cfg(feature = "fft")]
+        match source.borders() {
+            Some(region) if kernel.weights.len() > FFT_THRESHOLD => {
+                Some(fft::convolve(source, region, kernel))
+            }
+            _ => None,
+        }
+
+        #[cfg(not(feature = "fft"))]
+        {
+            _ = (source, kernel);
+            None
+        }
+    }
+}
+
+impl<S> Source for Convolve<S>
+where
+    S: Source,
+{
+    fn source(&self, (x, y): (i32, i32)) -> Color {
+        if let Some(borders) = self.borders() {
+            if !borders.contains((x, y)) {
+                return Color::default();
+            }
+
+            if let Some(baked) = &self.baked {
+                let (w, _) = borders.size();
+                let idx = (y - borders.h.0) as usize * w as usize + (x - borders.w.0) as usize;
+                return baked[idx];
+            }
+        }
+
+        let (w, h) = self.kernel.size;
+        let (cx, cy) = self.kernel.center();
+        let mut col = Color::default();
+        for j in 0..h as i32 {
+            for i in 0..w as i32 {
+                let k = self.kernel.weights[(j * w as i32 + i) as usize];
+                if k != 0. {
+                    col += self.source.source((x + i - cx, y + j - cy)) * k;
+                }
+            }
+        }
+
+        col
+    }
+
+    fn borders(&self) -> Option<Borders> {
+        self.source.borders().map(|b| self.kernel.grow(b))
+    }
+
+    fn prepare(&mut self, region: Borders) {
+        let (w, h) = self.kernel.size;
+        let (cx, cy) = self.kernel.center();
+        self.source.prepare(Borders {
+            w: (
+                region.w.0.wrapping_sub(cx),
+                region.w.1.wrapping_add(w as i32 - 1 - cx),
+            ),
+            h: (
+                region.h.0.wrapping_sub(cy),
+                region.h.1.wrapping_add(h as i32 - 1 - cy),
+            ),
+        });
+    }
+
+    /// Reseeds the source and convolves it again when it's baked.
+    fn reseed(&mut self, seed: u32) {
+        self.source.reseed(seed);
+        if self.baked.is_some() {
+            self.baked = Self::bake(&self.source, &self.kernel);
+        }
+    }
+}
+
+/// Blurs a source with a Gaussian kernel.
+pub struct GaussianBlur<S>(Convolve<S>);
+
+impl<S> GaussianBlur<S>
+where
+    S: Source,
+{
+    /// The [`GaussianBlur`] constructor.
+    ///
+    /// # Panics
+    /// Panics when a `sigma` is less than or equal to zero.
+    pub fn new(source: S, sigma: f32) -> Self {
+        Self(Convolve::new(source, Kernel::gaussian(sigma)))
+    }
+}
+
+impl<S> Source for GaussianBlur<S>
+where
+    S: Source,
+{
+    fn source(&self, pos: (i32, i32)) -> Color {
+        self.0.source(pos)
+    }
+
+    fn borders(&self) -> Option<Borders> {
+        self.0.borders()
+    }
+
+    fn prepare(&mut self, region: Borders) {
+        self.0.prepare(region);
+    }
+
+    fn reseed(&mut self, seed: u32) {
+        self.0.reseed(seed);
+    }
+}
+
+#[cfg(feature = "fft")]
+mod fft {
+    use {
+        super::Kernel,
+        crate::{
+            source::{Borders, Source},
+            Color,
+        },
+        rustfft::{num_complex::Complex, FftDirection, FftPlanner},
+    };
+
+    /// Convolves the source region with the kernel, returning pixels
+    /// of the grown region row by row.
+    pub(super) fn convolve<S>(source: &S, region: Borders, kernel: &Kernel) -> Vec<Color>
+    where
+        S: Source,
+    {
+        let (sw, sh) = region.size();
+        let (kw, kh) = kernel.size;
+        let (w, h) = ((sw + kw - 1) as usize, (sh + kh - 1) as usize);
+        let mut planner = FftPlanner::new();
+
+        // The kernel is flipped to turn the convolution into the correlation
+        // the direct path computes
+        let mut kern = vec![Complex::default(); w * h];
+        for j in 0..kh as usize {
+            for i in 0..kw as usize {
+                let k = kernel.weights[j * kw as usize + i];
+                kern[(kh as usize - 1 - j) * w + (kw as usize - 1 - i)] = Complex::new(k, 0.);
+            }
+        }
+
+        transform(&mut planner, &mut kern, (w, h), FftDirection::Forward);
+
+        let pixels: Vec<_> = region.positions().map(|pos| source.source(pos)).collect();
+        let mut channels = [(); 4].map(|_| vec![Complex::default(); w * h]);
+        for (n, col) in pixels.iter().enumerate() {
+            let idx = (n / sw as usize) * w + n % sw as usize;
+            for (channel, v) in channels.iter_mut().zip([col.r, col.g, col.b, col.a]) {
+                channel[idx] = Complex::new(v, 0.);
+            }
+        }
+
+        let norm = 1. / (w * h) as f32;
+        for channel in &mut channels {
+            transform(&mut planner, channel, (w, h), FftDirection::Forward);
+            for (v, k) in channel.iter_mut().zip(&kern) {
+                *v *= k * norm;
+            }
+
+            transform(&mut planner, channel, (w, h), FftDirection::Inverse);
+        }
+
+        let [r, g, b, a] = channels;
+        (0..w * h)
+            .map(|n| Color {
+                r: r[n].re,
+                g: g[n].re,
+                b: b[n].re,
+                a: a[n].re,
+            })
+            .collect()
+    }
+
+    /// Applies a 2D transform in place, rows first and then columns.
+    fn transform(
+        planner: &mut FftPlanner<f32>,
+        buf: &mut [Complex<f32>],
+        (w, h): (usize, usize),
+        direction: FftDirection,
+    ) {
+        planner.plan_fft(w, direction).process(buf);
+
+        let mut columns = vec![Complex::default(); w * h];
+        transpose(buf, &mut columns, (w, h));
+        planner.plan_fft(h, direction).process(&mut columns);
+        transpose(&columns, buf, (h, w));
+    }
+
+    fn transpose(from: &[Complex<f32>], to: &mut [Complex<f32>], (w, h): (usize, usize)) {
+        for y in 0..h {
+            for x in 0..w {
+                to[x * h + y] = from[y * w + x];
+            }
+        }
+    }
+}
@@ -0,0 +1,208 @@
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::Float;
+use crate::{
+    source::{Borders, Source},
+    Color,
+};
+
+/// Draws a frame around the source borders.
+///
+/// The frame is painted with another source, a [`Color`] for a solid frame
+/// or any other source for a patterned one.
+pub struct Framed<S, P = Color> {
+    source: S,
+    region: Borders,
+    thickness: f32,
+    paint: P,
+    inside: bool,
+    corner: Corner,
+}
+
+impl<S, P> Framed<S, P>
+where
+    S: Source,
+{
+    /// The [`Framed`] constructor.
+    ///
+    /// The frame is drawn outside of the source borders by default.
+    ///
+    /// # Panics
+    /// Panics when the source has no borders.
+    pub fn new(source: S, thickness: u32, paint: P) -> Self {
+        let region = source.borders().expect("the source must have borders");
+        Self {
+            source,
+            region,
+            thickness: thickness as f32,
+            paint,
+            inside: false,
+            corner: Corner::Miter,
+        }
+    }
+
+    /// Draws the frame inside of the source borders, over the source.
+    pub fn inside(mut self) -> Self {
+        self.inside = true;
+        self
+    }
+
+    /// Sets the corner style. Defaults to [`Corner::Miter`].
+    pub fn corner(mut self, corner: Corner) -> Self {
+        self.corner = corner;
+        self
+    }
+
+    fn coverage(&self, (x, y): (i32, i32)) -> f32 {
+        let Borders {
+            w: (x0, x1),
+            h: (y0, y1),
+        } = self.region;
+
+        let (x, y) = (x as f32, y as f32);
+        let (x0, x1, y0, y1) = (x0 as f32, x1 as f32, y0 as f32, y1 as f32);
+        let t = self.thickness;
+        if self.inside {
+            if x < x0 || x > x1 || y < y0 || y > y1 {
+                return 0.;
+            }
+
+            // Distance from the rectangle shrunk by the thickness
+            let dx = (x0 + t - x).max(x - (x1 - t)).max(0.);
+            let dy = (y0 + t - y).max(y - (y1 - t)).max(0.);
+            let d = self.corner.distance(dx, dy);
+
+            // Round corners of the region are cut like the outer edge of the outside frame
+            d.clamp(0., 1.).min((t - d + 1.).clamp(0., 1.))
+        } else {
+            let dx = (x0 - x).max(x - x1).max(0.);
+            let dy = (y0 - y).max(y - y1).max(0.);
+            let d = self.corner.distance(dx, dy);
+            if d <= 0. {
+                return 0.;
+            }
+
+            (t - d + 1.).clamp(0., 1.)
+        }
+    }
+}
+
+impl<S, P> Source for Framed<S, P>
+where
+    S: Source,
+    P: Source,
+{
+    fn source(&self, pos: (i32, i32)) -> Color {
+        let col = self.source.source(pos);
+        let cov = self.coverage(pos);
+        if cov <= 0. {
+            return col;
+        }
+
+        let mut paint = self.paint.source(pos);
+        paint.a *= cov;
+        col.overlay(paint)
+    }
+
+    fn borders(&self) -> Option<Borders> {
+        if self.inside {
+            Some(self.region)
+        } else {
+            Some(self.region.expand(self.thickness as i32))
+        }
+    }
+
+    fn prepare(&mut self, region: Borders) {
+        self.source.prepare(region);
+        self.paint.prepare(region);
+    }
+
+    fn reseed(&mut self, seed: u32) {
+        self.source.reseed(seed);
+        self.paint.reseed(seed);
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum Corner {
+    /// Sharp corners.
+    Miter,
+
+    /// Corners rounded with a radius of the frame thickness.
+    Round,
+}
+
+impl Corner {
+    fn distance(self, dx: f32, dy: f32) -> f32 {
+        match self {
+            Self::Miter => dx.max(dy),
+            Self::Round => (dx * dx + dy * dy).sqrt(),
+        }
+    }
+}
+
+/// Clips a bounded source to a rectangle with anti-aliased rounded corners.
+pub struct RoundedCorners<S> {
+    source: S,
+    region: Borders,
+    radius: f32,
+}
+
+impl<S> RoundedCorners<S>
+where
+    S: Source,
+{
+    /// The [`RoundedCorners`] constructor.
+    ///
+    /// # Panics
+    /// Panics when the source has no borders.
+    pub fn new(source: S, radius: f32) -> Self {
+        let region = source.borders().expect("the source must have borders");
+        Self {
+            source,
+            region,
+            radius: radius.max(0.),
+        }
+    }
+}
+
+impl<S> Source for RoundedCorners<S>
+where
+    S: Source,
+{
+    fn source(&self, (x, y): (i32, i32)) -> Color {
+        let Borders {
+            w: (x0, x1),
+            h: (y0, y1),
+        } = self.region;
+
+        // Signed distance from the pixel center to the rounded rectangle
+        let hw = (x1 - x0 + 1) as f32 * 0.5;
+        let hh = (y1 - y0 + 1) as f32 * 0.5;
+        let r = self.radius.min(hw).min(hh);
+        let px = (x as f32 + 0.5 - (x0 as f32 + hw)).abs() - (hw - r);
+        let py = (y as f32 + 0.5 - (y0 as f32 + hh)).abs() - (hh - r);
+        let outside = (px.max(0.).powi(2) + py.max(0.).powi(2)).sqrt();
+        let d = outside + px.max(py).min(0.) - r;
+
+        let cov = (0.5 - d).clamp(0., 1.);
+        if cov <= 0. {
+            return Color::default();
+        }
+
+        let mut col = self.source.source((x, y));
+        col.a *= cov;
+        col
+    }
+
+    fn borders(&self) -> Option<Borders> {
+        Some(self.region)
+    }
+
+    fn prepare(&mut self, region: Borders) {
+        self.source.prepare(region);
+    }
+
+    fn reseed(&mut self, seed: u32) {
+        self.source.reseed(seed);
+    }
+}
@@ -0,0 +1,227 @@
+use {
+    crate::{
+        source::{Borders, Source},
+        Color,
+    },
+    alloc::{boxed::Box, vec, vec::Vec},
+};
+
+/// A source of colors with a depth per pixel.
+///
+/// Smaller depth values are closer to the viewer.
+pub trait DepthSource {
+    fn source_depth(&self, pos: (i32, i32)) -> (Color, f32);
+
+    fn borders(&self) -> Option<Borders> {
+        None
+    }
+
+    /// Called before sampling with the region that is going to be rendered,
+    /// see [`Source::prepare`].
+    fn prepare(&mut self, region: Borders) {
+        _ = region;
+    }
+
+    /// Changes the seed of random sources, see [`Source::reseed`].
+    fn reseed(&mut self, seed: u32) {
+        _ = seed;
+    }
+}
+
+impl<D> DepthSource for &D
+where
+    D: DepthSource + ?Sized,
+{
+    fn source_depth(&self, pos: (i32, i32)) -> (Color, f32) {
+        D::source_depth(self, pos)
+    }
+
+    fn borders(&self) -> Option<Borders> {
+        D::borders(self)
+    }
+}
+
+impl<D> DepthSource for Box<D>
+where
+    D: DepthSource + ?Sized,
+{
+    fn source_depth(&self, pos: (i32, i32)) -> (Color, f32) {
+        D::source_depth(self, pos)
+    }
+
+    fn borders(&self) -> Option<Borders> {
+        D::borders(self)
+    }
+
+    fn prepare(&mut self, region: Borders) {
+        D::prepare(self, region);
+    }
+
+    fn reseed(&mut self, seed: u32) {
+        D::reseed(self, seed);
+    }
+}
+
+/// A source at a constant depth.
+pub struct Flat<S> {
+    source: S,
+    depth: f32,
+}
+
+impl<S> Flat<S> {
+    pub fn new(source: S, depth: f32) -> Self {
+        Self { source, depth }
+    }
+}
+
+impl<S> DepthSource for Flat<S>
+where
+    S: Source,
+{
+    fn source_depth(&self, pos: (i32, i32)) -> (Color, f32) {
+        (self.source.source(pos), self.depth)
+    }
+
+    fn borders(&self) -> Option<Borders> {
+        self.source.borders()
+    }
+
+    fn prepare(&mut self, region: Borders) {
+        self.source.prepare(region);
+    }
+
+    fn reseed(&mut self, seed: u32) {
+        self.source.reseed(seed);
+    }
+}
+
+/// A source with depth taken from the luminance of another source.
+pub struct WithDepth<S, D> {
+    source: S,
+    depth: D,
+    range: (f32, f32),
+}
+
+impl<S, D> WithDepth<S, D> {
+    /// The [`WithDepth`] constructor.
+    ///
+    /// Luminance of the `depth` source is mapped linearly to the `near..far` range,
+    /// so black is near and white is far.
+    pub fn new(source: S, depth: D, (near, far): (f32, f32)) -> Self {
+        Self {
+            source,
+            depth,
+            range: (near, far),
+        }
+    }
+}
+
+impl<S, D> DepthSource for WithDepth<S, D>
+where
+    S: Source,
+    D: Source,
+{
+    fn source_depth(&self, pos: (i32, i32)) -> (Color, f32) {
+        let (near, far) = self.range;
+        let l = self.depth.source(pos).luminance();
+        (self.source.source(pos), near + l * (far - near))
+    }
+
+    fn borders(&self) -> Option<Borders> {
+        self.source.borders()
+    }
+
+    fn prepare(&mut self, region: Borders) {
+        self.source.prepare(region);
+        self.depth.prepare(region);
+    }
+
+    fn reseed(&mut self, seed: u32) {
+        self.source.reseed(seed);
+        self.depth.reseed(seed);
+    }
+}
+
+/// Composites layers by per-pixel depth instead of their order.
+///
+/// At each position layers are blended from the farthest to the closest one.
+/// Layers with equal depth keep their order, later layers are drawn on top.
+pub struct ZComposite<D> {
+    layers: Vec<D>,
+}
+
+impl<D> ZComposite<D> {
+    pub fn new() -> Self {
+        Self { layers: vec![] }
+    }
+
+    pub fn push(mut self, layer: D) -> Self {
+        self.layers.push(layer);
+        self
+    }
+}
+
+impl<D> Default for ZComposite<D> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<D> Extend<D> for ZComposite<D> {
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = D>,
+    {
+        self.layers.extend(iter);
+    }
+}
+
+impl<D> FromIterator<D> for ZComposite<D> {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = D>,
+    {
+        Self {
+            layers: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl<D> Source for ZComposite<D>
+where
+    D: DepthSource,
+{
+    fn source(&self, pos: (i32, i32)) -> Color {
+        let mut samples: Vec<_> = self
+            .layers
+            .iter()
+            .map(|layer| layer.source_depth(pos))
+            .filter(|(col, _)| col.is_visible())
+            .collect();
+
+        samples.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+        samples
+            .into_iter()
+            .fold(Color::default(), |res, (col, _)| res.overlay(col))
+    }
+
+    fn borders(&self) -> Option<Borders> {
+        self.layers
+            .iter()
+            .map(|layer| layer.borders())
+            .reduce(|a, b| Some(a?.union(b?)))
+            .flatten()
+    }
+
+    fn prepare(&mut self, region: Borders) {
+        for layer in &mut self.layers {
+            layer.prepare(region);
+        }
+    }
+
+    fn reseed(&mut self, seed: u32) {
+        for layer in &mut self.layers {
+            layer.reseed(seed);
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use crate::{
+    source::{Borders, Source},
+    Color,
+};
+
+/// Per-pixel difference between two sources.
+///
+/// Colors are compared premultiplied by alpha, so alpha differences show up as well.
+pub struct Diff<A, B> {
+    a: A,
+    b: B,
+    amplify: f32,
+    mode: DiffMode,
+}
+
+impl<A, B> Diff<A, B> {
+    /// The [`Diff`] constructor.
+    ///
+    /// The difference is multiplied by `amplify` before it is shown.
+    ///
+    /// # Panics
+    /// Panics when an `amplify` is less than zero.
+    pub fn new(a: A, b: B, amplify: f32, mode: DiffMode) -> Self {
+        assert!(amplify >= 0., "amplify cannot be less than zero");
+        Self {
+            a,
+            b,
+            amplify,
+            mode,
+        }
+    }
+}
+
+impl<A, B> Source for Diff<A, B>
+where
+    A: Source,
+    B: Source,
+{
+    fn source(&self, pos: (i32, i32)) -> Color {
+        if let Some(borders) = self.borders() {
+            if !borders.contains(pos) {
+                return Color::default();
+            }
+        }
+
+        let a = premultiplied(self.a.source(pos));
+        let b = premultiplied(self.b.source(pos));
+        let diff = Color {
+            r: (a.r - b.r).abs(),
+            g: (a.g - b.g).abs(),
+            b: (a.b - b.b).abs(),
+            a: (a.a - b.a).abs(),
+        };
+
+        match self.mode {
+            DiffMode::Channels => Color {
+                r: (diff.r * self.amplify).min(1.),
+                g: (diff.g * self.amplify).min(1.),
+                b: (diff.b * self.amplify).min(1.),
+                a: 1.,
+            },
+            DiffMode::Heat => {
+                let v = diff.r.max(diff.g).max(diff.b).max(diff.a);
+                heat((v * self.amplify).min(1.))
+            }
+        }
+    }
+
+    fn borders(&self) -> Option<Borders> {
+        match (self.a.borders(), self.b.borders()) {
+            (Some(a), Some(b)) => Some(a.union(b)),
+            _ => None,
+        }
+    }
+
+    fn prepare(&mut self, region: Borders) {
+        self.a.prepare(region);
+        self.b.prepare(region);
+    }
+
+    fn reseed(&mut self, seed: u32) {
+        self.a.reseed(seed);
+        self.b.reseed(seed);
+    }
+}
+
+pub enum DiffMode {
+    /// Shows the absolute difference of every color channel.
+    Channels,
+
+    /// Shows the largest channel difference on a black-blue-green-yellow-red ramp.
+    Heat,
+}
+
+fn premultiplied(col: Color) -> Color {
+    Color {
+        r: col.r * col.a,
+        g: col.g * col.a,
+        b: col.b * col.a,
+        a: col.a,
+    }
+}
+
+fn heat(v: f32) -> Color {
+    const RAMP: [u32; 5] = [0x000000FF, 0x0000FFFF, 0x00FF00FF, 0xFFFF00FF, 0xFF0000FF];
+
+    let t = v * (RAMP.len() - 1) as f32;
+    let i = (t as usize).min(RAMP.len() - 2);
+    Color::from_u32(RAMP[i]).lerp(Color::from_u32(RAMP[i + 1]), t - i as f32)
+}
@@ -0,0 +1,250 @@
+use {
+    crate::{
+        source::{Borders, Source},
+        Color,
+    },
+    std::sync::OnceLock,
+};
+
+/// Euclidean distance transform of a source alpha as a grayscale source.
+///
+/// The transform covers the source borders grown by the `spread`, pixels with alpha
+/// of at least a half are inside the shape. It's computed once, when first sampled,
+/// or for the rendered region only, when the source is prepared.
+pub struct DistanceField<S> {
+    source: S,
+    region: Borders,
+    spread: f32,
+    mode: DistanceMode,
+    full: OnceLock<Distances>,
+    prepared: Option<Distances>,
+}
+
+/// Distances over an area, exact up to the spread inside the covered part.
+struct Distances {
+    area: Borders,
+    covered: Borders,
+    values: Vec<f32>,
+}
+
+impl Distances {
+    fn get(&self, pos: (i32, i32)) -> Option<f32> {
+        if !self.covered.contains(pos) {
+            return None;
+        }
+
+        let (w, _) = self.area.size();
+        let x = (pos.0 - self.area.w.0) as usize;
+        let y = (pos.1 - self.area.h.0) as usize;
+        Some(self.values[y * w as usize + x])
+    }
+}
+
+impl<S> DistanceField<S>
+where
+    S: Source,
+{
+    /// The [`DistanceField`] constructor.
+    ///
+    /// The `spread` is the distance in pixels over which the output fades.
+    ///
+    /// # Panics
+    /// Panics when the source has no borders or a `spread` is less than or equal to zero.
+    pub fn new(source: S, spread: f32, mode: DistanceMode) -> Self {
+        assert!(
+            spread > f32::EPSILON,
+            "spread cannot be less than or equal to zero",
+        );
+
+        let region = source
+            .borders()
+            .expect("the source must have borders")
+            .expand(spread.ceil() as i32);
+
+        Self {
+            source,
+            region,
+            spread,
+            mode,
+            full: OnceLock::new(),
+            prepared: None,
+        }
+    }
+
+    /// Returns the distance in pixels from the position to the shape edge.
+    ///
+    /// In the [`DistanceMode::Signed`] mode distances inside the shape are negative.
+    /// Returns `None` if the position is outside of the computed region.
+    pub fn distance(&self, pos: (i32, i32)) -> Option<f32> {
+        self.full
+            .get_or_init(|| self.compute(self.region, self.region))
+            .get(pos)
+    }
+
+    /// Computes distances over the `area`, which are used inside the `covered` part.
+    fn compute(&self, area: Borders, covered: Borders) -> Distances {
+        let (w, h) = area.size();
+        let (width, height) = (w as usize, h as usize);
+        let inside: Vec<_> = area
+            .positions()
+            .map(|pos| self.source.source(pos).a >= 0.5)
+            .collect();
+
+        let outer = transform(&inside, width, height);
+        let values = match self.mode {
+            DistanceMode::Unsigned => outer,
+            DistanceMode::Signed => {
+                let outside: Vec<_> = inside.iter().map(|&v| !v).collect();
+                let inner = transform(&outside, width, height);
+                outer
+                    .iter()
+                    .zip(inner)
+                    // Put the zero crossing on the pixel edge
+                    .map(|(&o, i)| if o > 0. { o - 0.5 } else { 0.5 - i })
+                    .collect()
+            }
+        };
+
+        Distances {
+            area,
+            covered,
+            values,
+        }
+    }
+}
+
+impl<S> Source for DistanceField<S>
+where
+    S: Source,
+{
+    fn source(&self, pos: (i32, i32)) -> Color {
+        let prepared = self
+            .prepared
+            .as_ref()
+            .and_then(|prepared| prepared.get(pos));
+        let Some(d) = prepared.or_else(|| self.distance(pos)) else {
+            return Color::default();
+        };
+
+        let v = match self.mode {
+            DistanceMode::Unsigned => 1. - d / self.spread,
+            DistanceMode::Signed => 0.5 - d / (2. * self.spread),
+        }
+        .clamp(0., 1.);
+
+        Color {
+            r: v,
+            g: v,
+            b: v,
+            a: 1.,
+        }
+    }
+
+    fn borders(&self) -> Option<Borders> {
+        Some(self.region)
+    }
+
+    /// Computes distances for the region only. Shape pixels farther than
+    /// the spread can't change the output, so the region grown by the spread is read.
+    fn prepare(&mut self, region: Borders) {
+        let clip = |b: Borders| Borders {
+            w: (b.w.0.max(self.region.w.0), b.w.1.min(self.region.w.1)),
+            h: (b.h.0.max(self.region.h.0), b.h.1.min(self.region.h.1)),
+        };
+
+        let area = clip(region.expand(self.spread.ceil() as i32 + 1));
+        self.source.prepare(area);
+        self.prepared = None;
+
+        let (w, h) = area.size();
+        if w > 0 && h > 0 {
+            self.prepared = Some(self.compute(area, clip(region)));
+        }
+    }
+
+    /// Reseeds the source, so distances are computed again.
+    fn reseed(&mut self, seed: u32) {
+        self.source.reseed(seed);
+        self.full = OnceLock::new();
+        self.prepared = None;
+    }
+}
+
+pub enum DistanceMode {
+    /// Distance to the nearest inside pixel, zero inside the shape.
+    /// The output is white on the shape and fades to black at the `spread` distance.
+    Unsigned,
+
+    /// Distance to the shape edge, negative inside the shape.
+    /// The output is a half gray on the edge, brighter inside and darker outside.
+    Signed,
+}
+
+/// Computes the distance from every pixel to the nearest feature pixel.
+fn transform(features: &[bool], width: usize, height: usize) -> Vec<f32> {
+    const INF: f32 = 1e20;
+
+    let mut grid: Vec<_> = features.iter().map(|&f| if f { 0. } else { INF }).collect();
+
+    let mut line = vec![0.; width.max(height)];
+    let mut out = vec![0.; width.max(height)];
+    for x in 0..width {
+        for y in 0..height {
+            line[y] = grid[y * width + x];
+        }
+
+        transform_line(&line[..height], &mut out[..height]);
+        for y in 0..height {
+            grid[y * width + x] = out[y];
+        }
+    }
+
+    for y in 0..height {
+        let row = &mut grid[y * width..(y + 1) * width];
+        line[..width].copy_from_slice(row);
+        transform_line(&line[..width], &mut out[..width]);
+        row.copy_from_slice(&out[..width]);
+    }
+
+    grid.iter().map(|d| d.sqrt()).collect()
+}
+
+/// One dimensional squared distance transform by Felzenszwalb and Huttenlocher.
+fn transform_line(f: &[f32], out: &mut [f32]) {
+    let n = f.len();
+    if n == 0 {
+        return;
+    }
+
+    let parabola = |p: usize| f[p] + (p * p) as f32;
+    let intersect = |q: usize, p: usize| (parabola(q) - parabola(p)) / (2 * q - 2 * p) as f32;
+
+    let mut v = vec![0; n];
+    let mut z = vec![0.; n + 1];
+    let mut k = 0;
+    z[0] = f32::NEG_INFINITY;
+    z[1] = f32::INFINITY;
+    for q in 1..n {
+        let mut s = intersect(q, v[k]);
+        while s <= z[k] {
+            k -= 1;
+            s = intersect(q, v[k]);
+        }
+
+        k += 1;
+        v[k] = q;
+        z[k] = s;
+        z[k + 1] = f32::INFINITY;
+    }
+
+    k = 0;
+    for (q, d) in out.iter_mut().enumerate() {
+        while z[k + 1] < q as f32 {
+            k += 1;
+        }
+
+        let p = v[k];
+        let dq = q as f32 - p as f32;
+        *d = dq * dq + f[p];
+    }
+}
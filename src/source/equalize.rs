@@ -0,0 +1,292 @@
+use {
+    crate::{
+        math::lerp,
+        source::{Borders, Source},
+        Color,
+    },
+    std::sync::OnceLock,
+};
+
+/// Remaps the luminance of a bounded source to stretch its contrast.
+///
+/// The histogram of the source is computed over its borders, once,
+/// when the source is prepared or first sampled.
+pub struct Equalize<S> {
+    source: S,
+    region: Borders,
+    mode: Mode,
+    lut: OnceLock<Lut>,
+}
+
+enum Mode {
+    Global(Equalization),
+    Tiled { tiles: (u32, u32), clip_limit: f32 },
+}
+
+enum Lut {
+    Global(Box<[f32; 256]>),
+    Tiled {
+        tiles: (u32, u32),
+        luts: Vec<[f32; 256]>,
+    },
+}
+
+impl<S> Equalize<S>
+where
+    S: Source,
+{
+    /// The [`Equalize`] constructor.
+    ///
+    /// # Panics
+    /// Panics when the source has no borders or its borders are empty.
+    pub fn new(source: S, mode: Equalization) -> Self {
+        let region = source.borders().expect("the source must have borders");
+        let (w, h) = region.size();
+        assert!(w > 0 && h > 0, "the source borders cannot be empty");
+
+        Self {
+            source,
+            region,
+            mode: Mode::Global(mode),
+            lut: OnceLock::new(),
+        }
+    }
+
+    /// Creates a contrast limited adaptive equalization (CLAHE).
+    ///
+    /// The source is split into `tiles` regions, each one is equalized separately
+    /// and the results are blended bilinearly between tile centers.
+    /// There are at most as many tiles as pixels along each axis.
+    /// The `clip_limit` is a multiple of the average bin count above which
+    /// the histogram is clipped and redistributed, lower values give less contrast.
+    ///
+    /// # Panics
+    /// Panics when the source has no borders or its borders are empty,
+    /// when any of `tiles` is zero or when a `clip_limit` is less than one.
+    pub fn tiled(source: S, (tx, ty): (u32, u32), clip_limit: f32) -> Self {
+        assert!(tx > 0 && ty > 0, "tiles cannot be zero");
+        assert!(clip_limit >= 1., "clip limit cannot be less than one");
+
+        let region = source.borders().expect("the source must have borders");
+        let (w, h) = region.size();
+        assert!(w > 0 && h > 0, "the source borders cannot be empty");
+
+        // Every tile covers at least one pixel
+        let tiles = (tx.min(w), ty.min(h));
+        Self {
+            source,
+            region,
+            mode: Mode::Tiled { tiles, clip_limit },
+            lut: OnceLock::new(),
+        }
+    }
+
+    fn lut(&self) -> &Lut {
+        self.lut.get_or_init(|| self.build())
+    }
+
+    fn build(&self) -> Lut {
+        let bins = luma_bins(&self.source, self.region);
+        match self.mode {
+            Mode::Global(Equalization::Histogram) => {
+                let hist = count(bins.iter().flatten().copied());
+                Lut::Global(Box::new(equalize(&hist, None)))
+            }
+            Mode::Global(Equalization::Levels { clip }) => {
+                let hist = count(bins.iter().flatten().copied());
+                Lut::Global(Box::new(levels(&hist, clip)))
+            }
+            Mode::Tiled { tiles, clip_limit } => {
+                let (w, h) = self.region.size();
+                let (tx, ty) = tiles;
+                let mut luts = Vec::with_capacity(tx as usize * ty as usize);
+                for j in 0..ty {
+                    for i in 0..tx {
+                        let (x0, x1) = (w * i / tx, w * (i + 1) / tx);
+                        let (y0, y1) = (h * j / ty, h * (j + 1) / ty);
+                        let hist = count(
+                            (y0..y1)
+                                .flat_map(|y| (x0..x1).map(move |x| (y * w + x) as usize))
+                                .filter_map(|i| bins[i]),
+                        );
+
+                        luts.push(equalize(&hist, Some(clip_limit)));
+                    }
+                }
+
+                Lut::Tiled { tiles, luts }
+            }
+        }
+    }
+}
+
+impl<S> Source for Equalize<S>
+where
+    S: Source,
+{
+    fn source(&self, pos: (i32, i32)) -> Color {
+        let col = self.source.source(pos);
+        let bin = (col.luminance() * 255.) as u8 as usize;
+        let luma = match self.lut() {
+            Lut::Global(lut) => lut[bin],
+            Lut::Tiled { tiles, luts } => {
+                let region = self.region;
+                let (w, h) = region.size();
+                let (tx, ty) = *tiles;
+
+                // Tile coordinates relative to tile centers
+                let fx = (pos.0 - region.w.0) as f32 * tx as f32 / w as f32 - 0.5;
+                let fy = (pos.1 - region.h.0) as f32 * ty as f32 / h as f32 - 0.5;
+                let cell = |f: f32, n: u32| {
+                    let f = f.clamp(0., (n - 1) as f32);
+                    let a = f as u32;
+                    (a, (a + 1).min(n - 1), f - a as f32)
+                };
+
+                let (x0, x1, xt) = cell(fx, tx);
+                let (y0, y1, yt) = cell(fy, ty);
+                let at = |x: u32, y: u32| luts[(y * tx + x) as usize][bin];
+                let top = lerp(at(x0, y0), at(x1, y0), xt);
+                let bottom = lerp(at(x0, y1), at(x1, y1), xt);
+                lerp(top, bottom, yt)
+            }
+        };
+
+        let old = col.luminance();
+        if old > f32::EPSILON {
+            let k = luma / old;
+            Color {
+                r: (col.r * k).min(1.),
+                g: (col.g * k).min(1.),
+                b: (col.b * k).min(1.),
+                a: col.a,
+            }
+        } else {
+            Color {
+                r: luma,
+                g: luma,
+                b: luma,
+                a: col.a,
+            }
+        }
+    }
+
+    fn borders(&self) -> Option<Borders> {
+        self.source.borders()
+    }
+
+    /// Prepares the source for the region and computes the histogram.
+    fn prepare(&mut self, region: Borders) {
+        self.source.prepare(region);
+        self.lut = OnceLock::from(self.build());
+    }
+
+    /// Reseeds the source, so the histogram is computed again.
+    fn reseed(&mut self, seed: u32) {
+        self.source.reseed(seed);
+        self.lut = OnceLock::new();
+    }
+}
+
+pub enum Equalization {
+    /// Flattens the luminance histogram.
+    Histogram,
+
+    /// Linearly stretches luminance so that the `clip` fraction of darkest
+    /// and brightest pixels is saturated.
+    Levels { clip: f32 },
+}
+
+/// Returns luminance bins of visible pixels, row by row.
+fn luma_bins<S>(source: &S, region: Borders) -> Vec<Option<u8>>
+where
+    S: Source,
+{
+    region
+        .positions()
+        .map(|pos| {
+            let col = source.source(pos);
+            col.is_visible().then(|| (col.luminance() * 255.) as u8)
+        })
+        .collect()
+}
+
+fn count<I>(bins: I) -> [u32; 256]
+where
+    I: IntoIterator<Item = u8>,
+{
+    let mut hist = [0; 256];
+    for bin in bins {
+        hist[bin as usize] += 1;
+    }
+
+    hist
+}
+
+fn equalize(hist: &[u32; 256], clip_limit: Option<f32>) -> [f32; 256] {
+    let mut hist = hist.map(|n| n as f32);
+    let total: f32 = hist.iter().sum();
+    if let Some(limit) = clip_limit {
+        let limit = limit * total / 256.;
+        let mut excess = 0.;
+        for n in &mut hist {
+            if *n > limit {
+                excess += *n - limit;
+                *n = limit;
+            }
+        }
+
+        for n in &mut hist {
+            *n += excess / 256.;
+        }
+    }
+
+    let mut lut = [0.; 256];
+    let min = hist.iter().copied().find(|&n| n > 0.).unwrap_or_default();
+    let mut cdf = 0.;
+    for (i, (v, n)) in lut.iter_mut().zip(hist).enumerate() {
+        cdf += n;
+
+        // Luminance is kept without visible pixels or when there is only one
+        *v = if total > min {
+            ((cdf - min) / (total - min)).clamp(0., 1.)
+        } else {
+            i as f32 / 255.
+        };
+    }
+
+    lut
+}
+
+fn levels(hist: &[u32; 256], clip: f32) -> [f32; 256] {
+    let total: u32 = hist.iter().sum();
+    let skip = (total as f32 * clip.clamp(0., 0.5)) as u32;
+    let low = percentile(hist.iter().enumerate(), skip).unwrap_or(0) as f32;
+    let high = percentile(hist.iter().enumerate().rev(), skip).unwrap_or(255) as f32;
+
+    let mut lut = [0.; 256];
+    for (i, v) in lut.iter_mut().enumerate() {
+        *v = if high > low {
+            ((i as f32 - low) / (high - low)).clamp(0., 1.)
+        } else {
+            i as f32 / 255.
+        };
+    }
+
+    lut
+}
+
+fn percentile<'a, I>(bins: I, skip: u32) -> Option<usize>
+where
+    I: Iterator<Item = (usize, &'a u32)>,
+{
+    let mut acc = 0;
+    for (i, &n) in bins {
+        acc += n;
+        if acc > skip {
+            return Some(i);
+        }
+    }
+
+    None
+}
@@ -0,0 +1,457 @@
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::Float;
+use {
+    crate::{source::Source, Color},
+    alloc::{borrow::ToOwned, boxed::Box, string::String, vec, vec::Vec},
+};
+
+/// A source computed by math expressions.
+///
+/// The text is one, three or four comma separated expressions: a gray value,
+/// `r, g, b` or `r, g, b, a` components. Values are clamped to the `0..=1` range,
+/// the alpha defaults to one.
+///
+/// Expressions are made of numbers, variables, `+ - * / % ^` operators, parentheses
+/// and function calls. Variables are the pixel position `x` and `y`,
+/// the size `w` and `h`, the time `t` and the `pi` constant. Functions are
+/// `sin cos tan asin acos atan atan2 sqrt abs floor ceil fract exp ln pow min max
+/// clamp mix step smoothstep`.
+///
+/// For example, `sin(x * 0.1) * cos(y * 0.1) * 0.5 + 0.5` renders a grid of blobs.
+pub struct Expr {
+    channels: Vec<Node>,
+    size: (f32, f32),
+    time: f32,
+}
+
+impl Expr {
+    /// The [`Expr`] constructor.
+    ///
+    /// # Errors
+    /// Returns an [`ExprError`] if the text doesn't parse.
+    pub fn new(text: &str) -> Result<Self, ExprError> {
+        let mut parser = Parser {
+            text: text.as_bytes(),
+            pos: 0,
+        };
+
+        let mut channels = vec![parser.expr()?];
+        while parser.eat(b',') {
+            channels.push(parser.expr()?);
+        }
+
+        parser.skip_spaces();
+        if parser.pos < text.len() {
+            return Err(ExprError::Unexpected(parser.pos));
+        }
+
+        if !matches!(channels.len(), 1 | 3 | 4) {
+            return Err(ExprError::Channels(channels.len()));
+        }
+
+        Ok(Self {
+            channels,
+            size: (1., 1.),
+            time: 0.,
+        })
+    }
+
+    /// Sets the `w` and `h` variables. Defaults to `(1, 1)`.
+    pub fn size(mut self, (w, h): (u32, u32)) -> Self {
+        self.size = (w as f32, h as f32);
+        self
+    }
+
+    /// Sets the `t` variable. Defaults to `0`.
+    pub fn time(mut self, t: f32) -> Self {
+        self.time = t;
+        self
+    }
+}
+
+impl Source for Expr {
+    fn source(&self, (x, y): (i32, i32)) -> Color {
+        let vars = Vars {
+            x: x as f32,
+            y: y as f32,
+            w: self.size.0,
+            h: self.size.1,
+            t: self.time,
+        };
+
+        let v = |n: usize| self.channels[n].eval(&vars).clamp(0., 1.);
+        match self.channels.len() {
+            1 => {
+                let v = v(0);
+                Color {
+                    r: v,
+                    g: v,
+                    b: v,
+                    a: 1.,
+                }
+            }
+            3 => Color {
+                r: v(0),
+                g: v(1),
+                b: v(2),
+                a: 1.,
+            },
+            _ => Color {
+                r: v(0),
+                g: v(1),
+                b: v(2),
+                a: v(3),
+            },
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ExprError {
+    /// An unexpected character at the byte position.
+    Unexpected(usize),
+
+    /// The text ends in the middle of an expression.
+    End,
+
+    /// An unknown variable or function.
+    Unknown(String),
+
+    /// A function called with a wrong number of arguments.
+    Arguments { name: String, expected: usize },
+
+    /// A number of channels other than one, three or four.
+    Channels(usize),
+}
+
+struct Vars {
+    x: f32,
+    y: f32,
+    w: f32,
+    h: f32,
+    t: f32,
+}
+
+enum Node {
+    Num(f32),
+    Var(Var),
+    Neg(Box<Node>),
+    Bin(Op, Box<[Node; 2]>),
+    Call(Func, Vec<Node>),
+}
+
+#[derive(Clone, Copy)]
+enum Var {
+    X,
+    Y,
+    W,
+    H,
+    T,
+}
+
+#[derive(Clone, Copy)]
+enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Pow,
+}
+
+#[derive(Clone, Copy)]
+enum Func {
+    Sin,
+    Cos,
+    Tan,
+    Asin,
+    Acos,
+    Atan,
+    Atan2,
+    Sqrt,
+    Abs,
+    Floor,
+    Ceil,
+    Fract,
+    Exp,
+    Ln,
+    Pow,
+    Min,
+    Max,
+    Clamp,
+    Mix,
+    Step,
+    Smoothstep,
+}
+
+impl Func {
+    fn from_name(name: &str) -> Option<Self> {
+        let f = match name {
+            "sin" => Self::Sin,
+            "cos" => Self::Cos,
+            "tan" => Self::Tan,
+            "asin" => Self::Asin,
+            "acos" => Self::Acos,
+            "atan" => Self::Atan,
+            "atan2" => Self::Atan2,
+            "sqrt" => Self::Sqrt,
+            "abs" => Self::Abs,
+            "floor" => Self::Floor,
+            "ceil" => Self::Ceil,
+            "fract" => Self::Fract,
+            "exp" => Self::Exp,
+            "ln" => Self::Ln,
+            "pow" => Self::Pow,
+            "min" => Self::Min,
+            "max" => Self::Max,
+            "clamp" => Self::Clamp,
+            "mix" => Self::Mix,
+            "step" => Self::Step,
+            "smoothstep" => Self::Smoothstep,
+            _ => return None,
+        };
+
+        Some(f)
+    }
+
+    fn arity(self) -> usize {
+        match self {
+            Self::Atan2 | Self::Pow | Self::Min | Self::Max | Self::Step => 2,
+            Self::Clamp | Self::Mix | Self::Smoothstep => 3,
+            _ => 1,
+        }
+    }
+
+    fn apply(self, args: &[f32]) -> f32 {
+        use crate::math::{lerp, smoothstep};
+
+        match (self, args) {
+            (Self::Sin, &[a]) => a.sin(),
+            (Self::Cos, &[a]) => a.cos(),
+            (Self::Tan, &[a]) => a.tan(),
+            (Self::Asin, &[a]) => a.asin(),
+            (Self::Acos, &[a]) => a.acos(),
+            (Self::Atan, &[a]) => a.atan(),
+            (Self::Atan2, &[y, x]) => y.atan2(x),
+            (Self::Sqrt, &[a]) => a.sqrt(),
+            (Self::Abs, &[a]) => a.abs(),
+            (Self::Floor, &[a]) => a.floor(),
+            (Self::Ceil, &[a]) => a.ceil(),
+            (Self::Fract, &[a]) => a - a.floor(),
+            (Self::Exp, &[a]) => a.exp(),
+            (Self::Ln, &[a]) => a.ln(),
+            (Self::Pow, &[a, b]) => a.powf(b),
+            (Self::Min, &[a, b]) => a.min(b),
+            (Self::Max, &[a, b]) => a.max(b),
+            (Self::Clamp, &[v, lo, hi]) => v.max(lo).min(hi),
+            (Self::Mix, &[a, b, t]) => lerp(a, b, t),
+            (Self::Step, &[e, v]) => {
+                if v < e {
+                    0.
+                } else {
+                    1.
+                }
+            }
+            (Self::Smoothstep, &[e0, e1, v]) => smoothstep(e0, e1, v),
+            _ => unreachable!("the number of arguments is checked when parsing"),
+        }
+    }
+}
+
+impl Node {
+    fn eval(&self, vars: &Vars) -> f32 {
+        match self {
+            Self::Num(v) => *v,
+            Self::Var(var) => match var {
+                Var::X => vars.x,
+                Var::Y => vars.y,
+                Var::W => vars.w,
+                Var::H => vars.h,
+                Var::T => vars.t,
+            },
+            Self::Neg(node) => -node.eval(vars),
+            Self::Bin(op, nodes) => {
+                let [a, b] = &**nodes;
+                let (a, b) = (a.eval(vars), b.eval(vars));
+                match op {
+                    Op::Add => a + b,
+                    Op::Sub => a - b,
+                    Op::Mul => a * b,
+                    Op::Div => a / b,
+                    Op::Rem => a.rem_euclid(b),
+                    Op::Pow => a.powf(b),
+                }
+            }
+            Self::Call(func, args) => {
+                let mut values = [0.; 3];
+                for (v, arg) in values.iter_mut().zip(args) {
+                    *v = arg.eval(vars);
+                }
+
+                func.apply(&values[..args.len()])
+            }
+        }
+    }
+}
+
+/// A recursive descent parser, from the lowest precedence to the highest.
+struct Parser<'a> {
+    text: &'a [u8],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn expr(&mut self) -> Result<Node, ExprError> {
+        let mut node = self.term()?;
+        loop {
+            let op = if self.eat(b'+') {
+                Op::Add
+            } else if self.eat(b'-') {
+                Op::Sub
+            } else {
+                return Ok(node);
+            };
+
+            node = Node::Bin(op, Box::new([node, self.term()?]));
+        }
+    }
+
+    fn term(&mut self) -> Result<Node, ExprError> {
+        let mut node = self.unary()?;
+        loop {
+            let op = if self.eat(b'*') {
+                Op::Mul
+            } else if self.eat(b'/') {
+                Op::Div
+            } else if self.eat(b'%') {
+                Op::Rem
+            } else {
+                return Ok(node);
+            };
+
+            node = Node::Bin(op, Box::new([node, self.unary()?]));
+        }
+    }
+
+    fn unary(&mut self) -> Result<Node, ExprError> {
+        if self.eat(b'-') {
+            return Ok(Node::Neg(Box::new(self.unary()?)));
+        }
+
+        self.power()
+    }
+
+    /// The power is right associative and binds tighter than the unary minus.
+    fn power(&mut self) -> Result<Node, ExprError> {
+        let base = self.atom()?;
+        if self.eat(b'^') {
+            let exp = self.unary()?;
+            return Ok(Node::Bin(Op::Pow, Box::new([base, exp])));
+        }
+
+        Ok(base)
+    }
+
+    fn atom(&mut self) -> Result<Node, ExprError> {
+        self.skip_spaces();
+        let start = self.pos;
+        match self.text.get(self.pos) {
+            None => Err(ExprError::End),
+            Some(b'(') => {
+                self.pos += 1;
+                let node = self.expr()?;
+                self.expect(b')')?;
+                Ok(node)
+            }
+            Some(c) if c.is_ascii_digit() || *c == b'.' => {
+                while self
+                    .text
+                    .get(self.pos)
+                    .is_some_and(|c| c.is_ascii_digit() || *c == b'.')
+                {
+                    self.pos += 1;
+                }
+
+                let num = core::str::from_utf8(&self.text[start..self.pos]).expect("ascii");
+                num.parse()
+                    .map(Node::Num)
+                    .map_err(|_| ExprError::Unexpected(start))
+            }
+            Some(c) if c.is_ascii_alphabetic() => {
+                while self
+                    .text
+                    .get(self.pos)
+                    .is_some_and(|c| c.is_ascii_alphanumeric() || *c == b'_')
+                {
+                    self.pos += 1;
+                }
+
+                let name = core::str::from_utf8(&self.text[start..self.pos]).expect("ascii");
+                self.name(name)
+            }
+            Some(_) => Err(ExprError::Unexpected(start)),
+        }
+    }
+
+    fn name(&mut self, name: &str) -> Result<Node, ExprError> {
+        let var = match name {
+            "x" => Var::X,
+            "y" => Var::Y,
+            "w" => Var::W,
+            "h" => Var::H,
+            "t" => Var::T,
+            "pi" => return Ok(Node::Num(core::f32::consts::PI)),
+            _ => {
+                let func =
+                    Func::from_name(name).ok_or_else(|| ExprError::Unknown(name.to_owned()))?;
+                return self.call(func, name);
+            }
+        };
+
+        Ok(Node::Var(var))
+    }
+
+    fn call(&mut self, func: Func, name: &str) -> Result<Node, ExprError> {
+        self.expect(b'(')?;
+        let mut args = vec![self.expr()?];
+        while self.eat(b',') {
+            args.push(self.expr()?);
+        }
+
+        self.expect(b')')?;
+        if args.len() != func.arity() {
+            return Err(ExprError::Arguments {
+                name: name.to_owned(),
+                expected: func.arity(),
+            });
+        }
+
+        Ok(Node::Call(func, args))
+    }
+
+    fn skip_spaces(&mut self) {
+        while self.text.get(self.pos).is_some_and(u8::is_ascii_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    fn eat(&mut self, c: u8) -> bool {
+        self.skip_spaces();
+        let found = self.text.get(self.pos) == Some(&c);
+        if found {
+            self.pos += 1;
+        }
+
+        found
+    }
+
+    fn expect(&mut self, c: u8) -> Result<(), ExprError> {
+        if self.eat(c) {
+            Ok(())
+        } else if self.pos < self.text.len() {
+            Err(ExprError::Unexpected(self.pos))
+        } else {
+            Err(ExprError::End)
+        }
+    }
+}
@@ -0,0 +1,332 @@
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::Float;
+use {
+    crate::{
+        source::{Borders, Source},
+        Color,
+    },
+    alloc::vec::Vec,
+};
+
+/// Edge-preserving smoothing filter.
+///
+/// Each neighbor is weighted both by its distance and by its color difference
+/// from the center pixel, so noise is smoothed while edges stay sharp.
+pub struct Bilateral<S> {
+    source: S,
+    radius: i32,
+    kernel: Vec<f32>,
+    range: f32,
+}
+
+impl<S> Bilateral<S> {
+    /// The [`Bilateral`] constructor.
+    ///
+    /// The `spatial` sigma is measured in pixels and the `range` sigma
+    /// in color units, where `1` is the full channel range.
+    ///
+    /// # Panics
+    /// Panics when a `spatial` or `range` sigma is less than or equal to zero.
+    pub fn new(source: S, spatial: f32, range: f32) -> Self {
+        assert!(
+            spatial > f32::EPSILON,
+            "spatial sigma cannot be less than or equal to zero",
+        );
+
+        assert!(
+            range > f32::EPSILON,
+            "range sigma cannot be less than or equal to zero",
+        );
+
+        let radius = (spatial * 2.).ceil() as i32;
+        let side = radius * 2 + 1;
+        let kernel = (0..side * side)
+            .map(|i| {
+                let dx = (i % side - radius) as f32;
+                let dy = (i / side - radius) as f32;
+                (-(dx * dx + dy * dy) / (2. * spatial * spatial)).exp()
+            })
+            .collect();
+
+        Self {
+            source,
+            radius,
+            kernel,
+            range: 1. / (2. * range * range),
+        }
+    }
+}
+
+impl<S> Source for Bilateral<S>
+where
+    S: Source,
+{
+    fn source(&self, (x, y): (i32, i32)) -> Color {
+        if let Some(borders) = self.borders() {
+            if !borders.contains((x, y)) {
+                return Color::default();
+            }
+        }
+
+        let center = self.source.source((x, y));
+        let radius = self.radius;
+        let side = radius * 2 + 1;
+        let mut sum = Color::default();
+        let mut total = 0.;
+        for dy in -radius..=radius {
+            for dx in -radius..=radius {
+                let col = self.source.source((x + dx, y + dy));
+                let d = distance_sqr(center, col);
+                let w = self.kernel[((dy + radius) * side + dx + radius) as usize]
+                    * (-d * self.range).exp();
+
+                sum += col * w;
+                total += w;
+            }
+        }
+
+        if total > 0. {
+            sum * (1. / total)
+        } else {
+            center
+        }
+    }
+
+    fn borders(&self) -> Option<Borders> {
+        self.source.borders().map(|b| b.expand(self.radius))
+    }
+
+    fn prepare(&mut self, region: Borders) {
+        self.source.prepare(region.expand(self.radius));
+    }
+
+    fn reseed(&mut self, seed: u32) {
+        self.source.reseed(seed);
+    }
+}
+
+fn distance_sqr(a: Color, b: Color) -> f32 {
+    let dr = a.r - b.r;
+    let dg = a.g - b.g;
+    let db = a.b - b.b;
+    let da = a.a - b.a;
+    dr * dr + dg * dg + db * db + da * da
+}
+
+/// Painterly filter that smooths each pixel with its most uniform neighborhood.
+pub struct Kuwahara<S> {
+    source: S,
+    radius: i32,
+    kind: KuwaharaKind,
+}
+
+impl<S> Kuwahara<S> {
+    pub fn new(source: S, radius: u8, kind: KuwaharaKind) -> Self {
+        Self {
+            source,
+            radius: radius as i32,
+            kind,
+        }
+    }
+}
+
+impl<S> Source for Kuwahara<S>
+where
+    S: Source,
+{
+    fn source(&self, (x, y): (i32, i32)) -> Color {
+        if let Some(borders) = self.borders() {
+            if !borders.contains((x, y)) {
+                return Color::default();
+            }
+        }
+
+        let radius = self.radius;
+        match self.kind {
+            KuwaharaKind::Classic => {
+                let mut best = Color::default();
+                let mut best_var = f32::INFINITY;
+                for (sx, sy) in [(-1, -1), (1, -1), (-1, 1), (1, 1)] {
+                    let mut sum = Color::default();
+                    let mut lsum = 0.;
+                    let mut lsqr = 0.;
+                    for dy in 0..=radius {
+                        for dx in 0..=radius {
+                            let col = self.source.source((x + dx * sx, y + dy * sy));
+                            let l = col.luminance();
+                            sum += col;
+                            lsum += l;
+                            lsqr += l * l;
+                        }
+                    }
+
+                    let n = ((radius + 1) * (radius + 1)) as f32;
+                    let mean = lsum / n;
+                    let var = lsqr / n - mean * mean;
+                    if var < best_var {
+                        best_var = var;
+                        best = sum * (1. / n);
+                    }
+                }
+
+                best
+            }
+            KuwaharaKind::Generalized => {
+                use core::f32::consts::TAU;
+
+                const SECTORS: usize = 8;
+                const SHARPNESS: f32 = 8.;
+
+                let sigma = (radius as f32 * 0.5).max(0.5);
+                let mut sums = [Color::default(); SECTORS];
+                let mut sqrs = [Color::default(); SECTORS];
+                let mut weights = [0.; SECTORS];
+                for dy in -radius..=radius {
+                    for dx in -radius..=radius {
+                        let d = (dx * dx + dy * dy) as f32;
+                        if d > (radius * radius) as f32 {
+                            continue;
+                        }
+
+                        let col = self.source.source((x + dx, y + dy));
+                        let w = (-d / (2. * sigma * sigma)).exp();
+                        let mut add = |k: usize| {
+                            sums[k] += col * w;
+                            sqrs[k] += col * col * w;
+                            weights[k] += w;
+                        };
+
+                        if dx == 0 && dy == 0 {
+                            (0..SECTORS).for_each(add);
+                        } else {
+                            let angle = (dy as f32).atan2(dx as f32) + TAU / 2.;
+                            add((angle / TAU * SECTORS as f32) as usize % SECTORS);
+                        }
+                    }
+                }
+
+                let mut res = Color::default();
+                let mut total = 0.;
+                for k in 0..SECTORS {
+                    if weights[k] <= 0. {
+                        continue;
+                    }
+
+                    let mean = sums[k] * (1. / weights[k]);
+                    let sqr = sqrs[k] * (1. / weights[k]);
+                    let var = (sqr.r - mean.r * mean.r).max(0.)
+                        + (sqr.g - mean.g * mean.g).max(0.)
+                        + (sqr.b - mean.b * mean.b).max(0.);
+
+                    // Scale the deviation to the byte range so the sharpness behaves as usual
+                    let alpha = 1. / (1. + (var.sqrt() * 255.).powf(SHARPNESS * 0.5));
+                    res += mean * alpha;
+                    total += alpha;
+                }
+
+                if total > 0. {
+                    res * (1. / total)
+                } else {
+                    self.source.source((x, y))
+                }
+            }
+        }
+    }
+
+    fn borders(&self) -> Option<Borders> {
+        self.source.borders().map(|b| b.expand(self.radius))
+    }
+
+    fn prepare(&mut self, region: Borders) {
+        self.source.prepare(region.expand(self.radius));
+    }
+
+    fn reseed(&mut self, seed: u32) {
+        self.source.reseed(seed);
+    }
+}
+
+pub enum KuwaharaKind {
+    /// Picks the mean of the square quadrant with the lowest luminance variance.
+    Classic,
+
+    /// Blends eight gaussian-weighted disc sectors by their variance,
+    /// which gives smoother, less blocky results.
+    Generalized,
+}
+
+/// Oil painting stylization.
+///
+/// Neighbors are grouped into intensity levels and each pixel takes
+/// the average color of the most common level around it.
+pub struct OilPaint<S> {
+    source: S,
+    radius: i32,
+    levels: u8,
+}
+
+impl<S> OilPaint<S> {
+    /// The [`OilPaint`] constructor.
+    ///
+    /// # Panics
+    /// Panics when `levels` is zero.
+    pub fn new(source: S, radius: u8, levels: u8) -> Self {
+        assert!(levels > 0, "levels cannot be zero");
+        Self {
+            source,
+            radius: radius as i32,
+            levels,
+        }
+    }
+}
+
+impl<S> Source for OilPaint<S>
+where
+    S: Source,
+{
+    fn source(&self, (x, y): (i32, i32)) -> Color {
+        if let Some(borders) = self.borders() {
+            if !borders.contains((x, y)) {
+                return Color::default();
+            }
+        }
+
+        let mut counts = [0_u32; 256];
+        let mut sums = [Color::default(); 256];
+        let radius = self.radius;
+        let rsqr = radius * radius;
+        let top = (self.levels - 1) as f32;
+        for dy in -radius..=radius {
+            for dx in -radius..=radius {
+                if dx * dx + dy * dy > rsqr {
+                    continue;
+                }
+
+                let col = self.source.source((x + dx, y + dy));
+                let level = (col.luminance().clamp(0., 1.) * top).round() as usize;
+                counts[level] += 1;
+                sums[level] += col;
+            }
+        }
+
+        let (level, &n) = counts
+            .iter()
+            .enumerate()
+            .max_by_key(|&(_, n)| n)
+            .expect("counts is not empty");
+
+        sums[level] * (1. / n as f32)
+    }
+
+    fn borders(&self) -> Option<Borders> {
+        self.source.borders().map(|b| b.expand(self.radius))
+    }
+
+    fn prepare(&mut self, region: Borders) {
+        self.source.prepare(region.expand(self.radius));
+    }
+
+    fn reseed(&mut self, seed: u32) {
+        self.source.reseed(seed);
+    }
+}
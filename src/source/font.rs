@@ -0,0 +1,559 @@
+use {
+    crate::{
+        source::{sample, Borders, Fill, Filter, Path, Source},
+        Color, Image,
+    },
+    std::{
+        fs, io,
+        path::Path as FsPath,
+        sync::{Arc, OnceLock},
+    },
+    ttf_parser::{
+        colr::{ClipBox, CompositeMode, Paint, Painter},
+        Face, FaceParsingError, GlyphId, OutlineBuilder, RasterImageFormat, RgbaColor, Transform,
+    },
+};
+
+/// A TrueType or OpenType font.
+///
+/// Clones share the font data.
+#[derive(Clone)]
+pub struct Font {
+    data: Arc<[u8]>,
+}
+
+impl Font {
+    /// Loads a font from raw data.
+    ///
+    /// # Errors
+    /// Returns a [`FontError::Parse`] if the data is not a valid font.
+    pub fn from_vec(data: Vec<u8>) -> Result<Self, FontError> {
+        Face::parse(&data, 0)?;
+        Ok(Self { data: data.into() })
+    }
+
+    /// Reads a font file.
+    ///
+    /// # Errors
+    /// Returns a [`FontError::Io`] if the file cannot be read,
+    /// otherwise fails the same way as [`from_vec`](Self::from_vec).
+    pub fn open<P>(path: P) -> Result<Self, FontError>
+    where
+        P: AsRef<FsPath>,
+    {
+        Self::from_vec(fs::read(path)?)
+    }
+
+    fn face(&self) -> Face<'_> {
+        Face::parse(&self.data, 0).expect("the font is validated on load")
+    }
+}
+
+#[derive(Debug)]
+pub enum FontError {
+    Io(io::Error),
+    Parse(FaceParsingError),
+}
+
+impl From<io::Error> for FontError {
+    fn from(v: io::Error) -> Self {
+        Self::Io(v)
+    }
+}
+
+impl From<FaceParsingError> for FontError {
+    fn from(v: FaceParsingError) -> Self {
+        Self::Parse(v)
+    }
+}
+
+/// Text rendered with loaded fonts.
+///
+/// Each character is drawn with the first font that has it, so a chain of fonts
+/// can cover scripts and emoji missing in the main one. Characters missing in all
+/// fonts are drawn as the missing glyph of the first font.
+///
+/// Color emoji are drawn from bitmap (`CBDT`, `sbix`) and layered (`COLR`) glyphs.
+/// Gradients of layered glyphs are approximated with their average color.
+///
+/// The text is rasterized once, when it's first sampled or measured,
+/// and placed at the origin, line feeds start new lines.
+pub struct FontText {
+    text: String,
+    fonts: Vec<Font>,
+    size: f32,
+    color: Color,
+    antialias: Antialias,
+    hinting: Hinting,
+    raster: OnceLock<Raster>,
+}
+
+struct Raster {
+    pixels: Vec<Color>,
+    size: (u32, u32),
+}
+
+impl FontText {
+    /// The [`FontText`] constructor.
+    ///
+    /// The `size` is the font size in pixels, the `color` paints plain glyphs.
+    ///
+    /// # Panics
+    /// Panics when `fonts` is empty.
+    pub fn new(text: &str, fonts: &[Font], size: f32, color: Color) -> Self {
+        assert!(!fonts.is_empty(), "fonts cannot be empty");
+
+        Self {
+            text: text.to_owned(),
+            fonts: fonts.to_vec(),
+            size,
+            color,
+            antialias: Antialias::Grayscale,
+            hinting: Hinting::None,
+            raster: OnceLock::new(),
+        }
+    }
+
+    /// Sets the anti-aliasing mode. Defaults to [`Antialias::Grayscale`].
+    pub fn antialias(mut self, antialias: Antialias) -> Self {
+        self.antialias = antialias;
+        self
+    }
+
+    /// Sets the hinting mode. Defaults to [`Hinting::None`].
+    pub fn hinting(mut self, hinting: Hinting) -> Self {
+        self.hinting = hinting;
+        self
+    }
+
+    /// Returns the text size in pixels.
+    pub fn size(&self) -> (u32, u32) {
+        self.raster().size
+    }
+
+    fn raster(&self) -> &Raster {
+        self.raster.get_or_init(|| self.render())
+    }
+
+    fn render(&self) -> Raster {
+        let (size, color, hinting) = (self.size, self.color, self.hinting);
+        let faces: Vec<_> = self.fonts.iter().map(Font::face).collect();
+        let main = &faces[0];
+        let units = |face: &Face| size / face.units_per_em() as f32;
+        let snap_y = |v: f32| match hinting {
+            Hinting::None => v,
+            Hinting::Vertical | Hinting::Full => v.round(),
+        };
+
+        let ascent = snap_y(main.ascender() as f32 * units(main));
+        let line =
+            snap_y((main.ascender() - main.descender() + main.line_gap()) as f32 * units(main));
+
+        // Lay out glyphs first to know the canvas size
+        let mut glyphs = vec![];
+        let mut width: f32 = 0.;
+        let lines: Vec<_> = self.text.split('\n').collect();
+        for (row, text) in lines.iter().enumerate() {
+            let mut pen = 0.;
+            for ch in text.chars() {
+                let (face, id) = faces
+                    .iter()
+                    .find_map(|face| Some((face, face.glyph_index(ch)?)))
+                    .unwrap_or((main, GlyphId(0)));
+
+                let origin = (pen, row as f32 * line + ascent);
+                let advance = face.glyph_hor_advance(id).unwrap_or_default() as f32 * units(face);
+                pen += match hinting {
+                    Hinting::Full => advance.round(),
+                    Hinting::None | Hinting::Vertical => advance,
+                };
+
+                glyphs.push((face, id, origin));
+            }
+
+            width = width.max(pen);
+        }
+
+        let (w, h) = (
+            width.ceil() as u32,
+            (lines.len() as f32 * line).ceil() as u32,
+        );
+        let mut canvas = Canvas {
+            pixels: vec![Color::default(); w as usize * h as usize],
+            size: (w, h),
+        };
+
+        for (face, id, origin) in glyphs {
+            let scale = units(face);
+            let bitmap = face
+                .glyph_raster_image(id, size.ceil() as u16)
+                .filter(|im| im.format == RasterImageFormat::PNG)
+                .and_then(|im| {
+                    let decoded = image::load_from_memory(im.data).ok()?.to_rgba8();
+                    Some((im, Image::from(decoded)))
+                });
+
+            if let Some((im, decoded)) = bitmap {
+                let s = size / im.pixels_per_em as f32;
+                let left = origin.0 + im.x as f32 * s;
+                let top = origin.1 - (im.y as f32 + im.height as f32) * s;
+                canvas.draw_image(&decoded, (left, top), s);
+                continue;
+            }
+
+            let mut painter = ColorPainter {
+                face,
+                canvas: &mut canvas,
+                origin,
+                scale,
+                hinting,
+                antialias: match self.antialias {
+                    // Colored layers have no single color to split into subpixels
+                    Antialias::Subpixel { .. } => Antialias::Grayscale,
+                    antialias => antialias,
+                },
+                transforms: vec![Transform::default()],
+                outline: None,
+            };
+
+            let foreground = {
+                let [r, g, b, a] = color.into_byte_array();
+                RgbaColor::new(r, g, b, a)
+            };
+
+            if face.is_color_glyph(id)
+                && face
+                    .paint_color_glyph(id, 0, foreground, &mut painter)
+                    .is_some()
+            {
+                continue;
+            }
+
+            painter.outline_glyph(id);
+            if let Some(path) = painter.outline.take() {
+                canvas.fill(&path, color, self.antialias);
+            }
+        }
+
+        Raster {
+            pixels: canvas.pixels,
+            size: (w, h),
+        }
+    }
+}
+
+/// The anti-aliasing mode of text.
+#[derive(Clone, Copy)]
+pub enum Antialias {
+    /// Hard glyph edges.
+    None,
+    /// Smooth glyph edges with partial transparency.
+    Grayscale,
+    /// Smooth glyph edges with a triple horizontal resolution on LCD screens.
+    ///
+    /// Every color channel is covered separately, so the result is only exact
+    /// over the given `background`.
+    Subpixel {
+        order: SubpixelOrder,
+        background: Color,
+    },
+}
+
+/// The order of color subpixels of an LCD screen.
+#[derive(Clone, Copy)]
+pub enum SubpixelOrder {
+    Rgb,
+    Bgr,
+}
+
+/// The grid fitting of glyph outlines.
+#[derive(Clone, Copy)]
+pub enum Hinting {
+    /// Keeps exact outlines.
+    None,
+    /// Snaps horizontal edges and baselines to pixels, keeping horizontal spacing.
+    Vertical,
+    /// Snaps all outline points and glyph advances to pixels.
+    Full,
+}
+
+impl Source for FontText {
+    fn source(&self, (x, y): (i32, i32)) -> Color {
+        let raster = self.raster();
+        let (w, h) = raster.size;
+        if x < 0 || y < 0 || x >= w as i32 || y >= h as i32 {
+            return Color::default();
+        }
+
+        raster.pixels[(y as u32 * w + x as u32) as usize]
+    }
+
+    fn borders(&self) -> Option<Borders> {
+        let (w, h) = self.size();
+        Some(Borders {
+            w: (0, w as i32 - 1),
+            h: (0, h as i32 - 1),
+        })
+    }
+}
+
+struct Canvas {
+    pixels: Vec<Color>,
+    size: (u32, u32),
+}
+
+impl Canvas {
+    fn paint<S>(&mut self, source: &S, area: Borders)
+    where
+        S: Source,
+    {
+        let (w, h) = self.size;
+        let area = Borders {
+            w: (area.w.0.max(0), area.w.1.min(w as i32 - 1)),
+            h: (area.h.0.max(0), area.h.1.min(h as i32 - 1)),
+        };
+
+        for (x, y) in area.positions() {
+            let idx = (y as u32 * w + x as u32) as usize;
+            self.pixels[idx] = self.pixels[idx].overlay(source.source((x, y)));
+        }
+    }
+
+    fn fill(&mut self, path: &Path, color: Color, antialias: Antialias) {
+        struct Covered {
+            fill: Fill,
+            color: Color,
+            antialias: Antialias,
+        }
+
+        impl Source for Covered {
+            fn source(&self, (x, y): (i32, i32)) -> Color {
+                let (px, py) = (x as f32 + 0.5, y as f32 + 0.5);
+                let mut col = self.color;
+                match self.antialias {
+                    Antialias::None => {
+                        if self.fill.coverage((px, py)) < 0.5 {
+                            return Color::default();
+                        }
+                    }
+                    Antialias::Grayscale => col.a *= self.fill.coverage((px, py)),
+                    Antialias::Subpixel { order, background } => {
+                        // Sample thirds of a pixel and its neighbors, then smooth them
+                        // with a low-pass filter to tame color fringes
+                        const WEIGHTS: [f32; 5] = [1. / 9., 2. / 9., 3. / 9., 2. / 9., 1. / 9.];
+
+                        let thirds: [f32; 7] = std::array::from_fn(|i| {
+                            let x = px + (i as f32 - 3.) / 3.;
+                            self.fill.coverage((x, py))
+                        });
+
+                        let mut cover: [f32; 3] = std::array::from_fn(|c| {
+                            WEIGHTS.iter().zip(&thirds[c..]).map(|(w, t)| w * t).sum()
+                        });
+
+                        if let SubpixelOrder::Bgr = order {
+                            cover.reverse();
+                        }
+
+                        let a = (cover[0] + cover[1] + cover[2]) / 3.;
+                        if a <= 0. {
+                            return Color::default();
+                        }
+
+                        // Pick colors that give exact channels when painted over the background
+                        let channel =
+                            |fg: f32, bg: f32, c: f32| (bg + (fg - bg) * c / a).clamp(0., 1.);
+                        col = Color {
+                            r: channel(col.r, background.r, cover[0]),
+                            g: channel(col.g, background.g, cover[1]),
+                            b: channel(col.b, background.b, cover[2]),
+                            a: a * col.a,
+                        };
+                    }
+                }
+
+                col
+            }
+        }
+
+        let fill = Fill::new(path, Color::default());
+        if let Some(area) = fill.borders() {
+            let covered = Covered {
+                fill,
+                color,
+                antialias,
+            };
+
+            self.paint(&covered, area.expand(1));
+        }
+    }
+
+    fn draw_image(&mut self, im: &Image, (left, top): (f32, f32), scale: f32) {
+        struct Placed<'a> {
+            im: &'a Image,
+            origin: (f32, f32),
+            scale: f32,
+        }
+
+        impl Source for Placed<'_> {
+            fn source(&self, (x, y): (i32, i32)) -> Color {
+                let u = (x as f32 + 0.5 - self.origin.0) / self.scale;
+                let v = (y as f32 + 0.5 - self.origin.1) / self.scale;
+                let (w, h) = self.im.size();
+                if u < 0. || v < 0. || u >= w as f32 || v >= h as f32 {
+                    return Color::default();
+                }
+
+                sample(self.im, Filter::Linear, (u, v))
+            }
+        }
+
+        let (w, h) = im.size();
+        let area = Borders {
+            w: (left.floor() as _, (left + w as f32 * scale).ceil() as _),
+            h: (top.floor() as _, (top + h as f32 * scale).ceil() as _),
+        };
+
+        let placed = Placed {
+            im,
+            origin: (left, top),
+            scale,
+        };
+
+        self.paint(&placed, area);
+    }
+}
+
+/// Paints layered `COLR` glyphs onto a canvas.
+struct ColorPainter<'f, 'c> {
+    face: &'f Face<'f>,
+    canvas: &'c mut Canvas,
+    origin: (f32, f32),
+    scale: f32,
+    hinting: Hinting,
+    antialias: Antialias,
+    transforms: Vec<Transform>,
+    outline: Option<Path>,
+}
+
+impl ColorPainter<'_, '_> {
+    /// Maps a point in font units to the canvas.
+    fn map(&self, (x, y): (f32, f32)) -> (f32, f32) {
+        let t = self.transforms.last().copied().unwrap_or_default();
+        let (x, y) = (t.a * x + t.c * y + t.e, t.b * x + t.d * y + t.f);
+        let (x, y) = (
+            self.origin.0 + x * self.scale,
+            self.origin.1 - y * self.scale,
+        );
+        match self.hinting {
+            Hinting::None => (x, y),
+            Hinting::Vertical => (x, y.round()),
+            Hinting::Full => (x.round(), y.round()),
+        }
+    }
+}
+
+impl<'a> Painter<'a> for ColorPainter<'_, '_> {
+    fn outline_glyph(&mut self, glyph_id: GlyphId) {
+        let mut builder = Outline {
+            painter: self,
+            path: Path::new(),
+        };
+
+        let face = builder.painter.face;
+        let path = face
+            .outline_glyph(glyph_id, &mut builder)
+            .map(|_| builder.path);
+
+        self.outline = path;
+    }
+
+    fn paint(&mut self, paint: Paint<'a>) {
+        let average = |stops: &mut dyn Iterator<Item = RgbaColor>| {
+            let (sum, n) = stops.fold((Color::default(), 0), |(sum, n), c| (sum + rgba(c), n + 1));
+
+            sum * (1. / n.max(1) as f32)
+        };
+
+        let color = match paint {
+            Paint::Solid(c) => rgba(c),
+            Paint::LinearGradient(g) => average(&mut g.stops(0).map(|s| s.color)),
+            Paint::RadialGradient(g) => average(&mut g.stops(0).map(|s| s.color)),
+            Paint::SweepGradient(g) => average(&mut g.stops(0).map(|s| s.color)),
+        };
+
+        if let Some(path) = &self.outline {
+            self.canvas.fill(path, color, self.antialias);
+        }
+    }
+
+    fn push_clip(&mut self) {}
+
+    fn push_clip_box(&mut self, _: ClipBox) {}
+
+    fn pop_clip(&mut self) {}
+
+    fn push_layer(&mut self, _: CompositeMode) {}
+
+    fn pop_layer(&mut self) {}
+
+    fn push_transform(&mut self, t: Transform) {
+        let p = self.transforms.last().copied().unwrap_or_default();
+        self.transforms.push(Transform {
+            a: p.a * t.a + p.c * t.b,
+            b: p.b * t.a + p.d * t.b,
+            c: p.a * t.c + p.c * t.d,
+            d: p.b * t.c + p.d * t.d,
+            e: p.a * t.e + p.c * t.f + p.e,
+            f: p.b * t.e + p.d * t.f + p.f,
+        });
+    }
+
+    fn pop_transform(&mut self) {
+        self.transforms.pop();
+    }
+}
+
+/// Collects a glyph outline into a path on the canvas.
+struct Outline<'p, 'f, 'c> {
+    painter: &'p ColorPainter<'f, 'c>,
+    path: Path,
+}
+
+impl Outline<'_, '_, '_> {
+    fn update(&mut self, f: impl FnOnce(Path) -> Path) {
+        self.path = f(std::mem::take(&mut self.path));
+    }
+}
+
+impl OutlineBuilder for Outline<'_, '_, '_> {
+    fn move_to(&mut self, x: f32, y: f32) {
+        let p = self.painter.map((x, y));
+        self.update(|path| path.move_to(p));
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        let p = self.painter.map((x, y));
+        self.update(|path| path.line_to(p));
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let (c, p) = (self.painter.map((x1, y1)), self.painter.map((x, y)));
+        self.update(|path| path.quad_to(c, p));
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let c0 = self.painter.map((x1, y1));
+        let c1 = self.painter.map((x2, y2));
+        let p = self.painter.map((x, y));
+        self.update(|path| path.cubic_to(c0, c1, p));
+    }
+
+    fn close(&mut self) {
+        self.update(Path::close);
+    }
+}
+
+fn rgba(c: RgbaColor) -> Color {
+    Color::from_byte_array([c.red, c.green, c.blue, c.alpha])
+}
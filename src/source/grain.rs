@@ -0,0 +1,102 @@
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::Float;
+use crate::{
+    hash,
+    math::lerp,
+    source::{Borders, Source},
+    Color,
+};
+
+/// Overlays seeded film grain on a source.
+///
+/// The grain is strongest in midtones and fades out in shadows and highlights.
+pub struct Grain<S> {
+    source: S,
+    seed: u32,
+    frame: u32,
+    intensity: f32,
+    size: f32,
+}
+
+impl<S> Grain<S> {
+    /// The [`Grain`] constructor.
+    pub fn new(source: S, seed: u32) -> Self {
+        Self {
+            source,
+            seed,
+            frame: 0,
+            intensity: 0.1,
+            size: 1.,
+        }
+    }
+
+    /// Sets the grain intensity. Defaults to `0.1`.
+    pub fn intensity(mut self, intensity: f32) -> Self {
+        self.intensity = intensity.max(0.);
+        self
+    }
+
+    /// Sets the grain size in pixels. Defaults to `1`.
+    ///
+    /// # Panics
+    /// Panics when a `size` is less than one.
+    pub fn size(mut self, size: f32) -> Self {
+        assert!(size >= 1., "size cannot be less than one");
+        self.size = size;
+        self
+    }
+
+    /// Selects an animation frame, each frame has its own grain. Defaults to `0`.
+    pub fn frame(mut self, frame: u32) -> Self {
+        self.frame = frame;
+        self
+    }
+}
+
+impl<S> Source for Grain<S>
+where
+    S: Source,
+{
+    fn source(&self, (x, y): (i32, i32)) -> Color {
+        let col = self.source.source((x, y));
+        if !col.is_visible() {
+            return col;
+        }
+
+        let fx = x as f32 / self.size;
+        let fy = y as f32 / self.size;
+        let (cx, cy) = (fx.floor(), fy.floor());
+        let (tx, ty) = (fx - cx, fy - cy);
+        let (cx, cy) = (cx as i32, cy as i32);
+        let seed = hash::hash(self.seed, (self.frame as i32, 0));
+        let at = |dx: i32, dy: i32| {
+            hash::unit(seed, (cx.wrapping_add(dx), cy.wrapping_add(dy))) * 2. - 1.
+        };
+        let top = lerp(at(0, 0), at(1, 0), tx);
+        let bottom = lerp(at(0, 1), at(1, 1), tx);
+        let noise = lerp(top, bottom, ty);
+
+        let l = col.luminance().clamp(0., 1.);
+        let d = noise * self.intensity * 4. * l * (1. - l);
+        Color {
+            r: col.r + d,
+            g: col.g + d,
+            b: col.b + d,
+            a: col.a,
+        }
+        .clamp()
+    }
+
+    fn borders(&self) -> Option<Borders> {
+        self.source.borders()
+    }
+
+    fn reseed(&mut self, seed: u32) {
+        self.seed = hash::hash(self.seed, (seed as i32, 0));
+        self.source.reseed(seed);
+    }
+
+    fn prepare(&mut self, region: Borders) {
+        self.source.prepare(region);
+    }
+}
@@ -0,0 +1,142 @@
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::Float;
+use crate::{
+    math::smoothstep,
+    source::{Borders, Source},
+    Color,
+};
+
+/// Makes colors close to a key color transparent.
+///
+/// Colors are compared by chroma only, so shadows and highlights
+/// on a green screen are keyed out as well.
+pub struct ChromaKey<S> {
+    source: S,
+    key: Color,
+    tolerance: f32,
+    softness: f32,
+}
+
+impl<S> ChromaKey<S> {
+    /// The [`ChromaKey`] constructor.
+    ///
+    /// Colors with a chroma distance to the `key` less than the `tolerance` become transparent.
+    pub fn new(source: S, key: Color, tolerance: f32) -> Self {
+        Self {
+            source,
+            key,
+            tolerance: tolerance.max(0.),
+            softness: 0.1,
+        }
+    }
+
+    /// Sets the width of the partially transparent edge. Defaults to `0.1`.
+    pub fn softness(mut self, softness: f32) -> Self {
+        self.softness = softness.max(0.);
+        self
+    }
+}
+
+impl<S> Source for ChromaKey<S>
+where
+    S: Source,
+{
+    fn source(&self, pos: (i32, i32)) -> Color {
+        let col = self.source.source(pos);
+        let (cb, cr) = chroma(col);
+        let (kb, kr) = chroma(self.key);
+        let d = ((cb - kb).powi(2) + (cr - kr).powi(2)).sqrt();
+        let t = smoothstep(self.tolerance, self.tolerance + self.softness, d);
+        Color {
+            a: col.a * t,
+            ..col
+        }
+    }
+
+    fn borders(&self) -> Option<Borders> {
+        self.source.borders()
+    }
+
+    fn prepare(&mut self, region: Borders) {
+        self.source.prepare(region);
+    }
+
+    fn reseed(&mut self, seed: u32) {
+        self.source.reseed(seed);
+    }
+}
+
+/// Replaces colors close to one color with another.
+///
+/// Matched colors are shifted by the difference between the two colors,
+/// so the shading of the replaced area is kept.
+pub struct ReplaceColor<S> {
+    source: S,
+    from: Color,
+    to: Color,
+    tolerance: f32,
+    softness: f32,
+}
+
+impl<S> ReplaceColor<S> {
+    /// The [`ReplaceColor`] constructor.
+    ///
+    /// Colors with a distance to `from` less than the `tolerance` are replaced.
+    pub fn new(source: S, from: Color, to: Color, tolerance: f32) -> Self {
+        Self {
+            source,
+            from,
+            to,
+            tolerance: tolerance.max(0.),
+            softness: 0.1,
+        }
+    }
+
+    /// Sets the width of the partially replaced range. Defaults to `0.1`.
+    pub fn softness(mut self, softness: f32) -> Self {
+        self.softness = softness.max(0.);
+        self
+    }
+}
+
+impl<S> Source for ReplaceColor<S>
+where
+    S: Source,
+{
+    fn source(&self, pos: (i32, i32)) -> Color {
+        let col = self.source.source(pos);
+        let d = ((col.r - self.from.r).powi(2)
+            + (col.g - self.from.g).powi(2)
+            + (col.b - self.from.b).powi(2))
+        .sqrt();
+
+        let t = 1. - smoothstep(self.tolerance, self.tolerance + self.softness, d);
+        let shifted = Color {
+            r: col.r + self.to.r - self.from.r,
+            g: col.g + self.to.g - self.from.g,
+            b: col.b + self.to.b - self.from.b,
+            a: col.a,
+        }
+        .clamp();
+
+        col.lerp(shifted, t)
+    }
+
+    fn borders(&self) -> Option<Borders> {
+        self.source.borders()
+    }
+
+    fn prepare(&mut self, region: Borders) {
+        self.source.prepare(region);
+    }
+
+    fn reseed(&mut self, seed: u32) {
+        self.source.reseed(seed);
+    }
+}
+
+/// Returns the blue and red difference components of the color.
+fn chroma(col: Color) -> (f32, f32) {
+    let y = col.luminance();
+    ((col.b - y) / 1.8556, (col.r - y) / 1.5748)
+}
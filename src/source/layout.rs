@@ -0,0 +1,678 @@
+use {
+    crate::{
+        source::{self, Borders, Filter, Source},
+        Color,
+    },
+    alloc::{vec, vec::Vec},
+};
+
+/// Distances from each side of a rectangle.
+#[derive(Clone, Copy, Default)]
+pub struct Insets {
+    pub left: u32,
+    pub top: u32,
+    pub right: u32,
+    pub bottom: u32,
+}
+
+impl Insets {
+    pub fn uniform(v: u32) -> Self {
+        Self {
+            left: v,
+            top: v,
+            right: v,
+            bottom: v,
+        }
+    }
+}
+
+/// Scales a bordered texture to a given size keeping its corners unscaled.
+///
+/// The source is split by the insets into nine parts: corners are copied as is,
+/// edges are resized along one axis and the center along both.
+/// The result is placed at the origin.
+pub struct NineSlice<S> {
+    source: S,
+    region: Borders,
+    insets: Insets,
+    size: (u32, u32),
+    mode: SliceMode,
+}
+
+impl<S> NineSlice<S>
+where
+    S: Source,
+{
+    /// The [`NineSlice`] constructor.
+    ///
+    /// # Panics
+    /// Panics when the source has no borders.
+    pub fn new(source: S, insets: Insets, size: (u32, u32), mode: SliceMode) -> Self {
+        let region = source.borders().expect("the source must have borders");
+        Self {
+            source,
+            region,
+            insets,
+            size,
+            mode,
+        }
+    }
+}
+
+impl<S> Source for NineSlice<S>
+where
+    S: Source,
+{
+    fn source(&self, (x, y): (i32, i32)) -> Color {
+        let (w, h) = self.size;
+        if x < 0 || y < 0 || x >= w as i32 || y >= h as i32 {
+            return Color::default();
+        }
+
+        let (sw, sh) = self.region.size();
+        let Insets {
+            left,
+            top,
+            right,
+            bottom,
+        } = self.insets;
+
+        let sx = self.mode.map(x as u32, w, sw, (left, right));
+        let sy = self.mode.map(y as u32, h, sh, (top, bottom));
+        self.source
+            .source((self.region.w.0 + sx as i32, self.region.h.0 + sy as i32))
+    }
+
+    fn borders(&self) -> Option<Borders> {
+        let (w, h) = self.size;
+        Some(Borders {
+            w: (0, w as i32 - 1),
+            h: (0, h as i32 - 1),
+        })
+    }
+
+    fn prepare(&mut self, _: Borders) {
+        self.source.prepare(self.region);
+    }
+
+    fn reseed(&mut self, seed: u32) {
+        self.source.reseed(seed);
+    }
+}
+
+pub enum SliceMode {
+    /// Stretches edges and the center.
+    Stretch,
+
+    /// Repeats edges and the center.
+    Tile,
+}
+
+impl SliceMode {
+    /// Maps an output coordinate on one axis to the source one.
+    fn map(&self, v: u32, len: u32, src_len: u32, (a, b): (u32, u32)) -> u32 {
+        if v < a {
+            return v.min(src_len.saturating_sub(1));
+        }
+
+        if v >= len.saturating_sub(b) {
+            return src_len.saturating_sub(len - v);
+        }
+
+        let mid = src_len.saturating_sub(a + b).max(1);
+        let out_mid = len.saturating_sub(a + b).max(1);
+        let offset = v - a;
+        let offset = match self {
+            Self::Stretch => (offset as u64 * mid as u64 / out_mid as u64) as u32,
+            Self::Tile => offset % mid,
+        };
+
+        (a + offset).min(src_len.saturating_sub(1))
+    }
+}
+
+/// Fits a bounded source into a box of a given size.
+///
+/// The result is placed at the origin and clipped to the box.
+pub struct Fit<S> {
+    source: S,
+    region: Borders,
+    size: (u32, u32),
+    mode: FitMode,
+    align: (Align, Align),
+    filter: Filter,
+    scale: (f32, f32),
+    offset: (f32, f32),
+}
+
+impl<S> Fit<S>
+where
+    S: Source,
+{
+    /// The [`Fit`] constructor.
+    ///
+    /// The source is centered in the box and sampled with the linear filter by default.
+    ///
+    /// # Panics
+    /// Panics when the source has no borders.
+    pub fn new(source: S, size: (u32, u32), mode: FitMode) -> Self {
+        let region = source.borders().expect("the source must have borders");
+        let mut fit = Self {
+            source,
+            region,
+            size,
+            mode,
+            align: (Align::Center, Align::Center),
+            filter: Filter::Linear,
+            scale: (1., 1.),
+            offset: (0., 0.),
+        };
+
+        fit.layout();
+        fit
+    }
+
+    /// Sets the horizontal and vertical alignment in the box.
+    pub fn align(mut self, horizontal: Align, vertical: Align) -> Self {
+        self.align = (horizontal, vertical);
+        self.layout();
+        self
+    }
+
+    /// Sets the filter used to resize the source.
+    pub fn filter(mut self, filter: Filter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    fn layout(&mut self) {
+        let (sw, sh) = self.region.size();
+        let (sw, sh) = (sw.max(1) as f32, sh.max(1) as f32);
+        let (bw, bh) = (self.size.0 as f32, self.size.1 as f32);
+        let (kx, ky) = (bw / sw, bh / sh);
+        self.scale = match self.mode {
+            FitMode::Contain => (kx.min(ky), kx.min(ky)),
+            FitMode::Cover => (kx.max(ky), kx.max(ky)),
+            FitMode::Fill => (kx, ky),
+            FitMode::Keep => (1., 1.),
+        };
+
+        let (h, v) = self.align;
+        self.offset = (
+            h.offset(bw - sw * self.scale.0),
+            v.offset(bh - sh * self.scale.1),
+        );
+    }
+}
+
+impl<S> Source for Fit<S>
+where
+    S: Source,
+{
+    fn source(&self, (x, y): (i32, i32)) -> Color {
+        let (w, h) = self.size;
+        if x < 0 || y < 0 || x >= w as i32 || y >= h as i32 {
+            return Color::default();
+        }
+
+        let (sx, sy) = self.scale;
+        let (ox, oy) = self.offset;
+        let px = self.region.w.0 as f32 + (x as f32 + 0.5 - ox) / sx;
+        let py = self.region.h.0 as f32 + (y as f32 + 0.5 - oy) / sy;
+        source::sample(&self.source, self.filter, (px, py))
+    }
+
+    fn borders(&self) -> Option<Borders> {
+        let (w, h) = self.size;
+        Some(Borders {
+            w: (0, w as i32 - 1),
+            h: (0, h as i32 - 1),
+        })
+    }
+
+    fn prepare(&mut self, _: Borders) {
+        // Linear filtering reads one more pixel on every side
+        self.source.prepare(self.region.expand(1));
+    }
+
+    fn reseed(&mut self, seed: u32) {
+        self.source.reseed(seed);
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum FitMode {
+    /// Scales the source to fit entirely into the box, keeping its aspect ratio.
+    Contain,
+
+    /// Scales the source to cover the whole box, keeping its aspect ratio.
+    Cover,
+
+    /// Stretches the source to the box size.
+    Fill,
+
+    /// Keeps the source size.
+    Keep,
+}
+
+#[derive(Clone, Copy)]
+pub enum Align {
+    Start,
+    Center,
+    End,
+}
+
+impl Align {
+    /// Returns the offset of an item given the free space around it.
+    fn offset(self, free: f32) -> f32 {
+        match self {
+            Self::Start => 0.,
+            Self::Center => free * 0.5,
+            Self::End => free,
+        }
+    }
+}
+
+/// Arranges bounded sources into a grid of equal cells.
+///
+/// Cells are filled row by row and each source is fitted into its cell.
+/// The grid is placed at the origin.
+pub struct Grid<S> {
+    cells: Vec<Fit<S>>,
+    cols: u32,
+    cell: (u32, u32),
+    gutter: u32,
+    mode: FitMode,
+    align: (Align, Align),
+}
+
+impl<S> Grid<S>
+where
+    S: Source,
+{
+    /// The [`Grid`] constructor.
+    ///
+    /// Sources are fitted with [`FitMode::Contain`] and centered by default.
+    ///
+    /// # Panics
+    /// Panics when `cols` or a side of the `cell` is zero.
+    pub fn new(cols: u32, cell: (u32, u32)) -> Self {
+        assert!(cols > 0, "cols cannot be zero");
+        assert!(cell.0 > 0 && cell.1 > 0, "cell size cannot be zero");
+        Self {
+            cells: vec![],
+            cols,
+            cell,
+            gutter: 0,
+            mode: FitMode::Contain,
+            align: (Align::Center, Align::Center),
+        }
+    }
+
+    /// Sets the space between cells.
+    pub fn gutter(mut self, gutter: u32) -> Self {
+        self.gutter = gutter;
+        self
+    }
+
+    /// Sets the fit mode for sources pushed after this call.
+    pub fn fit(mut self, mode: FitMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Sets the alignment in cells for sources pushed after this call.
+    pub fn align(mut self, horizontal: Align, vertical: Align) -> Self {
+        self.align = (horizontal, vertical);
+        self
+    }
+
+    /// Adds a source to the next cell.
+    ///
+    /// # Panics
+    /// Panics when the source has no borders.
+    pub fn push(self, source: S) -> Self {
+        let mode = self.mode;
+        self.push_fit(source, mode)
+    }
+
+    /// Adds a source to the next cell with its own fit mode.
+    ///
+    /// # Panics
+    /// Panics when the source has no borders.
+    pub fn push_fit(mut self, source: S, mode: FitMode) -> Self {
+        self.add(source, mode);
+        self
+    }
+
+    /// Returns the grid size in pixels.
+    pub fn size(&self) -> (u32, u32) {
+        let n = self.cells.len() as u32;
+        let cols = self.cols.min(n);
+        let rows = n.div_ceil(self.cols);
+        let len = |n: u32, cell: u32| {
+            n.saturating_mul(cell.saturating_add(self.gutter))
+                .saturating_sub(self.gutter)
+        };
+        (len(cols, self.cell.0), len(rows, self.cell.1))
+    }
+
+    fn add(&mut self, source: S, mode: FitMode) {
+        let (h, v) = self.align;
+        self.cells
+            .push(Fit::new(source, self.cell, mode).align(h, v));
+    }
+}
+
+impl<S> Extend<S> for Grid<S>
+where
+    S: Source,
+{
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = S>,
+    {
+        for source in iter {
+            self.add(source, self.mode);
+        }
+    }
+}
+
+impl<S> Source for Grid<S>
+where
+    S: Source,
+{
+    fn source(&self, (x, y): (i32, i32)) -> Color {
+        if x < 0 || y < 0 {
+            return Color::default();
+        }
+
+        let (cw, ch) = self.cell;
+        let (sx, sy) = (
+            cw.saturating_add(self.gutter),
+            ch.saturating_add(self.gutter),
+        );
+        let (x, y) = (x as u32, y as u32);
+        let (col, row) = (x / sx, y / sy);
+        let (lx, ly) = (x % sx, y % sy);
+        if col >= self.cols || lx >= cw || ly >= ch {
+            return Color::default();
+        }
+
+        let index = row.checked_mul(self.cols).and_then(|n| n.checked_add(col));
+
+        match index.and_then(|n| self.cells.get(n as usize)) {
+            Some(cell) => cell.source((lx as _, ly as _)),
+            None => Color::default(),
+        }
+    }
+
+    fn borders(&self) -> Option<Borders> {
+        let (w, h) = self.size();
+        Some(Borders {
+            w: (0, w as i32 - 1),
+            h: (0, h as i32 - 1),
+        })
+    }
+
+    fn prepare(&mut self, region: Borders) {
+        for cell in &mut self.cells {
+            cell.prepare(region);
+        }
+    }
+
+    fn reseed(&mut self, seed: u32) {
+        for cell in &mut self.cells {
+            cell.reseed(seed);
+        }
+    }
+}
+
+/// Places bounded sources side by side from left to right.
+///
+/// The stack is placed at the origin and sources are aligned vertically.
+pub struct HStack<S>(Stack<S>);
+
+impl<S> HStack<S>
+where
+    S: Source,
+{
+    /// The [`HStack`] constructor.
+    ///
+    /// Sources are aligned to the top by default.
+    pub fn new(spacing: u32) -> Self {
+        Self(Stack::new(Axis::Horizontal, spacing))
+    }
+
+    /// Sets the vertical alignment.
+    pub fn align(mut self, align: Align) -> Self {
+        self.0.align = align;
+        self
+    }
+
+    /// Adds a source to the end of the stack.
+    ///
+    /// # Panics
+    /// Panics when the source has no borders.
+    pub fn push(mut self, source: S) -> Self {
+        self.0.add(source);
+        self
+    }
+
+    /// Returns the stack size in pixels.
+    pub fn size(&self) -> (u32, u32) {
+        self.0.size()
+    }
+}
+
+impl<S> Extend<S> for HStack<S>
+where
+    S: Source,
+{
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = S>,
+    {
+        iter.into_iter().for_each(|source| self.0.add(source));
+    }
+}
+
+impl<S> Source for HStack<S>
+where
+    S: Source,
+{
+    fn source(&self, pos: (i32, i32)) -> Color {
+        self.0.source(pos)
+    }
+
+    fn borders(&self) -> Option<Borders> {
+        self.0.borders()
+    }
+
+    fn prepare(&mut self, _: Borders) {
+        for item in &mut self.0.items {
+            item.source.prepare(item.region);
+        }
+    }
+
+    fn reseed(&mut self, seed: u32) {
+        for item in &mut self.0.items {
+            item.source.reseed(seed);
+        }
+    }
+}
+
+/// Places bounded sources one under another from top to bottom.
+///
+/// The stack is placed at the origin and sources are aligned horizontally.
+pub struct VStack<S>(Stack<S>);
+
+impl<S> VStack<S>
+where
+    S: Source,
+{
+    /// The [`VStack`] constructor.
+    ///
+    /// Sources are aligned to the left by default.
+    pub fn new(spacing: u32) -> Self {
+        Self(Stack::new(Axis::Vertical, spacing))
+    }
+
+    /// Sets the horizontal alignment.
+    pub fn align(mut self, align: Align) -> Self {
+        self.0.align = align;
+        self
+    }
+
+    /// Adds a source to the end of the stack.
+    ///
+    /// # Panics
+    /// Panics when the source has no borders.
+    pub fn push(mut self, source: S) -> Self {
+        self.0.add(source);
+        self
+    }
+
+    /// Returns the stack size in pixels.
+    pub fn size(&self) -> (u32, u32) {
+        self.0.size()
+    }
+}
+
+impl<S> Extend<S> for VStack<S>
+where
+    S: Source,
+{
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = S>,
+    {
+        iter.into_iter().for_each(|source| self.0.add(source));
+    }
+}
+
+impl<S> Source for VStack<S>
+where
+    S: Source,
+{
+    fn source(&self, pos: (i32, i32)) -> Color {
+        self.0.source(pos)
+    }
+
+    fn borders(&self) -> Option<Borders> {
+        self.0.borders()
+    }
+
+    fn prepare(&mut self, _: Borders) {
+        for item in &mut self.0.items {
+            item.source.prepare(item.region);
+        }
+    }
+
+    fn reseed(&mut self, seed: u32) {
+        for item in &mut self.0.items {
+            item.source.reseed(seed);
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Axis {
+    Horizontal,
+    Vertical,
+}
+
+struct Item<S> {
+    source: S,
+    region: Borders,
+    start: u32,
+}
+
+struct Stack<S> {
+    items: Vec<Item<S>>,
+    axis: Axis,
+    spacing: u32,
+    align: Align,
+    main: u32,
+    cross: u32,
+}
+
+impl<S> Stack<S>
+where
+    S: Source,
+{
+    fn new(axis: Axis, spacing: u32) -> Self {
+        Self {
+            items: vec![],
+            axis,
+            spacing,
+            align: Align::Start,
+            main: 0,
+            cross: 0,
+        }
+    }
+
+    fn add(&mut self, source: S) {
+        let region = source.borders().expect("the source must have borders");
+        let (main, cross) = self.split(region.size());
+        let start = if self.items.is_empty() {
+            0
+        } else {
+            self.main + self.spacing
+        };
+
+        self.items.push(Item {
+            source,
+            region,
+            start,
+        });
+
+        self.main = start + main;
+        self.cross = self.cross.max(cross);
+    }
+
+    /// Splits a pair into main and cross axis components, or joins them back.
+    fn split<T>(&self, (a, b): (T, T)) -> (T, T) {
+        match self.axis {
+            Axis::Horizontal => (a, b),
+            Axis::Vertical => (b, a),
+        }
+    }
+
+    fn size(&self) -> (u32, u32) {
+        self.split((self.main, self.cross))
+    }
+
+    fn source(&self, pos: (i32, i32)) -> Color {
+        let (m, c) = self.split(pos);
+        if m < 0 || c < 0 {
+            return Color::default();
+        }
+
+        let (m, c) = (m as u32, c as u32);
+        let idx = self.items.partition_point(|item| item.start <= m);
+        let Some(item) = idx.checked_sub(1).map(|i| &self.items[i]) else {
+            return Color::default();
+        };
+
+        let (main, cross) = self.split(item.region.size());
+        let offset = self.align.offset((self.cross - cross) as f32) as u32;
+        if m >= item.start + main || c < offset || c >= offset + cross {
+            return Color::default();
+        }
+
+        let (lx, ly) = self.split((m - item.start, c - offset));
+        item.source.source((
+            item.region.w.0.wrapping_add(lx as i32),
+            item.region.h.0.wrapping_add(ly as i32),
+        ))
+    }
+
+    fn borders(&self) -> Option<Borders> {
+        let (w, h) = self.size();
+        Some(Borders {
+            w: (0, w as i32 - 1),
+            h: (0, h as i32 - 1),
+        })
+    }
+}
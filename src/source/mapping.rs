@@ -0,0 +1,432 @@
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::Float;
+use {
+    super::sample_in,
+    crate::{
+        source::{Borders, Field, Filter, Source},
+        Color, LerpSpace,
+    },
+    core::ops::Range,
+};
+
+/// A transform of positions, from the output to the source.
+///
+/// Mappings compose with [`then`](Self::then) into one mapping, so a chain of
+/// transforms costs one [`Mapped`] source instead of a wrapper per transform.
+/// Chains of [`Affine`] transforms collapse further, into a single matrix.
+pub trait Mapping {
+    /// Maps an output position to the source position to sample.
+    fn map(&self, pos: (f32, f32)) -> (f32, f32);
+
+    /// Returns how many source pixels one output pixel covers along each axis.
+    fn footprint(&self) -> f32 {
+        1.
+    }
+
+    /// Returns the output region covering the source `borders`,
+    /// if the mapping can bound it.
+    fn bounds(&self, borders: Borders) -> Option<Borders> {
+        _ = borders;
+        None
+    }
+
+    /// Returns the source region read when the output `region` is rendered,
+    /// if the mapping can bound it.
+    fn region(&self, region: Borders) -> Option<Borders> {
+        _ = region;
+        None
+    }
+
+    /// Returns the whole pixel offset the mapping moves the image by,
+    /// if it does nothing else, so the source can be read without filtering.
+    fn translation(&self) -> Option<(i32, i32)> {
+        None
+    }
+
+    /// Applies the transform of this mapping first, then the `next` one.
+    fn then<N>(self, next: N) -> Then<Self, N>
+    where
+        Self: Sized,
+    {
+        Then { first: self, next }
+    }
+}
+
+impl<F> Mapping for F
+where
+    F: Fn((f32, f32)) -> (f32, f32),
+{
+    fn map(&self, pos: (f32, f32)) -> (f32, f32) {
+        self(pos)
+    }
+}
+
+/// Two mappings applied one after another, made by [`Mapping::then`].
+pub struct Then<A, B> {
+    first: A,
+    next: B,
+}
+
+impl<A, B> Mapping for Then<A, B>
+where
+    A: Mapping,
+    B: Mapping,
+{
+    fn map(&self, pos: (f32, f32)) -> (f32, f32) {
+        // Positions go from the output back to the source,
+        // so the last transform maps first
+        self.first.map(self.next.map(pos))
+    }
+
+    fn footprint(&self) -> f32 {
+        self.first.footprint() * self.next.footprint()
+    }
+
+    fn bounds(&self, borders: Borders) -> Option<Borders> {
+        self.next.bounds(self.first.bounds(borders)?)
+    }
+
+    fn region(&self, region: Borders) -> Option<Borders> {
+        self.first.region(self.next.region(region)?)
+    }
+
+    fn translation(&self) -> Option<(i32, i32)> {
+        let (ax, ay) = self.first.translation()?;
+        let (bx, by) = self.next.translation()?;
+        Some((ax.wrapping_add(bx), ay.wrapping_add(by)))
+    }
+}
+
+/// An affine transform: any combination of offsets, scales and rotations.
+///
+/// Constructors describe how the transform moves the image,
+/// while [`map`](Mapping::map) goes the other way, from the output to the source.
+#[derive(Clone, Copy)]
+pub struct Affine {
+    /// The output to source matrix, row by row, without the last `0 0 1` row.
+    m: [f32; 6],
+}
+
+impl Affine {
+    /// The transform that keeps positions as they are.
+    pub const IDENTITY: Self = Self {
+        m: [1., 0., 0., 0., 1., 0.],
+    };
+
+    /// Moves the image by the `offset`, like [`Offset`](crate::source::Offset).
+    pub fn offset((dx, dy): (f32, f32)) -> Self {
+        Self {
+            m: [1., 0., -dx, 0., 1., -dy],
+        }
+    }
+
+    /// Scales the image by the `factor` around the origin.
+    ///
+    /// # Panics
+    /// Panics when a `factor` is less than or equal to zero.
+    pub fn scale(factor: f32) -> Self {
+        assert!(
+            factor > f32::EPSILON,
+            "factor cannot be less than or equal to zero",
+        );
+
+        let k = 1. / factor;
+        Self {
+            m: [k, 0., 0., 0., k, 0.],
+        }
+    }
+
+    /// Rotates the image by the `angle` in radians around the `center`,
+    /// clockwise on screen.
+    pub fn rotate(angle: f32, (cx, cy): (f32, f32)) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        Self {
+            m: [
+                cos,
+                sin,
+                cx - cos * cx - sin * cy,
+                -sin,
+                cos,
+                cy + sin * cx - cos * cy,
+            ],
+        }
+    }
+
+    /// Applies this transform first, then the `next` one, as a single matrix.
+    pub fn compose(self, next: Self) -> Self {
+        let [a, b, c, d, e, f] = self.m;
+        let [p, q, r, s, t, u] = next.m;
+        Self {
+            m: [
+                a * p + b * s,
+                a * q + b * t,
+                a * r + b * u + c,
+                d * p + e * s,
+                d * q + e * t,
+                d * r + e * u + f,
+            ],
+        }
+    }
+
+    /// Returns the inverse transform, if the matrix isn't degenerate.
+    pub fn inverse(self) -> Option<Self> {
+        let [a, b, c, d, e, f] = self.m;
+        let det = a * e - b * d;
+        if det.abs() <= f32::EPSILON {
+            return None;
+        }
+
+        let k = 1. / det;
+        Some(Self {
+            m: [
+                e * k,
+                -b * k,
+                (b * f - c * e) * k,
+                -d * k,
+                a * k,
+                (c * d - a * f) * k,
+            ],
+        })
+    }
+
+    /// Maps the corners of the region and returns the pixels covering them.
+    fn map_region(self, region: Borders) -> Borders {
+        let Borders {
+            w: (x0, x1),
+            h: (y0, y1),
+        } = region;
+
+        let (x0, x1) = (x0 as f32, x1 as f32 + 1.);
+        let (y0, y1) = (y0 as f32, y1 as f32 + 1.);
+        let (mut lo, mut hi) = (
+            (f32::INFINITY, f32::INFINITY),
+            (f32::NEG_INFINITY, f32::NEG_INFINITY),
+        );
+        for pos in [(x0, y0), (x1, y0), (x0, y1), (x1, y1)] {
+            let (x, y) = self.map(pos);
+            lo = (lo.0.min(x), lo.1.min(y));
+            hi = (hi.0.max(x), hi.1.max(y));
+        }
+
+        let clamp = |v: f32| v.clamp(i32::MIN as f32, i32::MAX as f32) as i32;
+        Borders {
+            w: (clamp(lo.0.floor()), clamp(hi.0.ceil()) - 1),
+            h: (clamp(lo.1.floor()), clamp(hi.1.ceil()) - 1),
+        }
+    }
+}
+
+impl Default for Affine {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Mapping for Affine {
+    fn map(&self, (x, y): (f32, f32)) -> (f32, f32) {
+        let [a, b, c, d, e, f] = self.m;
+        (a * x + b * y + c, d * x + e * y + f)
+    }
+
+    fn footprint(&self) -> f32 {
+        let [a, b, _, d, e, _] = self.m;
+        (a * e - b * d).abs().sqrt()
+    }
+
+    fn bounds(&self, borders: Borders) -> Option<Borders> {
+        self.inverse().map(|inv| inv.map_region(borders))
+    }
+
+    fn region(&self, region: Borders) -> Option<Borders> {
+        Some(self.map_region(region))
+    }
+
+    fn translation(&self) -> Option<(i32, i32)> {
+        // Larger floats skip whole numbers
+        const EXACT: f32 = (1 << f32::MANTISSA_DIGITS) as f32;
+
+        let [a, b, c, d, e, f] = self.m;
+        let whole = |v: f32| (v.abs() <= EXACT && v.fract() == 0.).then_some(-v as i32);
+        if [a, b, d, e] != [1., 0., 0., 1.] {
+            return None;
+        }
+
+        Some((whole(c)?, whole(f)?))
+    }
+}
+
+/// Displaces positions by the values of a noise field,
+/// like [`DomainWarp`](crate::source::DomainWarp) does for fields.
+pub struct Warp<F> {
+    field: F,
+    strength: f32,
+}
+
+impl<F> Warp<F> {
+    /// The [`Warp`] constructor.
+    ///
+    /// Positions are displaced by the `field` values multiplied by the `strength`.
+    pub fn new(field: F, strength: f32) -> Self {
+        Self { field, strength }
+    }
+}
+
+impl<F> Mapping for Warp<F>
+where
+    F: Field,
+{
+    fn map(&self, (x, y): (f32, f32)) -> (f32, f32) {
+        let dx = self.field.value((x, y));
+        let dy = self.field.value((x + 5.2, y + 1.3));
+        (x + dx * self.strength, y + dy * self.strength)
+    }
+}
+
+/// Samples a source at positions transformed by a [`Mapping`].
+///
+/// Pixel centers are mapped, so the identity mapping keeps the source as it is.
+/// Mappings that only move the image by whole pixels read the source directly, row by row.
+pub struct Mapped<S, M> {
+    pub(super) source: S,
+    pub(super) mapping: M,
+    pub(super) filter: Filter,
+    space: LerpSpace,
+}
+
+impl<S, M> Mapped<S, M> {
+    /// The [`Mapped`] constructor.
+    pub fn new(source: S, mapping: M) -> Self {
+        Self {
+            source,
+            mapping,
+            filter: Filter::Linear,
+            space: LerpSpace::Srgb,
+        }
+    }
+
+    /// Sets the filter used to sample the source. Defaults to [`Filter::Linear`].
+    pub fn filter(mut self, filter: Filter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// Sets the space the linear filter interpolates in. Defaults to [`LerpSpace::Srgb`].
+    ///
+    /// Other spaces sample the source directly, without its
+    /// [`sample_scaled`](Source::sample_scaled) implementation.
+    pub fn space(mut self, space: LerpSpace) -> Self {
+        self.space = space;
+        self
+    }
+
+    /// Applies the current mapping first, then the `next` one.
+    pub fn then<N>(self, next: N) -> Mapped<S, Then<M, N>>
+    where
+        M: Mapping,
+    {
+        Mapped {
+            source: self.source,
+            mapping: self.mapping.then(next),
+            filter: self.filter,
+            space: self.space,
+        }
+    }
+}
+
+impl<S> Mapped<S, Affine> {
+    /// Applies the current transform first, then the `next` one,
+    /// keeping a single matrix.
+    pub fn transform(mut self, next: Affine) -> Self {
+        self.mapping = self.mapping.compose(next);
+        self
+    }
+}
+
+impl<S, M> Mapped<S, M>
+where
+    M: Mapping,
+{
+    /// Returns how far from a mapped position the filter reads source pixels.
+    fn reach(&self) -> i32 {
+        // Whole pixel offsets read the source pixel as it is
+        if self.mapping.translation().is_some() {
+            return 0;
+        }
+
+        let reach = match self.filter {
+            Filter::Near => 1.,
+            Filter::Linear => 2.,
+        };
+
+        (reach * self.mapping.footprint().max(1.)).ceil() as i32
+    }
+
+    /// Samples the source at the mapped `pos` with the filter.
+    pub(super) fn sample(&self, pos: (f32, f32)) -> Color
+    where
+        S: Source,
+    {
+        let (u, v) = self.mapping.map(pos);
+        let footprint = self.mapping.footprint();
+        match (self.filter, self.space) {
+            (Filter::Linear, LerpSpace::Srgb) | (Filter::Near, _) => {
+                self.source.sample_scaled((u, v), footprint, self.filter)
+            }
+            (Filter::Linear, space) => sample_in(&self.source, Filter::Linear, space, (u, v)),
+        }
+    }
+}
+
+impl<S, M> Source for Mapped<S, M>
+where
+    S: Source,
+    M: Mapping,
+{
+    fn source(&self, (x, y): (i32, i32)) -> Color {
+        if let Some((dx, dy)) = self.mapping.translation() {
+            return self.source.source((x.wrapping_sub(dx), y.wrapping_sub(dy)));
+        }
+
+        self.sample((x as f32 + 0.5, y as f32 + 0.5))
+    }
+
+    fn borders(&self) -> Option<Borders> {
+        let borders = self.source.borders()?;
+        if let Some(offset) = self.mapping.translation() {
+            return Some(borders.shift(offset));
+        }
+
+        self.mapping.bounds(borders.expand(self.reach()))
+    }
+
+    fn prepare(&mut self, region: Borders) {
+        if let Some((dx, dy)) = self.mapping.translation() {
+            self.source
+                .prepare(region.shift((dx.wrapping_neg(), dy.wrapping_neg())));
+            return;
+        }
+
+        // Unbounded mappings can read anywhere, so the source isn't prepared
+        if let Some(region) = self.mapping.region(region) {
+            self.source.prepare(region.expand(self.reach()));
+        }
+    }
+
+    fn reseed(&mut self, seed: u32) {
+        self.source.reseed(seed);
+    }
+
+    fn source_row(&self, y: i32, xs: Range<i32>, out: &mut [Color]) {
+        match self.mapping.translation() {
+            Some((dx, dy)) => {
+                let xs = xs.start.wrapping_sub(dx)..xs.end.wrapping_sub(dx);
+                self.source.source_row(y.wrapping_sub(dy), xs, out);
+            }
+            None => {
+                for (x, col) in xs.zip(out) {
+                    *col = self.source((x, y));
+                }
+            }
+        }
+    }
+}
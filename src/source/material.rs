@@ -0,0 +1,172 @@
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::Float;
+use {
+    crate::{
+        source::{adjust::ramp, Fbm, Field, Perlin, Source},
+        Color, LerpSpace,
+    },
+    alloc::{vec, vec::Vec},
+    core::f32::consts::PI,
+};
+
+/// The number of noise octaves of materials.
+const OCTAVES: u8 = 5;
+
+/// Wood with growth rings around the origin, distorted by noise.
+pub struct Wood {
+    noise: Fbm<Perlin>,
+    scale: f32,
+    frequency: f32,
+    turbulence: f32,
+    stops: Vec<(f32, Color)>,
+}
+
+impl Wood {
+    /// The [`Wood`] constructor.
+    pub fn new(seed: u32) -> Self {
+        Self {
+            noise: Fbm::new(Perlin::new(seed), OCTAVES),
+            scale: 1. / 64.,
+            frequency: 6.,
+            turbulence: 0.15,
+            stops: vec![
+                (0., Color::from_u32(0xE3B47CFF)),
+                (0.6, Color::from_u32(0xC08A52FF)),
+                (1., Color::from_u32(0x744626FF)),
+            ],
+        }
+    }
+
+    /// Sets the size of a noise unit in pixels. Defaults to `64`.
+    ///
+    /// # Panics
+    /// Panics when a `scale` is less than or equal to zero.
+    pub fn scale(mut self, scale: f32) -> Self {
+        assert!(
+            scale > f32::EPSILON,
+            "scale cannot be less than or equal to zero",
+        );
+
+        self.scale = 1. / scale;
+        self
+    }
+
+    /// Sets the number of rings per noise unit. Defaults to `6`.
+    pub fn frequency(mut self, frequency: f32) -> Self {
+        self.frequency = frequency;
+        self
+    }
+
+    /// Sets how far noise displaces the rings in noise units. Defaults to `0.15`.
+    pub fn turbulence(mut self, turbulence: f32) -> Self {
+        self.turbulence = turbulence;
+        self
+    }
+
+    /// Sets the color ramp across a ring, from its inner edge to the outer one.
+    ///
+    /// Each stop is a position in range `0..=1` paired with its color.
+    ///
+    /// # Panics
+    /// Panics when `stops` is empty.
+    pub fn stops(mut self, mut stops: Vec<(f32, Color)>) -> Self {
+        assert!(!stops.is_empty(), "stops cannot be empty");
+        stops.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+        self.stops = stops;
+        self
+    }
+}
+
+impl Source for Wood {
+    fn source(&self, (x, y): (i32, i32)) -> Color {
+        let (x, y) = ((x as f32 + 0.5) * self.scale, (y as f32 + 0.5) * self.scale);
+        let n = self.noise.value((x, y));
+        let v = (x.hypot(y) + n * self.turbulence) * self.frequency;
+        ramp(&self.stops, v.rem_euclid(1.), LerpSpace::Srgb)
+    }
+
+    fn reseed(&mut self, seed: u32) {
+        self.noise.reseed(seed);
+    }
+}
+
+/// Marble with veins running diagonally, distorted by noise.
+pub struct Marble {
+    noise: Fbm<Perlin>,
+    scale: f32,
+    frequency: f32,
+    turbulence: f32,
+    stops: Vec<(f32, Color)>,
+}
+
+impl Marble {
+    /// The [`Marble`] constructor.
+    pub fn new(seed: u32) -> Self {
+        Self {
+            noise: Fbm::new(Perlin::new(seed), OCTAVES),
+            scale: 1. / 64.,
+            frequency: 1.,
+            turbulence: 2.,
+            stops: vec![
+                (0., Color::from_u32(0xF2F0EBFF)),
+                (0.75, Color::from_u32(0xD9D5CDFF)),
+                (1., Color::from_u32(0x55565CFF)),
+            ],
+        }
+    }
+
+    /// Sets the size of a noise unit in pixels. Defaults to `64`.
+    ///
+    /// # Panics
+    /// Panics when a `scale` is less than or equal to zero.
+    pub fn scale(mut self, scale: f32) -> Self {
+        assert!(
+            scale > f32::EPSILON,
+            "scale cannot be less than or equal to zero",
+        );
+
+        self.scale = 1. / scale;
+        self
+    }
+
+    /// Sets the number of veins per noise unit. Defaults to `1`.
+    pub fn frequency(mut self, frequency: f32) -> Self {
+        self.frequency = frequency;
+        self
+    }
+
+    /// Sets how far noise displaces the veins in noise units. Defaults to `2`.
+    pub fn turbulence(mut self, turbulence: f32) -> Self {
+        self.turbulence = turbulence;
+        self
+    }
+
+    /// Sets the color ramp from the stone between veins to the middle of a vein.
+    ///
+    /// Each stop is a position in range `0..=1` paired with its color.
+    ///
+    /// # Panics
+    /// Panics when `stops` is empty.
+    pub fn stops(mut self, mut stops: Vec<(f32, Color)>) -> Self {
+        assert!(!stops.is_empty(), "stops cannot be empty");
+        stops.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+        self.stops = stops;
+        self
+    }
+}
+
+impl Source for Marble {
+    fn source(&self, (x, y): (i32, i32)) -> Color {
+        let (x, y) = ((x as f32 + 0.5) * self.scale, (y as f32 + 0.5) * self.scale);
+        let n = self.noise.value((x, y));
+        let v = ((x + y) * core::f32::consts::FRAC_1_SQRT_2 + n * self.turbulence) * self.frequency;
+
+        // Veins are where the wave crosses zero
+        let t = 1. - (v * PI).sin().abs();
+        ramp(&self.stops, t, LerpSpace::Srgb)
+    }
+
+    fn reseed(&mut self, seed: u32) {
+        self.noise.reseed(seed);
+    }
+}
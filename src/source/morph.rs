@@ -0,0 +1,210 @@
+use crate::{
+    source::{Borders, Source},
+    Color,
+};
+
+/// Grows bright or opaque areas of a source.
+///
+/// Each pixel takes the neighbor color with the largest key value.
+pub struct Dilate<S> {
+    source: S,
+    shape: Shape,
+}
+
+impl<S> Dilate<S> {
+    pub fn new(source: S, radius: u8, element: Element, key: MorphKey) -> Self {
+        Self {
+            source,
+            shape: Shape {
+                radius: radius as i32,
+                element,
+                key,
+            },
+        }
+    }
+}
+
+impl<S> Source for Dilate<S>
+where
+    S: Source,
+{
+    fn source(&self, pos: (i32, i32)) -> Color {
+        self.shape.select(&self.source, pos, |a, b| a > b)
+    }
+
+    fn borders(&self) -> Option<Borders> {
+        self.source.borders().map(|b| b.expand(self.shape.radius))
+    }
+
+    fn prepare(&mut self, region: Borders) {
+        self.source.prepare(region.expand(self.shape.radius));
+    }
+
+    fn reseed(&mut self, seed: u32) {
+        self.source.reseed(seed);
+    }
+}
+
+/// Shrinks bright or opaque areas of a source.
+///
+/// Each pixel takes the neighbor color with the smallest key value.
+pub struct Erode<S> {
+    source: S,
+    shape: Shape,
+}
+
+impl<S> Erode<S> {
+    pub fn new(source: S, radius: u8, element: Element, key: MorphKey) -> Self {
+        Self {
+            source,
+            shape: Shape {
+                radius: radius as i32,
+                element,
+                key,
+            },
+        }
+    }
+}
+
+impl<S> Source for Erode<S>
+where
+    S: Source,
+{
+    fn source(&self, pos: (i32, i32)) -> Color {
+        self.shape.select(&self.source, pos, |a, b| a < b)
+    }
+
+    fn borders(&self) -> Option<Borders> {
+        self.source.borders()
+    }
+
+    fn prepare(&mut self, region: Borders) {
+        self.source.prepare(region.expand(self.shape.radius));
+    }
+
+    fn reseed(&mut self, seed: u32) {
+        self.source.reseed(seed);
+    }
+}
+
+/// Morphological opening, an erosion followed by a dilation.
+///
+/// Removes small bright or opaque specks while keeping the size of larger areas.
+pub struct Open<S>(Dilate<Erode<S>>);
+
+impl<S> Open<S> {
+    pub fn new(source: S, radius: u8, element: Element, key: MorphKey) -> Self {
+        let erode = Erode::new(source, radius, element, key);
+        Self(Dilate::new(erode, radius, element, key))
+    }
+}
+
+impl<S> Source for Open<S>
+where
+    S: Source,
+{
+    fn source(&self, pos: (i32, i32)) -> Color {
+        self.0.source(pos)
+    }
+
+    fn borders(&self) -> Option<Borders> {
+        self.0.borders()
+    }
+
+    fn prepare(&mut self, region: Borders) {
+        self.0.prepare(region);
+    }
+
+    fn reseed(&mut self, seed: u32) {
+        self.0.reseed(seed);
+    }
+}
+
+/// Morphological closing, a dilation followed by an erosion.
+///
+/// Fills small dark or transparent holes while keeping the size of larger areas.
+pub struct Close<S>(Erode<Dilate<S>>);
+
+impl<S> Close<S> {
+    pub fn new(source: S, radius: u8, element: Element, key: MorphKey) -> Self {
+        let dilate = Dilate::new(source, radius, element, key);
+        Self(Erode::new(dilate, radius, element, key))
+    }
+}
+
+impl<S> Source for Close<S>
+where
+    S: Source,
+{
+    fn source(&self, pos: (i32, i32)) -> Color {
+        self.0.source(pos)
+    }
+
+    fn borders(&self) -> Option<Borders> {
+        self.0.borders()
+    }
+
+    fn prepare(&mut self, region: Borders) {
+        self.0.prepare(region);
+    }
+
+    fn reseed(&mut self, seed: u32) {
+        self.0.reseed(seed);
+    }
+}
+
+/// The structuring element.
+#[derive(Clone, Copy)]
+pub enum Element {
+    Square,
+    Disc,
+}
+
+/// The value the morphological operations compare.
+#[derive(Clone, Copy)]
+pub enum MorphKey {
+    Alpha,
+    Luminance,
+}
+
+struct Shape {
+    radius: i32,
+    element: Element,
+    key: MorphKey,
+}
+
+impl Shape {
+    fn select<S, F>(&self, source: &S, (x, y): (i32, i32), better: F) -> Color
+    where
+        S: Source,
+        F: Fn(f32, f32) -> bool,
+    {
+        let key = |col: Color| match self.key {
+            MorphKey::Alpha => col.a,
+            MorphKey::Luminance => col.luminance(),
+        };
+
+        let radius = self.radius;
+        let rsqr = radius * radius;
+        let mut best = source.source((x, y));
+        let mut best_key = key(best);
+        for dy in -radius..=radius {
+            for dx in -radius..=radius {
+                if let Element::Disc = self.element {
+                    if dx * dx + dy * dy > rsqr {
+                        continue;
+                    }
+                }
+
+                let col = source.source((x + dx, y + dy));
+                let k = key(col);
+                if better(k, best_key) {
+                    best = col;
+                    best_key = k;
+                }
+            }
+        }
+
+        best
+    }
+}
@@ -0,0 +1,465 @@
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::Float;
+use {
+    crate::{hash, math::lerp, source::Source, Color},
+    alloc::{boxed::Box, vec, vec::Vec},
+};
+
+/// A continuous scalar field, the base of procedural noise.
+pub trait Field {
+    /// Returns the field value at a position, usually in range `-1..=1`.
+    fn value(&self, pos: (f32, f32)) -> f32;
+
+    /// Mixes the seed of a render into the seeds of the field, see [`Source::reseed`].
+    fn reseed(&mut self, seed: u32) {
+        _ = seed;
+    }
+}
+
+impl<F> Field for &F
+where
+    F: Field + ?Sized,
+{
+    fn value(&self, pos: (f32, f32)) -> f32 {
+        F::value(self, pos)
+    }
+}
+
+impl<F> Field for Box<F>
+where
+    F: Field + ?Sized,
+{
+    fn value(&self, pos: (f32, f32)) -> f32 {
+        F::value(self, pos)
+    }
+
+    fn reseed(&mut self, seed: u32) {
+        F::reseed(self, seed);
+    }
+}
+
+/// Renders a field as a grayscale source.
+///
+/// Field values in range `-1..=1` are mapped to colors from black to white.
+pub struct Noise<F> {
+    field: F,
+    scale: f32,
+}
+
+impl<F> Noise<F> {
+    /// The [`Noise`] constructor.
+    ///
+    /// The `scale` is the size of a field unit in pixels.
+    ///
+    /// # Panics
+    /// Panics when a `scale` is less than or equal to zero.
+    pub fn new(field: F, scale: f32) -> Self {
+        assert!(
+            scale > f32::EPSILON,
+            "scale cannot be less than or equal to zero",
+        );
+
+        Self {
+            field,
+            scale: 1. / scale,
+        }
+    }
+}
+
+impl<F> Source for Noise<F>
+where
+    F: Field,
+{
+    fn source(&self, (x, y): (i32, i32)) -> Color {
+        let pos = ((x as f32 + 0.5) * self.scale, (y as f32 + 0.5) * self.scale);
+
+        let v = (self.field.value(pos) * 0.5 + 0.5).clamp(0., 1.);
+        Color {
+            r: v,
+            g: v,
+            b: v,
+            a: 1.,
+        }
+    }
+
+    fn reseed(&mut self, seed: u32) {
+        self.field.reseed(seed);
+    }
+}
+
+/// Seeded gradient noise.
+pub struct Perlin {
+    seed: u32,
+}
+
+impl Perlin {
+    pub fn new(seed: u32) -> Self {
+        Self { seed }
+    }
+}
+
+impl Field for Perlin {
+    fn value(&self, (x, y): (f32, f32)) -> f32 {
+        use core::f32::consts::FRAC_1_SQRT_2;
+
+        const GRADIENTS: [(f32, f32); 8] = [
+            (1., 0.),
+            (-1., 0.),
+            (0., 1.),
+            (0., -1.),
+            (FRAC_1_SQRT_2, FRAC_1_SQRT_2),
+            (-FRAC_1_SQRT_2, FRAC_1_SQRT_2),
+            (FRAC_1_SQRT_2, -FRAC_1_SQRT_2),
+            (-FRAC_1_SQRT_2, -FRAC_1_SQRT_2),
+        ];
+
+        let (cx, cy) = (x.floor(), y.floor());
+        let (fx, fy) = (x - cx, y - cy);
+        let (cx, cy) = (cx as i32, cy as i32);
+        let corner = |dx: i32, dy: i32| {
+            let h = hash::hash(self.seed, (cx.wrapping_add(dx), cy.wrapping_add(dy)));
+            let (gx, gy) = GRADIENTS[h as usize % GRADIENTS.len()];
+            gx * (fx - dx as f32) + gy * (fy - dy as f32)
+        };
+
+        let fade = |t: f32| t * t * t * (t * (t * 6. - 15.) + 10.);
+        let (u, v) = (fade(fx), fade(fy));
+        let top = lerp(corner(0, 0), corner(1, 0), u);
+        let bottom = lerp(corner(0, 1), corner(1, 1), u);
+
+        // Scale the result closer to the `-1..=1` range
+        lerp(top, bottom, v) * core::f32::consts::SQRT_2
+    }
+
+    fn reseed(&mut self, seed: u32) {
+        self.seed = hash::hash(self.seed, (seed as i32, 0));
+    }
+}
+
+/// Fractal Brownian motion, a sum of octaves of a field at growing frequencies.
+pub struct Fbm<F> {
+    field: F,
+    octaves: u8,
+    lacunarity: f32,
+    gain: f32,
+}
+
+impl<F> Fbm<F> {
+    /// The [`Fbm`] constructor.
+    ///
+    /// # Panics
+    /// Panics when `octaves` is zero.
+    pub fn new(field: F, octaves: u8) -> Self {
+        assert!(octaves > 0, "octaves cannot be zero");
+        Self {
+            field,
+            octaves,
+            lacunarity: 2.,
+            gain: 0.5,
+        }
+    }
+
+    /// Sets the frequency multiplier between octaves. Defaults to `2`.
+    pub fn lacunarity(mut self, lacunarity: f32) -> Self {
+        self.lacunarity = lacunarity;
+        self
+    }
+
+    /// Sets the amplitude multiplier between octaves. Defaults to `0.5`.
+    pub fn gain(mut self, gain: f32) -> Self {
+        self.gain = gain;
+        self
+    }
+}
+
+impl<F> Field for Fbm<F>
+where
+    F: Field,
+{
+    fn value(&self, (x, y): (f32, f32)) -> f32 {
+        let mut sum = 0.;
+        let mut norm = 0.;
+        let mut amplitude = 1.;
+        let mut frequency = 1.;
+        for octave in 0..self.octaves {
+            // Shift octaves apart so they don't line up at the origin
+            let shift = octave as f32 * 17.13;
+            sum += amplitude
+                * self
+                    .field
+                    .value((x * frequency + shift, y * frequency - shift));
+
+            norm += amplitude;
+            amplitude *= self.gain;
+            frequency *= self.lacunarity;
+        }
+
+        if norm > 0. {
+            sum / norm
+        } else {
+            0.
+        }
+    }
+
+    fn reseed(&mut self, seed: u32) {
+        self.field.reseed(seed);
+    }
+}
+
+/// Distorts the coordinates of a field with another field.
+pub struct DomainWarp<F, W> {
+    field: F,
+    warp: W,
+    strength: f32,
+}
+
+impl<F, W> DomainWarp<F, W> {
+    /// The [`DomainWarp`] constructor.
+    ///
+    /// Positions are displaced by the `warp` field values multiplied by the `strength`.
+    pub fn new(field: F, warp: W, strength: f32) -> Self {
+        Self {
+            field,
+            warp,
+            strength,
+        }
+    }
+}
+
+impl<F, W> Field for DomainWarp<F, W>
+where
+    F: Field,
+    W: Field,
+{
+    fn value(&self, (x, y): (f32, f32)) -> f32 {
+        let dx = self.warp.value((x, y));
+        let dy = self.warp.value((x + 5.2, y + 1.3));
+        self.field
+            .value((x + dx * self.strength, y + dy * self.strength))
+    }
+
+    fn reseed(&mut self, seed: u32) {
+        self.field.reseed(seed);
+        self.warp.reseed(seed);
+    }
+}
+
+/// Seeded cellular noise.
+///
+/// Space is split into unit cells with one random feature point in each,
+/// the value depends on distances to the nearest points.
+/// Distances in range `0..=1` are mapped to field values in range `-1..=1`.
+pub struct Worley {
+    seed: u32,
+    metric: Metric,
+    output: WorleyOutput,
+}
+
+impl Worley {
+    pub fn new(seed: u32, metric: Metric, output: WorleyOutput) -> Self {
+        Self {
+            seed,
+            metric,
+            output,
+        }
+    }
+}
+
+impl Field for Worley {
+    fn value(&self, (x, y): (f32, f32)) -> f32 {
+        let (cx, cy) = (x.floor() as i32, y.floor() as i32);
+        let mut f1 = f32::INFINITY;
+        let mut f2 = f32::INFINITY;
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                let cell = (cx.wrapping_add(dx), cy.wrapping_add(dy));
+                let px = cell.0 as f32 + hash::unit(self.seed, cell);
+                let py = cell.1 as f32 + hash::unit(self.seed ^ 0x5BD1_E995, cell);
+                let d = self.metric.distance(px - x, py - y);
+                if d < f1 {
+                    f2 = f1;
+                    f1 = d;
+                } else if d < f2 {
+                    f2 = d;
+                }
+            }
+        }
+
+        let d = match self.output {
+            WorleyOutput::F1 => f1,
+            WorleyOutput::F2MinusF1 => f2 - f1,
+        };
+
+        d.min(1.) * 2. - 1.
+    }
+
+    fn reseed(&mut self, seed: u32) {
+        self.seed = hash::hash(self.seed, (seed as i32, 0));
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum Metric {
+    Euclidean,
+    Manhattan,
+    Chebyshev,
+}
+
+impl Metric {
+    fn distance(self, dx: f32, dy: f32) -> f32 {
+        match self {
+            Self::Euclidean => (dx * dx + dy * dy).sqrt(),
+            Self::Manhattan => dx.abs() + dy.abs(),
+            Self::Chebyshev => dx.abs().max(dy.abs()),
+        }
+    }
+}
+
+pub enum WorleyOutput {
+    /// The distance to the nearest point, giving round cells.
+    F1,
+
+    /// The difference between distances to the two nearest points, giving cell edges.
+    F2MinusF1,
+}
+
+/// A tileable blue noise pattern.
+///
+/// The pattern is a threshold map generated with the void-and-cluster method:
+/// thresholding it at any level gives evenly spread points with no clumps,
+/// which is useful for dithering and stochastic transparency.
+pub struct BlueNoise {
+    size: u32,
+    ranks: Vec<f32>,
+    shift: (i32, i32),
+}
+
+impl BlueNoise {
+    /// The [`BlueNoise`] constructor.
+    ///
+    /// Generation takes quadratic time in the number of pixels,
+    /// so sizes beyond 128 get slow.
+    ///
+    /// # Panics
+    /// Panics when a `size` is zero.
+    pub fn new(seed: u32, size: u32) -> Self {
+        assert!(size > 0, "size cannot be zero");
+
+        const SIGMA: f32 = 1.5;
+
+        let side = size as usize;
+        let n = side * side;
+
+        // Gaussian energy kernel on a torus
+        let wrap = |d: usize| d.min(side - d) as f32;
+        let kernel: Vec<_> = (0..n)
+            .map(|i| {
+                let (dx, dy) = (wrap(i % side), wrap(i / side));
+                (-(dx * dx + dy * dy) / (2. * SIGMA * SIGMA)).exp()
+            })
+            .collect();
+
+        let mut pattern = vec![false; n];
+        let mut energy = vec![0.; n];
+        let toggle = |pattern: &mut [bool], energy: &mut [f32], p: usize| {
+            pattern[p] = !pattern[p];
+            let sign = if pattern[p] { 1. } else { -1. };
+            let (px, py) = (p % side, p / side);
+            for (q, e) in energy.iter_mut().enumerate() {
+                let dx = (q % side + side - px) % side;
+                let dy = (q / side + side - py) % side;
+                *e += sign * kernel[dy * side + dx];
+            }
+        };
+
+        let tightest = |pattern: &[bool], energy: &[f32]| {
+            (0..n)
+                .filter(|&i| pattern[i])
+                .max_by(|&a, &b| energy[a].total_cmp(&energy[b]))
+        };
+
+        let largest_void = |pattern: &[bool], energy: &[f32]| {
+            (0..n)
+                .filter(|&i| !pattern[i])
+                .min_by(|&a, &b| energy[a].total_cmp(&energy[b]))
+        };
+
+        // Initial random points
+        let initial = (n / 10).max(1);
+        let mut count = 0;
+        let mut i = 0;
+        while count < initial {
+            let p = hash::hash(seed, (i, 0)) as usize % n;
+            if !pattern[p] {
+                toggle(&mut pattern, &mut energy, p);
+                count += 1;
+            }
+
+            i += 1;
+        }
+
+        // Move points from clusters to voids until they are evenly spread
+        while let Some(cluster) = tightest(&pattern, &energy) {
+            toggle(&mut pattern, &mut energy, cluster);
+            let void = largest_void(&pattern, &energy).expect("a void exists");
+            toggle(&mut pattern, &mut energy, void);
+            if void == cluster {
+                break;
+            }
+        }
+
+        let mut ranks = vec![0.; n];
+        let scale = 1. / n as f32;
+
+        // Rank initial points by removing the tightest clusters
+        let (mut ones, mut ones_energy) = (pattern.clone(), energy.clone());
+        for rank in (0..count).rev() {
+            let p = tightest(&ones, &ones_energy).expect("a point exists");
+            toggle(&mut ones, &mut ones_energy, p);
+            ranks[p] = rank as f32 * scale;
+        }
+
+        // Rank the rest by filling the largest voids
+        for rank in count..n {
+            let p = largest_void(&pattern, &energy).expect("a void exists");
+            toggle(&mut pattern, &mut energy, p);
+            ranks[p] = rank as f32 * scale;
+        }
+
+        Self {
+            size,
+            ranks,
+            shift: (0, 0),
+        }
+    }
+
+    /// Returns the threshold at the position in range `0..1`.
+    pub fn value(&self, (x, y): (i32, i32)) -> f32 {
+        let (x, y) = (x.wrapping_add(self.shift.0), y.wrapping_add(self.shift.1));
+        let x = x.rem_euclid(self.size as i32) as usize;
+        let y = y.rem_euclid(self.size as i32) as usize;
+        self.ranks[y * self.size as usize + x]
+    }
+}
+
+impl Source for BlueNoise {
+    fn source(&self, pos: (i32, i32)) -> Color {
+        let v = self.value(pos);
+        Color {
+            r: v,
+            g: v,
+            b: v,
+            a: 1.,
+        }
+    }
+
+    /// Shifts the pattern by a seeded offset, since generating a new one is slow.
+    fn reseed(&mut self, seed: u32) {
+        let size = self.size as i32;
+        let (x, y) = self.shift;
+        self.shift = (
+            (x + (hash::hash(seed, (0, 0)) % self.size) as i32) % size,
+            (y + (hash::hash(seed, (0, 1)) % self.size) as i32) % size,
+        );
+    }
+}
@@ -0,0 +1,595 @@
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::Float;
+use {
+    crate::{
+        math::lerp,
+        source::{adjust::ramp, Borders, Source},
+        Color, LerpSpace,
+    },
+    alloc::{vec, vec::Vec},
+};
+
+/// A vector path made of lines and Bézier curves.
+///
+/// Curves are flattened into line segments as the path is built.
+#[derive(Clone, Default)]
+pub struct Path {
+    subpaths: Vec<Subpath>,
+}
+
+impl Path {
+    /// Creates an empty path.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts a new subpath at the point.
+    pub fn move_to(mut self, to: (f32, f32)) -> Self {
+        self.subpaths.push(Subpath {
+            points: vec![to],
+            closed: false,
+        });
+
+        self
+    }
+
+    /// Adds a straight line to the point.
+    pub fn line_to(mut self, to: (f32, f32)) -> Self {
+        self.current().points.push(to);
+        self
+    }
+
+    /// Adds a quadratic Bézier curve to the point.
+    pub fn quad_to(mut self, ctrl: (f32, f32), to: (f32, f32)) -> Self {
+        let from = self.last();
+        let n = steps(&[from, ctrl, to]);
+        let points = &mut self.current().points;
+        for i in 1..=n {
+            let t = i as f32 / n as f32;
+            let s = 1. - t;
+            let (a, b, c) = (s * s, 2. * s * t, t * t);
+            points.push((
+                a * from.0 + b * ctrl.0 + c * to.0,
+                a * from.1 + b * ctrl.1 + c * to.1,
+            ));
+        }
+
+        self
+    }
+
+    /// Adds a cubic Bézier curve to the point.
+    pub fn cubic_to(mut self, c0: (f32, f32), c1: (f32, f32), to: (f32, f32)) -> Self {
+        let from = self.last();
+        let n = steps(&[from, c0, c1, to]);
+        let points = &mut self.current().points;
+        for i in 1..=n {
+            let t = i as f32 / n as f32;
+            let s = 1. - t;
+            let (a, b, c, d) = (s * s * s, 3. * s * s * t, 3. * s * t * t, t * t * t);
+            points.push((
+                a * from.0 + b * c0.0 + c * c1.0 + d * to.0,
+                a * from.1 + b * c0.1 + c * c1.1 + d * to.1,
+            ));
+        }
+
+        self
+    }
+
+    /// Closes the current subpath with a line back to its start.
+    pub fn close(mut self) -> Self {
+        self.current().closed = true;
+        self
+    }
+
+    fn current(&mut self) -> &mut Subpath {
+        if self.subpaths.is_empty() {
+            self.subpaths.push(Subpath {
+                points: vec![(0., 0.)],
+                closed: false,
+            });
+        }
+
+        self.subpaths.last_mut().expect("a subpath exists")
+    }
+
+    fn last(&self) -> (f32, f32) {
+        self.subpaths
+            .last()
+            .and_then(|sub| sub.points.last().copied())
+            .unwrap_or_default()
+    }
+
+    /// Returns the total length of all subpaths.
+    pub fn length(&self) -> f32 {
+        self.segments(false)
+            .last()
+            .map_or(0., |seg| seg.offset + seg.length())
+    }
+
+    /// Returns all line segments of the path with their subpath flags
+    /// and arc length offsets.
+    ///
+    /// Open subpaths are implicitly closed when `close_all` is set, as fills need.
+    fn segments(&self, close_all: bool) -> Vec<Segment> {
+        let mut segments: Vec<Segment> = vec![];
+        let mut offset = 0.;
+        for sub in &self.subpaths {
+            let n = sub.points.len();
+            let closed = sub.closed || close_all;
+            let count = if closed { n } else { n.saturating_sub(1) };
+            for i in 0..count {
+                let seg = Segment {
+                    a: sub.points[i],
+                    b: sub.points[(i + 1) % n],
+                    offset,
+                    start: !closed && i == 0,
+                    end: !closed && i + 1 == count,
+                };
+
+                offset += seg.length();
+                segments.push(seg);
+            }
+        }
+
+        segments
+    }
+
+    fn bounds(&self) -> Option<((f32, f32), (f32, f32))> {
+        self.subpaths
+            .iter()
+            .flat_map(|sub| &sub.points)
+            .fold(None, |acc, &(x, y)| match acc {
+                None => Some(((x, y), (x, y))),
+                Some(((x0, y0), (x1, y1))) => {
+                    Some(((x0.min(x), y0.min(y)), (x1.max(x), y1.max(y))))
+                }
+            })
+    }
+}
+
+#[derive(Clone)]
+struct Subpath {
+    points: Vec<(f32, f32)>,
+    closed: bool,
+}
+
+#[derive(Clone, Copy)]
+struct Segment {
+    a: (f32, f32),
+    b: (f32, f32),
+    offset: f32,
+    start: bool,
+    end: bool,
+}
+
+impl Segment {
+    fn length(self) -> f32 {
+        ((self.b.0 - self.a.0).powi(2) + (self.b.1 - self.a.1).powi(2)).sqrt()
+    }
+
+    /// Returns the distance from the point to the segment
+    /// and the parameter of the nearest point on it.
+    fn distance(self, (px, py): (f32, f32)) -> (f32, f32) {
+        let (dx, dy) = (self.b.0 - self.a.0, self.b.1 - self.a.1);
+        let len = dx * dx + dy * dy;
+        let t = if len > 0. {
+            (((px - self.a.0) * dx + (py - self.a.1) * dy) / len).clamp(0., 1.)
+        } else {
+            0.
+        };
+
+        let (nx, ny) = (self.a.0 + dx * t - px, self.a.1 + dy * t - py);
+        ((nx * nx + ny * ny).sqrt(), t)
+    }
+
+    /// Returns the signed distance from the point to the segment thickened
+    /// by radii growing linearly from `ra` at its start to `rb` at its end,
+    /// and the parameter of the nearest point on the segment.
+    fn tapered_distance(self, p: (f32, f32), (ra, rb): (f32, f32)) -> (f32, f32) {
+        let (_, t) = self.distance(p);
+        let (bx, by) = (self.b.0 - self.a.0, self.b.1 - self.a.1);
+        let l2 = bx * bx + by * by;
+        let dr = ra - rb;
+        let a2 = l2 - dr * dr;
+        let disc = |c: (f32, f32), r: f32| ((p.0 - c.0).hypot(p.1 - c.1)) - r;
+
+        // One end disc covers the other, so the segment is the larger disc
+        if a2 <= 0. {
+            return if ra >= rb {
+                (disc(self.a, ra), 0.)
+            } else {
+                (disc(self.b, rb), 1.)
+            };
+        }
+
+        let (px, py) = (p.0 - self.a.0, p.1 - self.a.1);
+        let y = px * bx + py * by;
+        let z = y - l2;
+        let (qx, qy) = (px * l2 - bx * y, py * l2 - by * y);
+        let x2 = qx * qx + qy * qy;
+        let k = dr.signum() * dr * dr * x2;
+        let dist = if z.signum() * a2 * z * z * l2 > k {
+            disc(self.b, rb)
+        } else if y.signum() * a2 * y * y * l2 < k {
+            disc(self.a, ra)
+        } else {
+            ((x2 * a2 / l2).sqrt() + y * dr) / l2 - ra
+        };
+
+        (dist, t)
+    }
+}
+
+/// Returns the number of line segments to flatten a curve with the control polygon.
+fn steps(ctrl: &[(f32, f32)]) -> u32 {
+    let len: f32 = ctrl
+        .windows(2)
+        .map(|w| ((w[1].0 - w[0].0).powi(2) + (w[1].1 - w[0].1).powi(2)).sqrt())
+        .sum();
+
+    (len.sqrt() * 2.).ceil().clamp(1., 256.) as u32
+}
+
+/// The rule to decide which points are inside a filled path.
+#[derive(Clone, Copy)]
+pub enum FillRule {
+    NonZero,
+    EvenOdd,
+}
+
+/// Fills a path with an anti-aliased paint.
+///
+/// The paint can be any source, e.g. a plain [`Color`] or a gradient.
+pub struct Fill<P = Color> {
+    segments: Vec<Segment>,
+    bounds: Option<Borders>,
+    paint: P,
+    rule: FillRule,
+}
+
+impl<P> Fill<P> {
+    /// The [`Fill`] constructor.
+    pub fn new(path: &Path, paint: P) -> Self {
+        Self {
+            segments: path.segments(true),
+            bounds: path.bounds().map(|(min, max)| borders(min, max, 1.)),
+            paint,
+            rule: FillRule::NonZero,
+        }
+    }
+
+    /// Sets the fill rule. Defaults to [`FillRule::NonZero`].
+    pub fn rule(mut self, rule: FillRule) -> Self {
+        self.rule = rule;
+        self
+    }
+
+    /// Returns the coverage of a pixel centered at the point.
+    pub(super) fn coverage(&self, p: (f32, f32)) -> f32 {
+        let mut winding = 0;
+        let mut dist = f32::INFINITY;
+        for seg in &self.segments {
+            dist = dist.min(seg.distance(p).0);
+
+            let (a, b) = (seg.a, seg.b);
+            if (a.1 <= p.1) != (b.1 <= p.1) {
+                let x = a.0 + (p.1 - a.1) / (b.1 - a.1) * (b.0 - a.0);
+                if x > p.0 {
+                    winding += if b.1 > a.1 { 1 } else { -1 };
+                }
+            }
+        }
+
+        let inside = match self.rule {
+            FillRule::NonZero => winding != 0,
+            FillRule::EvenOdd => winding % 2 != 0,
+        };
+
+        if inside {
+            (0.5 + dist).min(1.)
+        } else {
+            (0.5 - dist).max(0.)
+        }
+    }
+}
+
+impl<P> Source for Fill<P>
+where
+    P: Source,
+{
+    fn source(&self, (x, y): (i32, i32)) -> Color {
+        match self.bounds {
+            Some(bounds) if bounds.contains((x, y)) => {}
+            _ => return Color::default(),
+        }
+
+        let coverage = self.coverage((x as f32 + 0.5, y as f32 + 0.5));
+        if coverage <= 0. {
+            return Color::default();
+        }
+
+        let mut col = self.paint.source((x, y));
+        col.a *= coverage;
+        col
+    }
+
+    fn borders(&self) -> Option<Borders> {
+        Some(self.bounds.unwrap_or(Borders {
+            w: (0, -1),
+            h: (0, -1),
+        }))
+    }
+
+    fn prepare(&mut self, region: Borders) {
+        self.paint.prepare(region);
+    }
+
+    fn reseed(&mut self, seed: u32) {
+        self.paint.reseed(seed);
+    }
+}
+
+/// The shape of open subpath ends.
+#[derive(Clone, Copy)]
+pub enum Cap {
+    /// Ends exactly at the endpoint.
+    Butt,
+    /// Ends with a half disc.
+    Round,
+    /// Ends with a half square.
+    Square,
+}
+
+/// Strokes a path with an anti-aliased paint.
+///
+/// Joins between segments are always round.
+pub struct Stroke<P = Color> {
+    segments: Vec<Segment>,
+    extent: Option<((f32, f32), (f32, f32))>,
+    bounds: Option<Borders>,
+    paint: P,
+    widths: (f32, f32),
+    length: f32,
+    cap: Cap,
+}
+
+impl<P> Stroke<P> {
+    /// The [`Stroke`] constructor.
+    ///
+    /// # Panics
+    /// Panics when a `width` is less than or equal to zero.
+    pub fn new(path: &Path, width: f32, paint: P) -> Self {
+        assert!(
+            width > f32::EPSILON,
+            "width cannot be less than or equal to zero",
+        );
+
+        Self {
+            segments: path.segments(false),
+            extent: path.bounds(),
+            bounds: None,
+            paint,
+            widths: (width, width),
+            length: path.length(),
+            cap: Cap::Butt,
+        }
+        .fit_bounds()
+    }
+
+    /// Sets the cap of open subpath ends. Defaults to [`Cap::Butt`].
+    pub fn cap(mut self, cap: Cap) -> Self {
+        self.cap = cap;
+        self
+    }
+
+    /// Sets widths at the start and the end of the path,
+    /// the width in between is interpolated by the arc length.
+    ///
+    /// This gives tapered calligraphic strokes and arrow shafts.
+    ///
+    /// # Panics
+    /// Panics when a width is negative or both are zero.
+    pub fn widths(mut self, start: f32, end: f32) -> Self {
+        assert!(start >= 0. && end >= 0., "width cannot be negative");
+        assert!(start.max(end) > f32::EPSILON, "widths cannot be both zero");
+
+        self.widths = (start, end);
+        self.fit_bounds()
+    }
+
+    fn fit_bounds(mut self) -> Self {
+        let reach = self.widths.0.max(self.widths.1) * core::f32::consts::FRAC_1_SQRT_2 + 1.;
+        self.bounds = self.extent.map(|(min, max)| borders(min, max, reach));
+        self
+    }
+
+    /// Returns the coverage of the point and the arc length at the nearest stroked point.
+    fn coverage(&self, p: (f32, f32)) -> (f32, f32) {
+        let width = |arc: f32| {
+            if self.length > 0. {
+                lerp(self.widths.0, self.widths.1, arc / self.length)
+            } else {
+                self.widths.0
+            }
+        };
+
+        let mut res = (0., 0.);
+        for &seg in &self.segments {
+            // Each segment tapers between the widths at its ends
+            let radii = (
+                width(seg.offset) / 2.,
+                width(seg.offset + seg.length()) / 2.,
+            );
+            let (dist, t) = seg.tapered_distance(p, radii);
+            let arc = seg.offset + t * seg.length();
+            let mut coverage = (0.5 - dist).clamp(0., 1.);
+
+            // Round caps come for free, the others cut off the end disc
+            let cut = |half| match self.cap {
+                Cap::Butt => Some(0.),
+                Cap::Round => None,
+                Cap::Square => Some(half),
+            };
+
+            if seg.start {
+                if let Some(cut) = cut(radii.0) {
+                    coverage *= (cut - along(seg.b, seg.a, p) + 0.5).clamp(0., 1.);
+                }
+            }
+
+            if seg.end {
+                if let Some(cut) = cut(radii.1) {
+                    coverage *= (cut - along(seg.a, seg.b, p) + 0.5).clamp(0., 1.);
+                }
+            }
+
+            if coverage > res.0 {
+                res = (coverage, arc);
+            }
+        }
+
+        res
+    }
+}
+
+/// Returns how far the point lies past `to` in the direction from `from` to `to`.
+fn along(from: (f32, f32), to: (f32, f32), (px, py): (f32, f32)) -> f32 {
+    let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+    let len = (dx * dx + dy * dy).sqrt();
+    if len > 0. {
+        ((px - to.0) * dx + (py - to.1) * dy) / len
+    } else {
+        0.
+    }
+}
+
+impl<P> Source for Stroke<P>
+where
+    P: Source,
+{
+    fn source(&self, (x, y): (i32, i32)) -> Color {
+        match self.bounds {
+            Some(bounds) if bounds.contains((x, y)) => {}
+            _ => return Color::default(),
+        }
+
+        let (coverage, _) = self.coverage((x as f32 + 0.5, y as f32 + 0.5));
+        if coverage <= 0. {
+            return Color::default();
+        }
+
+        let mut col = self.paint.source((x, y));
+        col.a *= coverage;
+        col
+    }
+
+    fn borders(&self) -> Option<Borders> {
+        Some(self.bounds.unwrap_or(Borders {
+            w: (0, -1),
+            h: (0, -1),
+        }))
+    }
+
+    fn prepare(&mut self, region: Borders) {
+        self.paint.prepare(region);
+    }
+
+    fn reseed(&mut self, seed: u32) {
+        self.paint.reseed(seed);
+    }
+}
+
+/// Strokes a path with a color ramp running along it.
+///
+/// The ramp is parameterized by the arc length, so it follows curves evenly.
+/// This is handy for progress rings and decorated curves.
+pub struct GradientStroke {
+    stroke: Stroke,
+    stops: Vec<(f32, Color)>,
+    space: LerpSpace,
+    length: f32,
+}
+
+impl GradientStroke {
+    /// The [`GradientStroke`] constructor.
+    ///
+    /// Each stop is a position in range `0..=1` of the path length paired with its color.
+    /// Stops are sorted by position.
+    ///
+    /// # Panics
+    /// Panics when a `width` is less than or equal to zero or `stops` is empty.
+    pub fn new(path: &Path, width: f32, mut stops: Vec<(f32, Color)>) -> Self {
+        assert!(!stops.is_empty(), "stops cannot be empty");
+        stops.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+        Self {
+            stroke: Stroke::new(path, width, Color::default()),
+            stops,
+            space: LerpSpace::Srgb,
+            length: path.length(),
+        }
+    }
+
+    /// Sets the cap of open subpath ends. Defaults to [`Cap::Butt`].
+    pub fn cap(mut self, cap: Cap) -> Self {
+        self.stroke = self.stroke.cap(cap);
+        self
+    }
+
+    /// Sets widths at the start and the end of the path, see [`Stroke::widths`].
+    ///
+    /// # Panics
+    /// Panics when a width is negative or both are zero.
+    pub fn widths(mut self, start: f32, end: f32) -> Self {
+        self.stroke = self.stroke.widths(start, end);
+        self
+    }
+
+    /// Sets the space stops are interpolated in. Defaults to [`LerpSpace::Srgb`].
+    pub fn space(mut self, space: LerpSpace) -> Self {
+        self.space = space;
+        self
+    }
+
+    /// Sets the length the ramp spans. Defaults to the path length.
+    ///
+    /// A partial progress ring can keep the ramp of the full circle this way.
+    pub fn length(mut self, length: f32) -> Self {
+        self.length = length;
+        self
+    }
+}
+
+impl Source for GradientStroke {
+    fn source(&self, (x, y): (i32, i32)) -> Color {
+        match self.stroke.bounds {
+            Some(bounds) if bounds.contains((x, y)) => {}
+            _ => return Color::default(),
+        }
+
+        let (coverage, arc) = self.stroke.coverage((x as f32 + 0.5, y as f32 + 0.5));
+        if coverage <= 0. {
+            return Color::default();
+        }
+
+        let t = if self.length > 0. {
+            arc / self.length
+        } else {
+            0.
+        };
+        let mut col = ramp(&self.stops, t, self.space);
+        col.a *= coverage;
+        col
+    }
+
+    fn borders(&self) -> Option<Borders> {
+        self.stroke.borders()
+    }
+}
+
+/// Returns pixel borders covering the box grown by `pad`.
+fn borders((x0, y0): (f32, f32), (x1, y1): (f32, f32), pad: f32) -> Borders {
+    Borders {
+        w: ((x0 - pad).floor() as _, (x1 + pad).ceil() as _),
+        h: ((y0 - pad).floor() as _, (y1 + pad).ceil() as _),
+    }
+}
@@ -0,0 +1,886 @@
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::Float;
+use {
+    crate::{
+        hash,
+        source::{Borders, Source},
+        Color,
+    },
+    alloc::vec::Vec,
+};
+
+const SQRT_3: f32 = 1.732_050_8;
+
+/// Tiles a source infinitely, randomly transforming each tile to hide repetition.
+///
+/// Every tile gets its own seeded flip, rotation by a multiple of a right angle
+/// and cyclic offset. Rotations by a right angle are only applied to square tiles.
+/// Offsets produce visible seams unless the tile is seamless itself.
+pub struct PatternFill<S> {
+    source: S,
+    tile: Borders,
+    seed: u32,
+    flips: bool,
+    rotations: bool,
+    offsets: bool,
+}
+
+impl<S> PatternFill<S>
+where
+    S: Source,
+{
+    /// The [`PatternFill`] constructor.
+    ///
+    /// # Panics
+    /// Panics when the `source` has no borders or its borders are empty.
+    pub fn new(source: S, seed: u32) -> Self {
+        let tile = source.borders().expect("the source must have borders");
+        let (w, h) = tile.size();
+        assert!(w > 0 && h > 0, "the source borders cannot be empty");
+
+        Self {
+            source,
+            tile,
+            seed,
+            flips: true,
+            rotations: true,
+            offsets: true,
+        }
+    }
+}
+
+impl<S> PatternFill<S> {
+    /// Enables random tile flips. Defaults to `true`.
+    pub fn flips(mut self, enabled: bool) -> Self {
+        self.flips = enabled;
+        self
+    }
+
+    /// Enables random tile rotations. Defaults to `true`.
+    pub fn rotations(mut self, enabled: bool) -> Self {
+        self.rotations = enabled;
+        self
+    }
+
+    /// Enables random cyclic tile offsets. Defaults to `true`.
+    pub fn offsets(mut self, enabled: bool) -> Self {
+        self.offsets = enabled;
+        self
+    }
+}
+
+impl<S> Source for PatternFill<S>
+where
+    S: Source,
+{
+    fn source(&self, (x, y): (i32, i32)) -> Color {
+        let (w, h) = self.tile.size();
+        let (w, h) = (w as i32, h as i32);
+        let tile = (x.div_euclid(w), y.div_euclid(h));
+        let (mut lx, mut ly) = (x.rem_euclid(w), y.rem_euclid(h));
+        let rand = hash::hash(self.seed, tile);
+
+        if self.offsets {
+            let ox = hash::hash(rand, (0, 0)) as i32;
+            let oy = hash::hash(rand, (0, 1)) as i32;
+            lx = (lx + ox.rem_euclid(w)) % w;
+            ly = (ly + oy.rem_euclid(h)) % h;
+        }
+
+        if self.rotations {
+            // Non-square tiles can only turn upside down
+            let turns = if w == h { rand & 3 } else { rand & 2 };
+            (lx, ly) = match turns {
+                0 => (lx, ly),
+                1 => (ly, w - 1 - lx),
+                2 => (w - 1 - lx, h - 1 - ly),
+                _ => (h - 1 - ly, lx),
+            };
+        }
+
+        if self.flips && rand & 4 != 0 {
+            lx = w - 1 - lx;
+        }
+
+        self.source.source((self.tile.w.0 + lx, self.tile.h.0 + ly))
+    }
+
+    fn reseed(&mut self, seed: u32) {
+        self.seed = hash::hash(self.seed, (seed as i32, 0));
+        self.source.reseed(seed);
+    }
+
+    fn prepare(&mut self, _: Borders) {
+        // Tiles read only the source borders
+        self.source.prepare(self.tile);
+    }
+}
+
+/// Repeats the region inside the borders of a source infinitely.
+pub struct Repeat<S> {
+    source: S,
+    tile: Borders,
+}
+
+impl<S> Repeat<S>
+where
+    S: Source,
+{
+    /// The [`Repeat`] constructor.
+    ///
+    /// # Panics
+    /// Panics when the `source` has no borders or its borders are empty.
+    pub fn new(source: S) -> Self {
+        let tile = source.borders().expect("the source must have borders");
+        let (w, h) = tile.size();
+        assert!(w > 0 && h > 0, "the source borders cannot be empty");
+        Self { source, tile }
+    }
+}
+
+impl<S> Source for Repeat<S>
+where
+    S: Source,
+{
+    fn source(&self, (x, y): (i32, i32)) -> Color {
+        let (w, h) = self.tile.size();
+        let (x0, y0) = (self.tile.w.0, self.tile.h.0);
+        let lx = (x - x0).rem_euclid(w as i32);
+        let ly = (y - y0).rem_euclid(h as i32);
+        self.source.source((x0 + lx, y0 + ly))
+    }
+
+    fn reseed(&mut self, seed: u32) {
+        self.source.reseed(seed);
+    }
+
+    fn prepare(&mut self, _: Borders) {
+        // Tiles read only the source borders
+        self.source.prepare(self.tile);
+    }
+}
+
+/// Seeded random Truchet tiles.
+///
+/// Every square tile holds either a pair of quarter circles around opposite corners
+/// or a diagonal, in one of two orientations picked at random, so lines run
+/// across tile edges into a maze of curves or zigzags.
+pub struct Truchet {
+    size: f32,
+    seed: u32,
+    style: TruchetStyle,
+    width: f32,
+    ink: Color,
+    paper: Color,
+}
+
+impl Truchet {
+    /// The [`Truchet`] constructor.
+    ///
+    /// The `size` is the side of a tile in pixels.
+    ///
+    /// # Panics
+    /// Panics when a `size` is less than one.
+    pub fn new(size: f32, seed: u32) -> Self {
+        assert!(size >= 1., "size cannot be less than one");
+        Self {
+            size,
+            seed,
+            style: TruchetStyle::Arcs,
+            width: 2.,
+            ink: Color::from_u32(0x000000FF),
+            paper: Color::from_u32(0xFFFFFFFF),
+        }
+    }
+
+    /// Sets the tile style. Defaults to [`TruchetStyle::Arcs`].
+    pub fn style(mut self, style: TruchetStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Sets the stroke width in pixels. Defaults to `2`.
+    pub fn width(mut self, width: f32) -> Self {
+        self.width = width.max(0.);
+        self
+    }
+
+    /// Sets the ink and paper colors. Defaults to black on white.
+    pub fn colors(mut self, ink: Color, paper: Color) -> Self {
+        self.ink = ink;
+        self.paper = paper;
+        self
+    }
+}
+
+impl Source for Truchet {
+    fn source(&self, (x, y): (i32, i32)) -> Color {
+        let s = self.size;
+        let (x, y) = (x as f32 + 0.5, y as f32 + 0.5);
+        let (tx, ty) = ((x / s).floor(), (y / s).floor());
+        let (mut lx, ly) = (x - tx * s, y - ty * s);
+
+        // The other orientation is the same tile mirrored
+        if hash::hash(self.seed, (tx as i32, ty as i32)) & 1 != 0 {
+            lx = s - lx;
+        }
+
+        let d = match self.style {
+            TruchetStyle::Arcs => {
+                let r = s * 0.5;
+                let a = (lx.hypot(ly) - r).abs();
+                let b = ((s - lx).hypot(s - ly) - r).abs();
+                a.min(b)
+            }
+            TruchetStyle::Diagonals => (lx - ly).abs() * core::f32::consts::FRAC_1_SQRT_2,
+        };
+
+        let cov = (self.width * 0.5 - d + 0.5).clamp(0., 1.);
+        self.paper.lerp(self.ink, cov)
+    }
+
+    fn reseed(&mut self, seed: u32) {
+        self.seed = hash::hash(self.seed, (seed as i32, 0));
+    }
+}
+
+pub enum TruchetStyle {
+    /// Quarter circles around two opposite corners of a tile.
+    Arcs,
+
+    /// A diagonal line from corner to corner.
+    Diagonals,
+}
+
+/// A grid of hexagons.
+///
+/// Without rotation hexagons point up and their sides are vertical,
+/// with a cell centered at the origin.
+pub struct HexGrid {
+    size: f32,
+    rotation: (f32, f32),
+    style: HexStyle,
+    width: f32,
+    ink: Color,
+    paper: Color,
+}
+
+impl HexGrid {
+    /// The [`HexGrid`] constructor.
+    ///
+    /// The `size` is the distance from a cell center to its corners in pixels.
+    ///
+    /// # Panics
+    /// Panics when a `size` is less than one.
+    pub fn new(size: f32) -> Self {
+        assert!(size >= 1., "size cannot be less than one");
+        Self {
+            size,
+            rotation: (0., 1.),
+            style: HexStyle::Outlines,
+            width: 1.,
+            ink: Color::from_u32(0x000000FF),
+            paper: Color::from_u32(0xFFFFFFFF),
+        }
+    }
+
+    /// Sets the rotation of the grid in radians, clockwise on screen. Defaults to `0`.
+    pub fn rotation(mut self, angle: f32) -> Self {
+        self.rotation = angle.sin_cos();
+        self
+    }
+
+    /// Sets the grid style. Defaults to [`HexStyle::Outlines`].
+    pub fn style(mut self, style: HexStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Sets the line width, or the gap between filled cells, in pixels. Defaults to `1`.
+    pub fn width(mut self, width: f32) -> Self {
+        self.width = width.max(0.);
+        self
+    }
+
+    /// Sets the ink and paper colors. Defaults to black on white.
+    pub fn colors(mut self, ink: Color, paper: Color) -> Self {
+        self.ink = ink;
+        self.paper = paper;
+        self
+    }
+}
+
+impl Source for HexGrid {
+    fn source(&self, (x, y): (i32, i32)) -> Color {
+        let (vx, vy) = hex_cell(self.size, self.rotation, (x, y));
+        let edge = hex_edge(self.size, (vx, vy));
+        let half = self.width * 0.5;
+        let cov = match self.style {
+            HexStyle::Outlines => half - edge + 0.5,
+            HexStyle::Fills => edge - half + 0.5,
+        };
+
+        self.paper.lerp(self.ink, cov.clamp(0., 1.))
+    }
+}
+
+pub enum HexStyle {
+    /// Lines along the cell sides.
+    Outlines,
+
+    /// Filled cells separated by gaps.
+    Fills,
+}
+
+/// Returns the position relative to the center of its hexagon in a grid
+/// of pointy-top hexagons rotated clockwise by the angle's `(sin, cos)`.
+fn hex_cell(size: f32, (sin, cos): (f32, f32), (x, y): (i32, i32)) -> (f32, f32) {
+    let (x, y) = (x as f32 + 0.5, y as f32 + 0.5);
+    let (x, y) = (x * cos + y * sin, y * cos - x * sin);
+
+    // Axial coordinates of the cell, rounded in cube coordinates
+    let q = (SQRT_3 / 3. * x - y / 3.) / size;
+    let r = (2. / 3. * y) / size;
+    let s = -q - r;
+    let (mut rq, mut rr, rs) = (q.round(), r.round(), s.round());
+    let (dq, dr, ds) = ((rq - q).abs(), (rr - r).abs(), (rs - s).abs());
+    if dq > dr && dq > ds {
+        rq = -rr - rs;
+    } else if dr > ds {
+        rr = -rq - rs;
+    }
+
+    let cx = size * SQRT_3 * (rq + rr * 0.5);
+    let cy = size * 1.5 * rr;
+    (x - cx, y - cy)
+}
+
+/// Returns the distance from a position inside a hexagon to its sides.
+fn hex_edge(size: f32, (vx, vy): (f32, f32)) -> f32 {
+    // Side normals point at 0°, 60° and 120°
+    let (s60, c60) = (SQRT_3 * 0.5, 0.5);
+    let far = vx
+        .abs()
+        .max((vx * c60 + vy * s60).abs())
+        .max((vy * s60 - vx * c60).abs());
+
+    size * SQRT_3 * 0.5 - far
+}
+
+/// A rhombille tiling, hexagons split into three rhombi like stacked cubes.
+///
+/// Without rotation hexagons point up, with a cell centered at the origin,
+/// and every hexagon is split into a top, a left and a right face.
+pub struct Rhombille {
+    size: f32,
+    rotation: (f32, f32),
+    style: TileStyle,
+    width: f32,
+    ink: Color,
+    paper: Color,
+    faces: [Color; 3],
+}
+
+impl Rhombille {
+    /// The [`Rhombille`] constructor.
+    ///
+    /// The `size` is the side of a rhombus in pixels.
+    ///
+    /// # Panics
+    /// Panics when a `size` is less than one.
+    pub fn new(size: f32) -> Self {
+        assert!(size >= 1., "size cannot be less than one");
+        Self {
+            size,
+            rotation: (0., 1.),
+            style: TileStyle::Edges,
+            width: 1.,
+            ink: Color::from_u32(0x000000FF),
+            paper: Color::from_u32(0xFFFFFFFF),
+            faces: [
+                Color::from_u32(0xE6E6E6FF),
+                Color::from_u32(0xA6A6A6FF),
+                Color::from_u32(0x666666FF),
+            ],
+        }
+    }
+
+    /// Sets the rotation of the tiling in radians, clockwise on screen. Defaults to `0`.
+    pub fn rotation(mut self, angle: f32) -> Self {
+        self.rotation = angle.sin_cos();
+        self
+    }
+
+    /// Sets the tiling style. Defaults to [`TileStyle::Edges`].
+    pub fn style(mut self, style: TileStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Sets the edge width in pixels. Defaults to `1`.
+    pub fn width(mut self, width: f32) -> Self {
+        self.width = width.max(0.);
+        self
+    }
+
+    /// Sets the ink and paper colors. Defaults to black on white.
+    pub fn colors(mut self, ink: Color, paper: Color) -> Self {
+        self.ink = ink;
+        self.paper = paper;
+        self
+    }
+
+    /// Sets the colors of the top, left and right faces. Defaults to shades of gray.
+    pub fn faces(mut self, top: Color, left: Color, right: Color) -> Self {
+        self.faces = [top, left, right];
+        self
+    }
+}
+
+impl Source for Rhombille {
+    fn source(&self, (x, y): (i32, i32)) -> Color {
+        let (vx, vy) = hex_cell(self.size, self.rotation, (x, y));
+
+        // Rays from the center to the bottom, upper left and upper right corners
+        // split the hexagon, each face lies opposite to one of them
+        let rays = [(0., 1.), (-SQRT_3 * 0.5, -0.5), (SQRT_3 * 0.5, -0.5)];
+        let mut edge = hex_edge(self.size, (vx, vy));
+        let mut face = 0;
+        let mut nearest = f32::INFINITY;
+        for (i, (rx, ry)) in rays.into_iter().enumerate() {
+            let along = vx * rx + vy * ry;
+            if along < nearest {
+                nearest = along;
+                face = i;
+            }
+
+            let t = along.clamp(0., self.size);
+            edge = edge.min((vx - rx * t).hypot(vy - ry * t));
+        }
+
+        // The top face is opposite to the bottom ray, the left one to the right ray
+        let fill = match (&self.style, face) {
+            (TileStyle::Edges, _) => self.paper,
+            (TileStyle::Faces, 0) => self.faces[0],
+            (TileStyle::Faces, 1) => self.faces[2],
+            (TileStyle::Faces, _) => self.faces[1],
+        };
+
+        let cov = self.width * 0.5 - edge + 0.5;
+        fill.lerp(self.ink, cov.clamp(0., 1.))
+    }
+}
+
+/// An aperiodic Penrose tiling of thick and thin rhombi.
+///
+/// The tiling is built with de Bruijn's pentagrid method
+/// and the seed picks one of infinitely many different tilings.
+pub struct Penrose {
+    size: f32,
+    seed: u32,
+    offsets: [f32; 5],
+    style: TileStyle,
+    width: f32,
+    ink: Color,
+    paper: Color,
+    faces: [Color; 2],
+}
+
+impl Penrose {
+    /// The [`Penrose`] constructor.
+    ///
+    /// The `size` is the side of a rhombus in pixels.
+    ///
+    /// # Panics
+    /// Panics when a `size` is less than one.
+    pub fn new(size: f32, seed: u32) -> Self {
+        assert!(size >= 1., "size cannot be less than one");
+        Self {
+            size,
+            seed,
+            offsets: Self::offsets(seed),
+            style: TileStyle::Edges,
+            width: 1.,
+            ink: Color::from_u32(0x000000FF),
+            paper: Color::from_u32(0xFFFFFFFF),
+            faces: [Color::from_u32(0xF2C14EFF), Color::from_u32(0x3D5A80FF)],
+        }
+    }
+
+    /// Sets the tiling style. Defaults to [`TileStyle::Edges`].
+    pub fn style(mut self, style: TileStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Sets the edge width in pixels. Defaults to `1`.
+    pub fn width(mut self, width: f32) -> Self {
+        self.width = width.max(0.);
+        self
+    }
+
+    /// Sets the ink and paper colors. Defaults to black on white.
+    pub fn colors(mut self, ink: Color, paper: Color) -> Self {
+        self.ink = ink;
+        self.paper = paper;
+        self
+    }
+
+    /// Sets the colors of the thick and thin rhombi. Defaults to amber and blue.
+    pub fn faces(mut self, thick: Color, thin: Color) -> Self {
+        self.faces = [thick, thin];
+        self
+    }
+
+    /// Returns offsets of the five grids, they must sum up to zero
+    /// for the tiling to be a Penrose one.
+    fn offsets(seed: u32) -> [f32; 5] {
+        let mut offsets = [0.; 5];
+        for (i, offset) in offsets.iter_mut().enumerate().take(4) {
+            *offset = hash::unit(seed, (i as i32, 0));
+        }
+
+        offsets[4] = -offsets[..4].iter().sum::<f32>();
+        offsets
+    }
+}
+
+impl Source for Penrose {
+    fn source(&self, (x, y): (i32, i32)) -> Color {
+        // How far in grid units a tile's intersection can be from the estimate
+        const REACH: f32 = 1.2;
+
+        let dirs: [(f32, f32); 5] = core::array::from_fn(|i| {
+            let (sin, cos) = (i as f32 * core::f32::consts::TAU / 5.).sin_cos();
+            (cos, sin)
+        });
+
+        let dot = |(ax, ay): (f32, f32), (bx, by): (f32, f32)| ax * bx + ay * by;
+        let (px, py) = ((x as f32 + 0.5) / self.size, (y as f32 + 0.5) / self.size);
+
+        // A tile lies near its grid intersection scaled by 5/2
+        // and shifted by the sum of offset directions
+        let (sx, sy) = self
+            .offsets
+            .iter()
+            .zip(dirs)
+            .fold((0., 0.), |(sx, sy), (g, d)| (sx + g * d.0, sy + g * d.1));
+
+        let center = ((px - sx) * 0.4, (py - sy) * 0.4);
+
+        // Find the tile around the point, it is the one the point is deepest in
+        let mut best = (f32::NEG_INFINITY, 0);
+        for j in 0..5 {
+            for k in j + 1..5 {
+                let (ej, ek) = (dirs[j], dirs[k]);
+                let det = ej.0 * ek.1 - ej.1 * ek.0;
+                let lines = |i: usize| {
+                    let v = dot(center, dirs[i]) + self.offsets[i];
+                    (v - REACH).ceil() as i32..=(v + REACH).floor() as i32
+                };
+
+                for nj in lines(j) {
+                    for nk in lines(k) {
+                        // The intersection of grid lines
+                        let (cj, ck) = (nj as f32 - self.offsets[j], nk as f32 - self.offsets[k]);
+                        let z = ((cj * ek.1 - ck * ej.1) / det, (ck * ej.0 - cj * ek.0) / det);
+
+                        // The base vertex of the tile
+                        let (mut bx, mut by) = (0., 0.);
+                        for (i, &(dx, dy)) in dirs.iter().enumerate() {
+                            let n = match i {
+                                _ if i == j => nj as f32,
+                                _ if i == k => nk as f32,
+                                _ => (dot(z, dirs[i]) + self.offsets[i]).ceil(),
+                            };
+
+                            (bx, by) = (bx + n * dx, by + n * dy);
+                        }
+
+                        // The point in coordinates along the tile sides
+                        let (vx, vy) = (px - bx, py - by);
+                        let a = (vx * ek.1 - vy * ek.0) / det;
+                        let b = (vy * ej.0 - vx * ej.1) / det;
+                        let depth = a.min(1. - a).min(b).min(1. - b) * det.abs();
+                        if depth > best.0 {
+                            best = (depth, k - j);
+                        }
+                    }
+                }
+            }
+        }
+
+        let (depth, gap) = best;
+        let fill = match self.style {
+            TileStyle::Edges => self.paper,
+            TileStyle::Faces if gap == 1 || gap == 4 => self.faces[0],
+            TileStyle::Faces => self.faces[1],
+        };
+
+        let cov = self.width * 0.5 - depth * self.size + 0.5;
+        fill.lerp(self.ink, cov.clamp(0., 1.))
+    }
+
+    fn reseed(&mut self, seed: u32) {
+        self.seed = hash::hash(self.seed, (seed as i32, 0));
+        self.offsets = Self::offsets(self.seed);
+    }
+}
+
+pub enum TileStyle {
+    /// Edges of tiles on paper.
+    Edges,
+
+    /// Tiles filled with face colors and outlined by edges.
+    Faces,
+}
+
+/// Repeating bands of colors.
+///
+/// Without rotation stripes are horizontal and the first band starts at `y = 0`.
+pub struct Stripes {
+    bands: Vec<(f32, Color)>,
+    period: f32,
+    rotation: (f32, f32),
+}
+
+impl Stripes {
+    /// The [`Stripes`] constructor.
+    ///
+    /// The `bands` are widths in pixels and colors of stripes in one period.
+    ///
+    /// # Panics
+    /// Panics when the `bands` are empty or have a width less than or equal to zero.
+    pub fn new(bands: Vec<(f32, Color)>) -> Self {
+        assert!(!bands.is_empty(), "bands cannot be empty");
+        assert!(
+            bands.iter().all(|&(w, _)| w > f32::EPSILON),
+            "width cannot be less than or equal to zero",
+        );
+
+        let period = bands.iter().map(|&(w, _)| w).sum();
+        Self {
+            bands,
+            period,
+            rotation: (0., 1.),
+        }
+    }
+
+    /// Sets the angle of the stripes in radians, clockwise on screen. Defaults to `0`.
+    pub fn angle(mut self, angle: f32) -> Self {
+        self.rotation = angle.sin_cos();
+        self
+    }
+}
+
+impl Source for Stripes {
+    fn source(&self, (x, y): (i32, i32)) -> Color {
+        let (x, y) = (x as f32 + 0.5, y as f32 + 0.5);
+        let (sin, cos) = self.rotation;
+        let v = (y * cos - x * sin).rem_euclid(self.period);
+
+        let n = self.bands.len();
+        let mut start = 0.;
+        for (i, &(w, col)) in self.bands.iter().enumerate() {
+            let end = start + w;
+            if v >= end && i + 1 < n {
+                start = end;
+                continue;
+            }
+
+            // Blend with a neighbour band within half a pixel of the edge
+            let (prev, next) = (self.bands[(i + n - 1) % n].1, self.bands[(i + 1) % n].1);
+            return if v - start < 0.5 {
+                col.lerp(prev, 0.5 - (v - start))
+            } else if end - v < 0.5 {
+                col.lerp(next, 0.5 - (end - v))
+            } else {
+                col
+            };
+        }
+
+        unreachable!("the last band ends the period")
+    }
+}
+
+/// A grid of round dots.
+///
+/// Without stagger dots are centered in square cells starting at the origin.
+pub struct Dots {
+    spacing: f32,
+    radius: f32,
+    stagger: f32,
+    ink: Color,
+    paper: Color,
+}
+
+impl Dots {
+    /// The [`Dots`] constructor.
+    ///
+    /// The `spacing` is the distance between dot centers in a row in pixels.
+    ///
+    /// # Panics
+    /// Panics when a `spacing` is less than one.
+    pub fn new(spacing: f32, radius: f32) -> Self {
+        assert!(spacing >= 1., "spacing cannot be less than one");
+        Self {
+            spacing,
+            radius: radius.max(0.),
+            stagger: 0.,
+            ink: Color::from_u32(0x000000FF),
+            paper: Color::from_u32(0xFFFFFFFF),
+        }
+    }
+
+    /// Shifts every next row by a fraction of the spacing. Defaults to `0`.
+    ///
+    /// A half shifts odd rows between the dots of even ones, the classic polka-dot layout.
+    pub fn stagger(mut self, stagger: f32) -> Self {
+        self.stagger = stagger;
+        self
+    }
+
+    /// Sets the ink and paper colors. Defaults to black on white.
+    pub fn colors(mut self, ink: Color, paper: Color) -> Self {
+        self.ink = ink;
+        self.paper = paper;
+        self
+    }
+}
+
+impl Source for Dots {
+    fn source(&self, (x, y): (i32, i32)) -> Color {
+        let s = self.spacing;
+        let (x, y) = (x as f32 + 0.5, y as f32 + 0.5);
+        let row = (y / s).floor();
+
+        // Large dots may reach into neighbour cells
+        let mut d = f32::INFINITY;
+        for j in [row - 1., row, row + 1.] {
+            let shift = (j * self.stagger).rem_euclid(1.) * s;
+            let col = ((x - shift) / s).floor();
+            for i in [col - 1., col, col + 1.] {
+                let (cx, cy) = ((i + 0.5) * s + shift, (j + 0.5) * s);
+                d = d.min((x - cx).hypot(y - cy));
+            }
+        }
+
+        let cov = (self.radius - d + 0.5).clamp(0., 1.);
+        self.paper.lerp(self.ink, cov)
+    }
+}
+
+/// A wall of bricks laid in rows.
+///
+/// Every next row is shifted by a fraction of a brick, and every brick gets
+/// its own seeded brightness, so the wall doesn't look printed.
+pub struct Bricks {
+    size: (f32, f32),
+    seed: u32,
+    mortar: f32,
+    offset: f32,
+    jitter: f32,
+    bevel: Option<f32>,
+    brick: Color,
+    joint: Color,
+}
+
+impl Bricks {
+    /// The [`Bricks`] constructor.
+    ///
+    /// The `size` is the width and height of a brick in pixels, without the mortar.
+    ///
+    /// # Panics
+    /// Panics when a side of the `size` is less than one.
+    pub fn new(size: (f32, f32), seed: u32) -> Self {
+        assert!(size.0 >= 1. && size.1 >= 1., "size cannot be less than one");
+        Self {
+            size,
+            seed,
+            mortar: 2.,
+            offset: 0.5,
+            jitter: 0.1,
+            bevel: None,
+            brick: Color::from_u32(0xA0452EFF),
+            joint: Color::from_u32(0xC8C0B4FF),
+        }
+    }
+
+    /// Sets the mortar width between bricks in pixels. Defaults to `2`.
+    pub fn mortar(mut self, width: f32) -> Self {
+        self.mortar = width.max(0.);
+        self
+    }
+
+    /// Sets the shift of every next row as a fraction of a brick. Defaults to `0.5`.
+    pub fn offset(mut self, offset: f32) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Sets how much the brightness of a brick varies, from `0` to `1`. Defaults to `0.1`.
+    pub fn jitter(mut self, jitter: f32) -> Self {
+        self.jitter = jitter.clamp(0., 1.);
+        self
+    }
+
+    /// Shades the brick edges within the `width` in pixels, lighter at the top
+    /// and left and darker at the bottom and right. Disabled by default.
+    pub fn bevel(mut self, width: f32) -> Self {
+        self.bevel = (width > 0.).then_some(width);
+        self
+    }
+
+    /// Sets the brick and mortar colors. Defaults to red bricks on gray mortar.
+    pub fn colors(mut self, brick: Color, mortar: Color) -> Self {
+        self.brick = brick;
+        self.joint = mortar;
+        self
+    }
+}
+
+impl Source for Bricks {
+    fn source(&self, (x, y): (i32, i32)) -> Color {
+        let (w, h) = self.size;
+        let m = self.mortar;
+        let (x, y) = (x as f32 + 0.5, y as f32 + 0.5);
+        let row = (y / (h + m)).floor();
+        let shift = (row * self.offset).rem_euclid(1.) * (w + m);
+        let col = ((x - shift) / (w + m)).floor();
+
+        // Distances to the brick sides, the mortar is split between neighbours
+        let lx = x - shift - col * (w + m) - m * 0.5;
+        let ly = y - row * (h + m) - m * 0.5;
+        let (left, right, top, bottom) = (lx, w - lx, ly, h - ly);
+        let inside = left.min(right).min(top).min(bottom);
+
+        let brick = (col as i32, row as i32);
+        let shade = 1. + (hash::unit(self.seed, brick) * 2. - 1.) * self.jitter;
+        let bevel = match self.bevel {
+            Some(width) if inside < width => {
+                let k = 0.25 * (1. - inside.max(0.) / width);
+                if left.min(top) <= right.min(bottom) {
+                    1. + k
+                } else {
+                    1. - k
+                }
+            }
+            _ => 1.,
+        };
+
+        let k = shade * bevel;
+        let col = Color {
+            r: self.brick.r * k,
+            g: self.brick.g * k,
+            b: self.brick.b * k,
+            a: self.brick.a,
+        }
+        .clamp();
+
+        self.joint.lerp(col, (inside + 0.5).clamp(0., 1.))
+    }
+
+    fn reseed(&mut self, seed: u32) {
+        self.seed = hash::hash(self.seed, (seed as i32, 0));
+    }
+}
@@ -0,0 +1,97 @@
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::Float;
+use {
+    crate::{source::Source, Color},
+    alloc::vec::Vec,
+    core::f32::consts::TAU,
+};
+
+/// Interfering sine waves mapped through a cycling palette.
+///
+/// Changing the [`time`](Self::time) between rendered frames moves
+/// the waves and shifts the palette, a classic animated background.
+pub struct Plasma {
+    scale: f32,
+    time: f32,
+    palette: Option<Vec<Color>>,
+    cycle: f32,
+}
+
+impl Plasma {
+    /// The [`Plasma`] constructor.
+    ///
+    /// The `scale` is the length of a wave in pixels.
+    ///
+    /// # Panics
+    /// Panics when a `scale` is less than or equal to zero.
+    pub fn new(scale: f32) -> Self {
+        assert!(
+            scale > f32::EPSILON,
+            "scale cannot be less than or equal to zero",
+        );
+
+        Self {
+            scale: TAU / scale,
+            time: 0.,
+            palette: None,
+            cycle: 0.1,
+        }
+    }
+
+    /// Sets the time. Defaults to `0`.
+    pub fn time(mut self, t: f32) -> Self {
+        self.time = t;
+        self
+    }
+
+    /// Sets the palette colors, evenly spaced and looping back to the first one.
+    /// Defaults to a rainbow.
+    ///
+    /// # Panics
+    /// Panics when the `palette` is empty.
+    pub fn palette(mut self, palette: Vec<Color>) -> Self {
+        assert!(!palette.is_empty(), "palette cannot be empty");
+        self.palette = Some(palette);
+        self
+    }
+
+    /// Sets how much the palette shifts per unit of time, in palette loops.
+    /// Defaults to `0.1`.
+    pub fn cycle(mut self, cycle: f32) -> Self {
+        self.cycle = cycle;
+        self
+    }
+}
+
+impl Source for Plasma {
+    fn source(&self, (x, y): (i32, i32)) -> Color {
+        let t = self.time;
+        let (x, y) = ((x as f32 + 0.5) * self.scale, (y as f32 + 0.5) * self.scale);
+
+        // A moving center of circular waves
+        let (cx, cy) = (x + (t * 0.3).sin() * 2., y + (t * 0.4).cos() * 2.);
+        let v = (x + t).sin()
+            + ((y + t) * 0.5).sin()
+            + ((x + y + t) * 0.5).sin()
+            + ((cx * cx + cy * cy).sqrt() + t).sin();
+
+        let v = (v * 0.125 + 0.5 + t * self.cycle).rem_euclid(1.);
+        match &self.palette {
+            Some(palette) => {
+                let pos = v * palette.len() as f32;
+                let i = pos as usize % palette.len();
+                let next = (i + 1) % palette.len();
+                palette[i].lerp(palette[next], pos.fract())
+            }
+            None => {
+                let wave = |phase: f32| 0.5 + 0.5 * ((v + phase) * TAU).sin();
+                Color {
+                    r: wave(0.),
+                    g: wave(1. / 3.),
+                    b: wave(2. / 3.),
+                    a: 1.,
+                }
+            }
+        }
+    }
+}
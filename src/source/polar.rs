@@ -0,0 +1,217 @@
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::Float;
+use {
+    crate::{
+        source::{sample, Borders, Filter, Source},
+        Color,
+    },
+    core::f32::consts::TAU,
+};
+
+/// Wraps a source around a center, sampling it in polar coordinates.
+///
+/// The source `x` axis runs along the angle, a full turn spans `period` pixels
+/// starting from the positive `x` direction and going clockwise on screen.
+/// The source `y` axis runs along the distance from the center.
+/// So vertical gradients turn into rings and horizontal repeats into sectors.
+pub struct Polar<S> {
+    source: S,
+    center: (f32, f32),
+    period: f32,
+    tunnel: Option<f32>,
+    filter: Filter,
+}
+
+impl<S> Polar<S> {
+    /// The [`Polar`] constructor.
+    ///
+    /// # Panics
+    /// Panics when a `period` is less than or equal to zero.
+    pub fn new(source: S, center: (f32, f32), period: f32) -> Self {
+        assert!(
+            period > f32::EPSILON,
+            "period cannot be less than or equal to zero",
+        );
+
+        Self {
+            source,
+            center,
+            period,
+            tunnel: None,
+            filter: Filter::Linear,
+        }
+    }
+
+    /// Samples the source `y` at `depth / distance` instead of the distance,
+    /// so the source recedes into the center like the walls of a tunnel.
+    ///
+    /// # Panics
+    /// Panics when a `depth` is less than or equal to zero.
+    pub fn tunnel(mut self, depth: f32) -> Self {
+        assert!(
+            depth > f32::EPSILON,
+            "depth cannot be less than or equal to zero",
+        );
+
+        self.tunnel = Some(depth);
+        self
+    }
+
+    /// Sets the filter used to sample the source. Defaults to [`Filter::Linear`].
+    pub fn filter(mut self, filter: Filter) -> Self {
+        self.filter = filter;
+        self
+    }
+}
+
+impl<S> Source for Polar<S>
+where
+    S: Source,
+{
+    fn source(&self, (x, y): (i32, i32)) -> Color {
+        let dx = x as f32 + 0.5 - self.center.0;
+        let dy = y as f32 + 0.5 - self.center.1;
+        let angle = dy.atan2(dx).rem_euclid(TAU);
+        let distance = (dx * dx + dy * dy).sqrt();
+        let v = match self.tunnel {
+            Some(depth) => depth / distance.max(f32::EPSILON),
+            None => distance,
+        };
+
+        sample(&self.source, self.filter, (angle / TAU * self.period, v))
+    }
+
+    fn borders(&self) -> Option<Borders> {
+        // The tunnel maps the far source rows to the center,
+        // so only the plain mapping is bounded by the last row
+        if self.tunnel.is_some() {
+            return None;
+        }
+
+        let Borders { h: (_, y1), .. } = self.source.borders()?;
+        let r = y1.max(0) as f32 + 2.;
+        let (cx, cy) = self.center;
+        Some(Borders {
+            w: ((cx - r).floor() as _, (cx + r).ceil() as _),
+            h: ((cy - r).floor() as _, (cy + r).ceil() as _),
+        })
+    }
+
+    fn prepare(&mut self, region: Borders) {
+        // Tunnel rows near the center are unbounded, so the source isn't prepared
+        if self.tunnel.is_some() {
+            return;
+        }
+
+        let last = farthest(self.center, region).ceil() as i32;
+        self.source.prepare(
+            Borders {
+                w: (0, self.period.ceil() as i32),
+                h: (0, last),
+            }
+            .expand(1),
+        );
+    }
+
+    fn reseed(&mut self, seed: u32) {
+        self.source.reseed(seed);
+    }
+}
+
+/// Unwraps a source around a center, the inverse of [`Polar`].
+///
+/// The output is a strip of `period` columns, one full turn of angles,
+/// and rows of distances from the center starting at zero.
+/// So rings of the source turn into horizontal lines.
+pub struct Unpolar<S> {
+    source: S,
+    center: (f32, f32),
+    period: f32,
+    filter: Filter,
+}
+
+impl<S> Unpolar<S> {
+    /// The [`Unpolar`] constructor.
+    ///
+    /// # Panics
+    /// Panics when a `period` is less than or equal to zero.
+    pub fn new(source: S, center: (f32, f32), period: f32) -> Self {
+        assert!(
+            period > f32::EPSILON,
+            "period cannot be less than or equal to zero",
+        );
+
+        Self {
+            source,
+            center,
+            period,
+            filter: Filter::Linear,
+        }
+    }
+
+    /// Sets the filter used to sample the source. Defaults to [`Filter::Linear`].
+    pub fn filter(mut self, filter: Filter) -> Self {
+        self.filter = filter;
+        self
+    }
+}
+
+impl<S> Source for Unpolar<S>
+where
+    S: Source,
+{
+    fn source(&self, (x, y): (i32, i32)) -> Color {
+        if x < 0 || y < 0 || x as f32 >= self.period {
+            return Color::default();
+        }
+
+        let angle = (x as f32 + 0.5) / self.period * TAU;
+        let distance = y as f32 + 0.5;
+        let (sin, cos) = angle.sin_cos();
+        let pos = (
+            self.center.0 + distance * cos,
+            self.center.1 + distance * sin,
+        );
+
+        sample(&self.source, self.filter, pos)
+    }
+
+    fn borders(&self) -> Option<Borders> {
+        // Without source borders the distance is unbounded
+        let last = match self.source.borders() {
+            Some(borders) => farthest(self.center, borders).ceil() as i32 + 1,
+            None => i32::MAX,
+        };
+
+        Some(Borders {
+            w: (0, self.period.ceil() as i32 - 1),
+            h: (0, last),
+        })
+    }
+
+    fn prepare(&mut self, region: Borders) {
+        // Rows are distances, so the region reads a disc around the center
+        let r = region.h.1.max(0) as f32 + 2.;
+        let (cx, cy) = self.center;
+        self.source.prepare(Borders {
+            w: ((cx - r).floor() as _, (cx + r).ceil() as _),
+            h: ((cy - r).floor() as _, (cy + r).ceil() as _),
+        });
+    }
+
+    fn reseed(&mut self, seed: u32) {
+        self.source.reseed(seed);
+    }
+}
+
+/// Returns the distance from the center to the farthest corner of the borders.
+fn farthest((cx, cy): (f32, f32), borders: Borders) -> f32 {
+    let Borders {
+        w: (x0, x1),
+        h: (y0, y1),
+    } = borders;
+
+    let far = |a: i32, b: i32, c: f32| (a as f32 - c).abs().max((b as f32 + 1. - c).abs());
+    let (fx, fy) = (far(x0, x1, cx), far(y0, y1, cy));
+    (fx * fx + fy * fy).sqrt()
+}
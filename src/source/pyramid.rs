@@ -0,0 +1,140 @@
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::Float;
+use {
+    crate::{
+        source::{sample, Borders, Filter, Source},
+        Color,
+    },
+    alloc::{vec, vec::Vec},
+};
+
+/// A bounded source with prebuilt downsampled levels.
+///
+/// Each level halves the previous one, down to a single pixel. When minified
+/// with [`Scale`](crate::source::Scale), the levels closest to the scale are
+/// sampled and blended, so the result doesn't alias and costs the same at any scale.
+///
+/// The source is rendered once, on construction, over its borders.
+pub struct Pyramid {
+    region: Borders,
+    levels: Vec<Level>,
+}
+
+impl Pyramid {
+    /// The [`Pyramid`] constructor.
+    ///
+    /// # Panics
+    /// Panics when the source has no borders.
+    pub fn new<S>(source: S) -> Self
+    where
+        S: Source,
+    {
+        let region = source.borders().expect("the source must have borders");
+        let base = Level {
+            pixels: region.positions().map(|pos| source.source(pos)).collect(),
+            size: region.size(),
+        };
+
+        let mut levels = vec![base];
+        while let Some(next) = levels.last().and_then(Level::half) {
+            levels.push(next);
+        }
+
+        Self { region, levels }
+    }
+
+    /// Returns the number of levels, including the full size one.
+    pub fn levels(&self) -> usize {
+        self.levels.len()
+    }
+}
+
+impl Source for Pyramid {
+    fn source(&self, (x, y): (i32, i32)) -> Color {
+        self.levels[0].source((
+            x.wrapping_sub(self.region.w.0),
+            y.wrapping_sub(self.region.h.0),
+        ))
+    }
+
+    fn borders(&self) -> Option<Borders> {
+        Some(self.region)
+    }
+
+    fn sample_scaled(&self, (x, y): (f32, f32), footprint: f32, filter: Filter) -> Color {
+        let at = |n: usize| {
+            let k = 1. / (1 << n) as f32;
+            let pos = (
+                (x - self.region.w.0 as f32) * k,
+                (y - self.region.h.0 as f32) * k,
+            );
+
+            sample(&self.levels[n], filter, pos)
+        };
+
+        let last = self.levels.len() - 1;
+        let lod = footprint.max(1.).log2().min(last as f32);
+        match filter {
+            Filter::Near => at(lod.round() as usize),
+            Filter::Linear => {
+                let n = lod.floor() as usize;
+                let t = lod - n as f32;
+                if n == last || t <= f32::EPSILON {
+                    at(n)
+                } else {
+                    at(n).lerp(at(n + 1), t)
+                }
+            }
+        }
+    }
+}
+
+struct Level {
+    pixels: Vec<Color>,
+    size: (u32, u32),
+}
+
+impl Level {
+    /// Averages every 2x2 block into a pixel of the next level.
+    fn half(&self) -> Option<Self> {
+        let (w, h) = self.size;
+        if w <= 1 && h <= 1 {
+            return None;
+        }
+
+        let size = (w.div_ceil(2), h.div_ceil(2));
+        let pixels = (0..size.1)
+            .flat_map(|y| (0..size.0).map(move |x| (x, y)))
+            .map(|(x, y)| {
+                let mut col = Color::default();
+                let mut n = 0;
+                for (x, y) in [
+                    (2 * x, 2 * y),
+                    (2 * x + 1, 2 * y),
+                    (2 * x, 2 * y + 1),
+                    (2 * x + 1, 2 * y + 1),
+                ] {
+                    if x < w && y < h {
+                        col += self.pixels[(y * w + x) as usize];
+                        n += 1;
+                    }
+                }
+
+                col * (1. / n as f32)
+            })
+            .collect();
+
+        Some(Self { pixels, size })
+    }
+}
+
+impl Source for Level {
+    fn source(&self, (x, y): (i32, i32)) -> Color {
+        let (w, h) = self.size;
+        if x < 0 || y < 0 || x >= w as i32 || y >= h as i32 {
+            return Color::default();
+        }
+
+        self.pixels[(y as u32 * w + x as u32) as usize]
+    }
+}
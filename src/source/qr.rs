@@ -0,0 +1,131 @@
+use {
+    crate::{
+        source::{Borders, Source},
+        Color,
+    },
+    qrcode::{types::QrError, EcLevel},
+};
+
+/// A QR code rendered as a grid of square modules.
+///
+/// The code is surrounded by a quiet zone of light modules,
+/// which scanners need to find it.
+pub struct QrCode {
+    modules: Vec<bool>,
+    width: u32,
+    module: u32,
+    quiet: u32,
+    dark: Color,
+    light: Color,
+}
+
+impl QrCode {
+    /// Encodes the text with the [`QrLevel::Medium`] error correction.
+    ///
+    /// # Errors
+    /// Returns an error if the text is too long to encode.
+    pub fn new(text: &str) -> Result<Self, QrError> {
+        Self::with_level(text, QrLevel::Medium)
+    }
+
+    /// Encodes the text with the given error correction level.
+    ///
+    /// # Errors
+    /// Returns an error if the text is too long to encode.
+    pub fn with_level(text: &str, level: QrLevel) -> Result<Self, QrError> {
+        let level = match level {
+            QrLevel::Low => EcLevel::L,
+            QrLevel::Medium => EcLevel::M,
+            QrLevel::Quartile => EcLevel::Q,
+            QrLevel::High => EcLevel::H,
+        };
+
+        let code = qrcode::QrCode::with_error_correction_level(text, level)?;
+        let modules = code
+            .to_colors()
+            .into_iter()
+            .map(|c| c == qrcode::Color::Dark)
+            .collect();
+
+        Ok(Self {
+            modules,
+            width: code.width() as u32,
+            module: 4,
+            quiet: 4,
+            dark: Color::from_u32(0x000000FF),
+            light: Color::from_u32(0xFFFFFFFF),
+        })
+    }
+
+    /// Sets the size of a module in pixels. Defaults to `4`.
+    ///
+    /// # Panics
+    /// Panics when a `size` is zero.
+    pub fn module(mut self, size: u32) -> Self {
+        assert!(size > 0, "module size cannot be zero");
+        self.module = size;
+        self
+    }
+
+    /// Sets the width of the quiet zone in modules. Defaults to `4`.
+    pub fn quiet_zone(mut self, modules: u32) -> Self {
+        self.quiet = modules;
+        self
+    }
+
+    /// Sets the module colors. Defaults to black on white.
+    pub fn colors(mut self, dark: Color, light: Color) -> Self {
+        self.dark = dark;
+        self.light = light;
+        self
+    }
+
+    /// Returns the size of the code with the quiet zone in pixels.
+    pub fn size(&self) -> (u32, u32) {
+        let side = (self.width + self.quiet * 2) * self.module;
+        (side, side)
+    }
+}
+
+impl Source for QrCode {
+    fn source(&self, (x, y): (i32, i32)) -> Color {
+        let (side, _) = self.size();
+        match (u32::try_from(x), u32::try_from(y)) {
+            (Ok(x), Ok(y)) if x < side && y < side => {
+                let mx = (x / self.module).wrapping_sub(self.quiet);
+                let my = (y / self.module).wrapping_sub(self.quiet);
+                let dark = mx < self.width
+                    && my < self.width
+                    && self.modules[(my * self.width + mx) as usize];
+
+                if dark {
+                    self.dark
+                } else {
+                    self.light
+                }
+            }
+            _ => Color::default(),
+        }
+    }
+
+    fn borders(&self) -> Option<Borders> {
+        let (side, _) = self.size();
+        Some(Borders {
+            w: (0, side as i32 - 1),
+            h: (0, side as i32 - 1),
+        })
+    }
+}
+
+/// The error correction level of a [`QrCode`].
+#[derive(Clone, Copy)]
+pub enum QrLevel {
+    /// Recovers 7% of the code.
+    Low,
+    /// Recovers 15% of the code.
+    Medium,
+    /// Recovers 25% of the code.
+    Quartile,
+    /// Recovers 30% of the code.
+    High,
+}
@@ -0,0 +1,184 @@
+use {
+    crate::{
+        hash,
+        source::{Borders, Source},
+        Color,
+    },
+    alloc::{vec, vec::Vec},
+};
+
+/// Parameters of the Gray–Scott reaction–diffusion model.
+///
+/// Two chemicals diffuse over the grid, `a` is fed in and `b` is killed
+/// at the given rates, while `b` grows by consuming `a`.
+#[derive(Clone, Copy)]
+pub struct GrayScott {
+    pub feed: f32,
+    pub kill: f32,
+    pub diffusion_a: f32,
+    pub diffusion_b: f32,
+}
+
+impl GrayScott {
+    /// Branching coral-like growth.
+    pub const CORAL: Self = Self::new(0.0545, 0.062);
+
+    /// Winding stripes like fingerprints or a maze.
+    pub const FINGERPRINT: Self = Self::new(0.029, 0.057);
+
+    /// Separate spots that keep dividing.
+    pub const SPOTS: Self = Self::new(0.0367, 0.0649);
+
+    /// Creates parameters with the `feed` and `kill` rates and common diffusion rates.
+    pub const fn new(feed: f32, kill: f32) -> Self {
+        Self {
+            feed,
+            kill,
+            diffusion_a: 1.,
+            diffusion_b: 0.5,
+        }
+    }
+}
+
+impl Default for GrayScott {
+    fn default() -> Self {
+        Self::CORAL
+    }
+}
+
+/// A pattern grown by a reaction–diffusion simulation.
+///
+/// The simulation runs once, on construction, over a grid of the given size
+/// placed at the origin. The grid wraps around, so the pattern tiles seamlessly.
+/// It starts from seeded spots of the second chemical, which is drawn with ink.
+pub struct ReactionDiffusion {
+    size: (u32, u32),
+    values: Vec<f32>,
+    ink: Color,
+    paper: Color,
+}
+
+impl ReactionDiffusion {
+    /// The [`ReactionDiffusion`] constructor.
+    ///
+    /// Patterns take a few thousand `iterations` to develop.
+    ///
+    /// # Panics
+    /// Panics when a side of the `size` is zero.
+    pub fn new(size: (u32, u32), iterations: u32, params: GrayScott, seed: u32) -> Self {
+        let (w, h) = size;
+        assert!(w > 0 && h > 0, "size cannot be zero");
+
+        let (w, h) = (w as usize, h as usize);
+        let mut a = vec![1_f32; w * h];
+        let mut b = vec![0_f32; w * h];
+
+        // Seed square spots of the second chemical, about one per 32×32 area
+        let spots = (w * h / 1024).max(1);
+        for k in 0..spots as i32 {
+            let cx = hash::hash(seed, (k, 0)) as usize % w;
+            let cy = hash::hash(seed, (k, 1)) as usize % h;
+            for dy in 0..4 {
+                for dx in 0..4 {
+                    b[(cy + dy) % h * w + (cx + dx) % w] = 1.;
+                }
+            }
+        }
+
+        let mut next = (vec![0.; w * h], vec![0.; w * h]);
+        for _ in 0..iterations {
+            step(&a, &b, &mut next, (w, h), params);
+            core::mem::swap(&mut a, &mut next.0);
+            core::mem::swap(&mut b, &mut next.1);
+        }
+
+        let values = a
+            .iter()
+            .zip(&b)
+            .map(|(a, b)| (a - b).clamp(0., 1.))
+            .collect();
+        Self {
+            size,
+            values,
+            ink: Color::from_u32(0x000000FF),
+            paper: Color::from_u32(0xFFFFFFFF),
+        }
+    }
+
+    /// Sets the ink and paper colors. Defaults to black on white.
+    pub fn colors(mut self, ink: Color, paper: Color) -> Self {
+        self.ink = ink;
+        self.paper = paper;
+        self
+    }
+}
+
+impl Source for ReactionDiffusion {
+    fn source(&self, (x, y): (i32, i32)) -> Color {
+        let (w, h) = self.size;
+        if x < 0 || y < 0 || x as u32 >= w || y as u32 >= h {
+            return Color::default();
+        }
+
+        let v = self.values[y as usize * w as usize + x as usize];
+        self.ink.lerp(self.paper, v)
+    }
+
+    fn borders(&self) -> Option<Borders> {
+        let (w, h) = self.size;
+        Some(Borders {
+            w: (0, w as i32 - 1),
+            h: (0, h as i32 - 1),
+        })
+    }
+}
+
+/// Advances the simulation by one step into the `next` grids.
+fn step(
+    a: &[f32],
+    b: &[f32],
+    next: &mut (Vec<f32>, Vec<f32>),
+    (w, h): (usize, usize),
+    params: GrayScott,
+) {
+    let row = |y: usize, (na, nb): (&mut [f32], &mut [f32])| {
+        let (up, down) = ((y + h - 1) % h, (y + 1) % h);
+        for x in 0..w {
+            let (left, right) = ((x + w - 1) % w, (x + 1) % w);
+            let laplacian = |v: &[f32]| {
+                let adjacent = v[up * w + x] + v[down * w + x] + v[y * w + left] + v[y * w + right];
+                let diagonal =
+                    v[up * w + left] + v[up * w + right] + v[down * w + left] + v[down * w + right];
+
+                adjacent * 0.2 + diagonal * 0.05 - v[y * w + x]
+            };
+
+            let (va, vb) = (a[y * w + x], b[y * w + x]);
+            let reaction = va * vb * vb;
+            na[x] = (va + params.diffusion_a * laplacian(a) - reaction + params.feed * (1. - va))
+                .clamp(0., 1.);
+
+            nb[x] = (vb + params.diffusion_b * laplacian(b) + reaction
+                - (params.kill + params.feed) * vb)
+                .clamp(0., 1.);
+        }
+    };
+
+    #[cfg(feature = "parallel")]
+    {
+        use rayon::prelude::*;
+
+        next.0
+            .par_chunks_mut(w)
+            .zip(next.1.par_chunks_mut(w))
+            .enumerate()
+            .for_each(|(y, rows)| row(y, rows));
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    next.0
+        .chunks_mut(w)
+        .zip(next.1.chunks_mut(w))
+        .enumerate()
+        .for_each(|(y, rows)| row(y, rows));
+}
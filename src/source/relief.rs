@@ -0,0 +1,284 @@
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::Float;
+use {
+    crate::{
+        source::{Borders, Source},
+        Color,
+    },
+    alloc::vec::Vec,
+};
+
+/// Generates a tangent-space normal map from a heightmap.
+///
+/// The source luminance is treated as height. Normals are encoded
+/// as colors with each component mapped from `-1..=1` to `0..=1`.
+pub struct NormalMap<S> {
+    source: S,
+    strength: f32,
+    invert_height: bool,
+    flip_y: bool,
+}
+
+impl<S> NormalMap<S> {
+    pub fn new(source: S, strength: f32) -> Self {
+        Self {
+            source,
+            strength,
+            invert_height: false,
+            flip_y: false,
+        }
+    }
+
+    /// Treats dark colors as high and bright colors as low.
+    pub fn invert_height(mut self) -> Self {
+        self.invert_height = true;
+        self
+    }
+
+    /// Flips the green channel, converting between OpenGL and DirectX conventions.
+    pub fn flip_y(mut self) -> Self {
+        self.flip_y = true;
+        self
+    }
+}
+
+impl<S> Source for NormalMap<S>
+where
+    S: Source,
+{
+    fn source(&self, pos: (i32, i32)) -> Color {
+        if let Some(borders) = self.borders() {
+            if !borders.contains(pos) {
+                return Color::default();
+            }
+        }
+
+        let (dx, dy) = gradient(&self.source, pos, self.invert_height);
+        let (x, y, z) = normalize((-dx * self.strength, dy * self.strength, 1.));
+        let y = if self.flip_y { -y } else { y };
+        Color {
+            r: x * 0.5 + 0.5,
+            g: y * 0.5 + 0.5,
+            b: z * 0.5 + 0.5,
+            a: 1.,
+        }
+    }
+
+    fn borders(&self) -> Option<Borders> {
+        self.source.borders().map(|b| b.expand(1))
+    }
+
+    fn prepare(&mut self, region: Borders) {
+        self.source.prepare(region.expand(1));
+    }
+
+    fn reseed(&mut self, seed: u32) {
+        self.source.reseed(seed);
+    }
+}
+
+/// Shaded relief of a heightmap lit by the sun.
+///
+/// The source luminance is treated as height.
+pub struct Hillshade<S> {
+    source: S,
+    light: (f32, f32, f32),
+    exaggeration: f32,
+}
+
+impl<S> Hillshade<S> {
+    /// The [`Hillshade`] constructor.
+    ///
+    /// The sun `azimuth` is measured in degrees clockwise from north, the top of the image,
+    /// and the `altitude` in degrees above the horizon.
+    pub fn new(source: S, azimuth: f32, altitude: f32) -> Self {
+        let (az_sin, az_cos) = azimuth.to_radians().sin_cos();
+        let (alt_sin, alt_cos) = altitude.to_radians().sin_cos();
+        Self {
+            source,
+            light: (az_sin * alt_cos, -az_cos * alt_cos, alt_sin),
+            exaggeration: 1.,
+        }
+    }
+
+    /// Sets the vertical exaggeration, the height of full luminance in pixels. Defaults to `1`.
+    pub fn exaggeration(mut self, exaggeration: f32) -> Self {
+        self.exaggeration = exaggeration;
+        self
+    }
+}
+
+impl<S> Source for Hillshade<S>
+where
+    S: Source,
+{
+    fn source(&self, pos: (i32, i32)) -> Color {
+        if let Some(borders) = self.borders() {
+            if !borders.contains(pos) {
+                return Color::default();
+            }
+        }
+
+        let (dx, dy) = gradient(&self.source, pos, false);
+        let (nx, ny, nz) = normalize((-dx * self.exaggeration, -dy * self.exaggeration, 1.));
+        let (lx, ly, lz) = self.light;
+        let v = (nx * lx + ny * ly + nz * lz).max(0.);
+        Color {
+            r: v,
+            g: v,
+            b: v,
+            a: 1.,
+        }
+    }
+
+    fn borders(&self) -> Option<Borders> {
+        self.source.borders().map(|b| b.expand(1))
+    }
+
+    fn prepare(&mut self, region: Borders) {
+        self.source.prepare(region.expand(1));
+    }
+
+    fn reseed(&mut self, seed: u32) {
+        self.source.reseed(seed);
+    }
+}
+
+/// Shades an albedo source with a normal map under a set of lights.
+///
+/// Normals are decoded the way [`NormalMap`] encodes them, with green pointing up.
+/// Light vectors use image coordinates: `x` to the right, `y` down and `z` toward the viewer.
+pub struct Lit<A, N> {
+    albedo: A,
+    normals: N,
+    lights: Vec<Light>,
+    ambient: Color,
+    specular: Option<(f32, f32)>,
+}
+
+impl<A, N> Lit<A, N> {
+    pub fn new(albedo: A, normals: N, lights: Vec<Light>) -> Self {
+        Self {
+            albedo,
+            normals,
+            lights,
+            ambient: Color::default(),
+            specular: None,
+        }
+    }
+
+    /// Sets the ambient light color. Defaults to no ambient light.
+    pub fn ambient(mut self, ambient: Color) -> Self {
+        self.ambient = ambient;
+        self
+    }
+
+    /// Enables Blinn-Phong specular highlights of given strength and shininess exponent.
+    pub fn specular(mut self, strength: f32, shininess: f32) -> Self {
+        self.specular = Some((strength, shininess));
+        self
+    }
+}
+
+impl<A, N> Source for Lit<A, N>
+where
+    A: Source,
+    N: Source,
+{
+    fn source(&self, (x, y): (i32, i32)) -> Color {
+        let albedo = self.albedo.source((x, y));
+        if !albedo.is_visible() {
+            return albedo;
+        }
+
+        let n = self.normals.source((x, y));
+        let n = normalize((n.r * 2. - 1., 1. - n.g * 2., n.b * 2. - 1.));
+        let mut diffuse = self.ambient;
+        let mut specular = Color::default();
+        for light in &self.lights {
+            let (l, color) = match *light {
+                Light::Directional { direction, color } => (normalize(direction), color),
+                Light::Point {
+                    position: (lx, ly, lz),
+                    color,
+                } => (normalize((lx - x as f32, ly - y as f32, lz)), color),
+            };
+
+            let lambert = dot(n, l).max(0.);
+            diffuse += color * lambert;
+            if let Some((strength, shininess)) = self.specular {
+                if lambert > 0. {
+                    let h = normalize((l.0, l.1, l.2 + 1.));
+                    specular += color * (dot(n, h).max(0.).powf(shininess) * strength);
+                }
+            }
+        }
+
+        let res = albedo * diffuse + specular;
+        Color {
+            a: albedo.a,
+            ..res.clamp()
+        }
+    }
+
+    fn borders(&self) -> Option<Borders> {
+        self.albedo.borders()
+    }
+
+    fn prepare(&mut self, region: Borders) {
+        self.albedo.prepare(region);
+        self.normals.prepare(region);
+    }
+
+    fn reseed(&mut self, seed: u32) {
+        self.albedo.reseed(seed);
+        self.normals.reseed(seed);
+    }
+}
+
+pub enum Light {
+    /// A light infinitely far away, the `direction` points toward the light.
+    Directional {
+        direction: (f32, f32, f32),
+        color: Color,
+    },
+
+    /// A light at the given position in pixels, with no attenuation.
+    Point {
+        position: (f32, f32, f32),
+        color: Color,
+    },
+}
+
+/// Computes the height gradient with the Sobel operator, in luminance per pixel.
+fn gradient<S>(source: &S, (x, y): (i32, i32), invert: bool) -> (f32, f32)
+where
+    S: Source,
+{
+    let h = |dx, dy| {
+        let l = source.source((x + dx, y + dy)).luminance();
+        if invert {
+            1. - l
+        } else {
+            l
+        }
+    };
+
+    let dx = (h(1, -1) + 2. * h(1, 0) + h(1, 1)) - (h(-1, -1) + 2. * h(-1, 0) + h(-1, 1));
+    let dy = (h(-1, 1) + 2. * h(0, 1) + h(1, 1)) - (h(-1, -1) + 2. * h(0, -1) + h(1, -1));
+    // The weights of each side add up to 4 over a distance of 2 pixels
+    (dx * 0.125, dy * 0.125)
+}
+
+fn normalize((x, y, z): (f32, f32, f32)) -> (f32, f32, f32) {
+    let len = (x * x + y * y + z * z).sqrt();
+    if len > f32::EPSILON {
+        (x / len, y / len, z / len)
+    } else {
+        (0., 0., 1.)
+    }
+}
+
+fn dot((ax, ay, az): (f32, f32, f32), (bx, by, bz): (f32, f32, f32)) -> f32 {
+    ax * bx + ay * by + az * bz
+}
@@ -0,0 +1,284 @@
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::Float;
+use {
+    crate::{
+        hash,
+        math::lerp,
+        source::{sample, Borders, Filter, Source},
+        Color,
+    },
+    alloc::{vec, vec::Vec},
+};
+
+/// Stamps a source at many seeded random positions within a region.
+///
+/// Each instance can be randomly rotated, scaled and tinted.
+/// Later instances are painted over earlier ones.
+pub struct Scatter<S> {
+    source: S,
+    stamp: Borders,
+    region: Borders,
+    count: u32,
+    seed: u32,
+    rotation: f32,
+    scale: (f32, f32),
+    tints: Vec<Color>,
+    filter: Filter,
+    instances: Vec<Instance>,
+    bins: Bins,
+}
+
+impl<S> Scatter<S>
+where
+    S: Source,
+{
+    /// The [`Scatter`] constructor.
+    ///
+    /// Places `count` instances with centers inside the `region`.
+    ///
+    /// # Panics
+    /// Panics when the `source` has no borders.
+    pub fn new(source: S, region: Borders, count: u32, seed: u32) -> Self {
+        let stamp = source.borders().expect("the source must have borders");
+        Self {
+            source,
+            stamp,
+            region,
+            count,
+            seed,
+            rotation: 0.,
+            scale: (1., 1.),
+            tints: vec![],
+            filter: Filter::Linear,
+            instances: vec![],
+            bins: Bins::default(),
+        }
+        .scatter()
+    }
+
+    /// Sets the maximum rotation angle in radians,
+    /// each instance is rotated by a random angle in `-angle..angle`. Defaults to `0`.
+    pub fn rotation(mut self, angle: f32) -> Self {
+        self.rotation = angle.abs();
+        self.scatter()
+    }
+
+    /// Sets the range of random instance scales. Defaults to `(1, 1)`.
+    ///
+    /// # Panics
+    /// Panics when a `min` is less than or equal to zero or greater than a `max`.
+    pub fn scale(mut self, min: f32, max: f32) -> Self {
+        assert!(
+            min > f32::EPSILON,
+            "min cannot be less than or equal to zero"
+        );
+        assert!(min <= max, "min cannot be greater than max");
+        self.scale = (min, max);
+        self.scatter()
+    }
+
+    /// Sets colors to randomly tint instances with. Defaults to no tint.
+    pub fn tints<I>(mut self, tints: I) -> Self
+    where
+        I: IntoIterator<Item = Color>,
+    {
+        self.tints = tints.into_iter().collect();
+        self.scatter()
+    }
+
+    /// Sets the sampling filter for transformed instances. Defaults to [`Filter::Linear`].
+    pub fn filter(mut self, filter: Filter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    fn scatter(mut self) -> Self {
+        self.place();
+        self
+    }
+
+    fn place(&mut self) {
+        let (sw, sh) = self.stamp.size();
+        let (rw, rh) = self.region.size();
+        let half = (sw as f32 / 2., sh as f32 / 2.);
+        let extent = (half.0 * half.0 + half.1 * half.1).sqrt();
+
+        self.instances = (0..self.count as i32)
+            .map(|i| {
+                let rand = |k| hash::unit(self.seed, (i, k));
+                let center = (
+                    self.region.w.0 as f32 + rand(0) * rw as f32,
+                    self.region.h.0 as f32 + rand(1) * rh as f32,
+                );
+
+                let angle = lerp(-self.rotation, self.rotation, rand(2));
+                let scale = lerp(self.scale.0, self.scale.1, rand(3));
+                let tint = match self.tints.len() {
+                    0 => None,
+                    n => Some(self.tints[hash::hash(self.seed, (i, 4)) as usize % n]),
+                };
+
+                // Rotated stamps need the whole diagonal, aligned ones only their half size
+                let (ex, ey) = if angle == 0. {
+                    (half.0 * scale, half.1 * scale)
+                } else {
+                    (extent * scale, extent * scale)
+                };
+
+                Instance {
+                    center,
+                    sin_cos: angle.sin_cos(),
+                    scale,
+                    tint,
+                    bounds: Borders {
+                        w: ((center.0 - ex).floor() as _, (center.0 + ex).ceil() as _),
+                        h: ((center.1 - ey).floor() as _, (center.1 + ey).ceil() as _),
+                    },
+                }
+            })
+            .collect();
+
+        let cell = (extent * self.scale.1).ceil().max(1.) as i32 * 2;
+        self.bins = Bins::new(&self.instances, cell);
+    }
+}
+
+impl<S> Source for Scatter<S>
+where
+    S: Source,
+{
+    fn source(&self, (x, y): (i32, i32)) -> Color {
+        let mut col = Color::default();
+        let Some(ids) = self.bins.get((x, y)) else {
+            return col;
+        };
+
+        let (sw, sh) = self.stamp.size();
+        let origin = (
+            self.stamp.w.0 as f32 + sw as f32 / 2.,
+            self.stamp.h.0 as f32 + sh as f32 / 2.,
+        );
+
+        let (px, py) = (x as f32 + 0.5, y as f32 + 0.5);
+        for &id in ids {
+            let inst = &self.instances[id as usize];
+            if !inst.bounds.contains((x, y)) {
+                continue;
+            }
+
+            // Map the pixel back into the stamp space
+            let (sin, cos) = inst.sin_cos;
+            let (dx, dy) = (px - inst.center.0, py - inst.center.1);
+            let lx = (dx * cos + dy * sin) / inst.scale + origin.0;
+            let ly = (dy * cos - dx * sin) / inst.scale + origin.1;
+            if lx < self.stamp.w.0 as f32
+                || ly < self.stamp.h.0 as f32
+                || lx >= (self.stamp.w.1 + 1) as f32
+                || ly >= (self.stamp.h.1 + 1) as f32
+            {
+                continue;
+            }
+
+            let mut stamp = sample(&self.source, self.filter, (lx, ly));
+            if let Some(tint) = inst.tint {
+                stamp *= tint;
+            }
+
+            col = col.overlay(stamp);
+        }
+
+        col
+    }
+
+    fn borders(&self) -> Option<Borders> {
+        self.instances
+            .iter()
+            .map(|inst| inst.bounds)
+            .reduce(Borders::union)
+            .or(Some(Borders {
+                w: (0, -1),
+                h: (0, -1),
+            }))
+    }
+
+    /// Places instances again with the mixed seed.
+    fn reseed(&mut self, seed: u32) {
+        self.seed = hash::hash(self.seed, (seed as i32, 0));
+        self.source.reseed(seed);
+        self.place();
+    }
+
+    fn prepare(&mut self, _: Borders) {
+        // Instances read only the stamp, linear filtering reads one more pixel around it
+        self.source.prepare(self.stamp.expand(1));
+    }
+}
+
+struct Instance {
+    center: (f32, f32),
+    sin_cos: (f32, f32),
+    scale: f32,
+    tint: Option<Color>,
+    bounds: Borders,
+}
+
+/// A uniform grid of instance lists for fast lookups.
+#[derive(Default)]
+struct Bins {
+    origin: (i32, i32),
+    cols: i32,
+    rows: i32,
+    cell: i32,
+    lists: Vec<Vec<u32>>,
+}
+
+impl Bins {
+    fn new(instances: &[Instance], cell: i32) -> Self {
+        let Some(area) = instances
+            .iter()
+            .map(|inst| inst.bounds)
+            .reduce(Borders::union)
+        else {
+            return Self::default();
+        };
+
+        let (w, h) = area.size();
+        let cols = (w as i32 + cell - 1) / cell;
+        let rows = (h as i32 + cell - 1) / cell;
+        let mut lists = vec![vec![]; (cols * rows) as usize];
+        for (id, inst) in instances.iter().enumerate() {
+            let Borders {
+                w: (x0, x1),
+                h: (y0, y1),
+            } = inst.bounds;
+
+            for row in (y0 - area.h.0) / cell..=(y1 - area.h.0) / cell {
+                for col in (x0 - area.w.0) / cell..=(x1 - area.w.0) / cell {
+                    lists[(row * cols + col) as usize].push(id as u32);
+                }
+            }
+        }
+
+        Self {
+            origin: (area.w.0, area.h.0),
+            cols,
+            rows,
+            cell,
+            lists,
+        }
+    }
+
+    fn get(&self, (x, y): (i32, i32)) -> Option<&[u32]> {
+        let (dx, dy) = (x - self.origin.0, y - self.origin.1);
+        if dx < 0 || dy < 0 {
+            return None;
+        }
+
+        let (col, row) = (dx / self.cell, dy / self.cell);
+        if col >= self.cols || row >= self.rows {
+            return None;
+        }
+
+        Some(&self.lists[(row * self.cols + col) as usize])
+    }
+}
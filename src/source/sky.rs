@@ -0,0 +1,310 @@
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::Float;
+use {
+    crate::{
+        hash,
+        math::smoothstep,
+        source::{Fbm, Field, Perlin, Source},
+        Color,
+    },
+    alloc::{vec, vec::Vec},
+    core::ops::Range,
+};
+
+/// The side of a starfield cell holding at most one star.
+const CELL: f32 = 16.;
+
+/// Seeded stars on a dark backdrop.
+///
+/// Most stars are dim and small, few are bright, and colors vary
+/// slightly from bluish to yellowish white.
+pub struct Starfield {
+    seed: u32,
+    density: f32,
+    size: f32,
+    spikes: Option<f32>,
+    backdrop: Color,
+    nebula: Option<(Fbm<Perlin>, f32, Color)>,
+}
+
+impl Starfield {
+    /// The [`Starfield`] constructor.
+    pub fn new(seed: u32) -> Self {
+        Self {
+            seed,
+            density: 0.3,
+            size: 1.5,
+            spikes: None,
+            backdrop: Color::from_u32(0x05060AFF),
+            nebula: None,
+        }
+    }
+
+    /// Sets the chance of a star in a 16×16 cell, from `0` to `1`. Defaults to `0.3`.
+    pub fn density(mut self, density: f32) -> Self {
+        self.density = density.clamp(0., 1.);
+        self
+    }
+
+    /// Sets the glow radius of the brightest stars in pixels. Defaults to `1.5`.
+    pub fn size(mut self, size: f32) -> Self {
+        self.size = size.max(0.);
+        self
+    }
+
+    /// Adds diffraction spikes of the given length in pixels to bright stars.
+    pub fn spikes(mut self, length: f32) -> Self {
+        self.spikes = (length > 0.).then_some(length);
+        self
+    }
+
+    /// Sets the color of the empty sky. Defaults to near black.
+    pub fn backdrop(mut self, color: Color) -> Self {
+        self.backdrop = color;
+        self
+    }
+
+    /// Adds a nebula of the color behind the stars, with clouds
+    /// of about the `scale` in pixels.
+    ///
+    /// # Panics
+    /// Panics when a `scale` is less than or equal to zero.
+    pub fn nebula(mut self, color: Color, scale: f32) -> Self {
+        assert!(
+            scale > f32::EPSILON,
+            "scale cannot be less than or equal to zero",
+        );
+
+        let noise = Fbm::new(Perlin::new(hash::hash(self.seed, (0, 1))), 5);
+        self.nebula = Some((noise, 1. / scale, color));
+        self
+    }
+
+    /// Returns the light of the star in a cell at the position, if there is any.
+    fn star(&self, cell: (i32, i32), (x, y): (f32, f32)) -> Option<[f32; 3]> {
+        let h = hash::hash(self.seed, cell);
+        if hash::unit(h, (0, 0)) >= self.density {
+            return None;
+        }
+
+        let sx = (cell.0 as f32 + hash::unit(h, (1, 0))) * CELL;
+        let sy = (cell.1 as f32 + hash::unit(h, (2, 0))) * CELL;
+        let (dx, dy) = (x - sx, y - sy);
+
+        // Few stars are bright
+        let brightness = hash::unit(h, (3, 0)).powi(3);
+        let radius = 0.4 + self.size * brightness;
+        let mut light = (-(dx * dx + dy * dy) / (radius * radius)).exp();
+        if let Some(length) = self.spikes {
+            if brightness > 0.3 {
+                let spike = |along: f32, across: f32| {
+                    (-along.abs() / (length * brightness)).exp() * (-across * across * 4.).exp()
+                };
+
+                light = light.max(0.6 * spike(dx, dy).max(spike(dy, dx)));
+            }
+        }
+
+        let light = light * (0.3 + 0.7 * brightness);
+        if light < 1. / 512. {
+            return None;
+        }
+
+        let warmth = hash::unit(h, (4, 0)) * 2. - 1.;
+        Some([
+            light * (1. + 0.15 * warmth),
+            light,
+            light * (1. - 0.15 * warmth),
+        ])
+    }
+}
+
+impl Source for Starfield {
+    fn source(&self, (x, y): (i32, i32)) -> Color {
+        let (x, y) = (x as f32 + 0.5, y as f32 + 0.5);
+        let mut col = match &self.nebula {
+            Some((noise, scale, color)) => {
+                let v = (noise.value((x * scale, y * scale)) * 0.5 + 0.5).clamp(0., 1.);
+                self.backdrop.lerp(*color, v * v)
+            }
+            None => self.backdrop,
+        };
+
+        // Glows and spikes reach into neighbour cells
+        let reach = self.size * 3. + self.spikes.map_or(0., |length| length * 4.);
+        let (cx, cy) = ((x / CELL).floor() as i32, (y / CELL).floor() as i32);
+        let n = (reach / CELL).ceil() as i32;
+        for j in cy - n..=cy + n {
+            for i in cx - n..=cx + n {
+                if let Some([r, g, b]) = self.star((i, j), (x, y)) {
+                    col.r += r;
+                    col.g += g;
+                    col.b += b;
+                }
+            }
+        }
+
+        col.clamp()
+    }
+
+    fn reseed(&mut self, seed: u32) {
+        self.seed = hash::hash(self.seed, (seed as i32, 0));
+        if let Some((noise, ..)) = &mut self.nebula {
+            noise.reseed(seed);
+        }
+    }
+}
+
+/// A daytime sky: a vertical gradient with layers of clouds and an optional sun.
+///
+/// Cloud edges near the sun are lit brighter, a silver lining.
+pub struct Sky {
+    seed: u32,
+    horizon: f32,
+    zenith_color: Color,
+    horizon_color: Color,
+    layers: Vec<(Fbm<Perlin>, f32, f32)>,
+    softness: f32,
+    sun: Option<(f32, f32)>,
+}
+
+impl Sky {
+    /// The [`Sky`] constructor.
+    ///
+    /// The `horizon` is the row in pixels where the gradient reaches the horizon color.
+    ///
+    /// # Panics
+    /// Panics when a `horizon` is less than or equal to zero.
+    pub fn new(horizon: f32, seed: u32) -> Self {
+        assert!(
+            horizon > f32::EPSILON,
+            "horizon cannot be less than or equal to zero",
+        );
+
+        Self {
+            seed,
+            horizon,
+            zenith_color: Color::from_u32(0x2F6DB5FF),
+            horizon_color: Color::from_u32(0xBFD9EEFF),
+            layers: vec![],
+            softness: 0.2,
+            sun: None,
+        }
+    }
+
+    /// Sets the colors at the top and at the horizon. Defaults to a clear blue sky.
+    pub fn gradient(mut self, zenith: Color, horizon: Color) -> Self {
+        self.zenith_color = zenith;
+        self.horizon_color = horizon;
+        self
+    }
+
+    /// Adds a layer of clouds over the previous ones.
+    ///
+    /// The `scale` is the size of clouds in pixels and the `coverage`
+    /// is the part of the sky they cover, from `0` to `1`.
+    ///
+    /// # Panics
+    /// Panics when a `scale` is less than or equal to zero.
+    pub fn clouds(mut self, scale: f32, coverage: f32) -> Self {
+        assert!(
+            scale > f32::EPSILON,
+            "scale cannot be less than or equal to zero",
+        );
+
+        let seed = hash::hash(self.seed, (self.layers.len() as i32, 2));
+        let noise = Fbm::new(Perlin::new(seed), 6);
+        self.layers
+            .push((noise, 1. / scale, coverage.clamp(0., 1.)));
+        self
+    }
+
+    /// Sets how gradually cloud edges fade, from `0` for sharp edges to `1`.
+    /// Defaults to `0.2`.
+    pub fn softness(mut self, softness: f32) -> Self {
+        self.softness = softness.clamp(0., 1.);
+        self
+    }
+
+    /// Adds a sun centered at the position in pixels.
+    pub fn sun(mut self, pos: (f32, f32)) -> Self {
+        self.sun = Some(pos);
+        self
+    }
+
+    /// Returns the sky gradient color of the row.
+    fn gradient_at(&self, y: f32) -> Color {
+        let t = (y / self.horizon).clamp(0., 1.);
+        self.zenith_color.lerp(self.horizon_color, t)
+    }
+
+    /// Draws the sun and clouds at the position over the gradient color.
+    fn shade(&self, (x, y): (f32, f32), mut col: Color) -> Color {
+        const SUN: Color = Color {
+            r: 1.,
+            g: 0.96,
+            b: 0.84,
+            a: 1.,
+        };
+
+        const SHADE: Color = Color {
+            r: 0.72,
+            g: 0.74,
+            b: 0.8,
+            a: 1.,
+        };
+
+        // The sun disc with a wide glow, and how close the position is to it
+        let near = match self.sun {
+            Some((sx, sy)) => {
+                let d = (x - sx).hypot(y - sy);
+                let glow = 0.6 * (-d / 48.).exp() + (12. - d + 0.5).clamp(0., 1.);
+                col = col.lerp(SUN, glow.min(1.));
+                (-d / 96.).exp()
+            }
+            None => 0.,
+        };
+
+        for (noise, scale, coverage) in &self.layers {
+            let v = noise.value((x * scale, y * scale)) * 0.5 + 0.5;
+            let edge = 1. - coverage;
+            let half = self.softness * 0.5;
+            let density = smoothstep(edge - half, edge + half, v);
+            if density <= 0. {
+                continue;
+            }
+
+            // Thin edges of clouds let the sun through
+            let lining = near * (1. - density) * 2.;
+            let cloud = Color::from_u32(0xFFFFFFFF)
+                .lerp(SHADE, density * density * 0.6)
+                .lerp(SUN, lining.min(1.));
+
+            col = col.lerp(cloud, density);
+        }
+
+        col
+    }
+}
+
+impl Source for Sky {
+    fn source(&self, (x, y): (i32, i32)) -> Color {
+        let (x, y) = (x as f32 + 0.5, y as f32 + 0.5);
+        self.shade((x, y), self.gradient_at(y))
+    }
+
+    fn source_row(&self, y: i32, xs: Range<i32>, out: &mut [Color]) {
+        let y = y as f32 + 0.5;
+        let col = self.gradient_at(y);
+        for (x, out) in xs.zip(out) {
+            *out = self.shade((x as f32 + 0.5, y), col);
+        }
+    }
+
+    fn reseed(&mut self, seed: u32) {
+        self.seed = hash::hash(self.seed, (seed as i32, 0));
+        for (noise, ..) in &mut self.layers {
+            noise.reseed(seed);
+        }
+    }
+}
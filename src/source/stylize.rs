@@ -0,0 +1,245 @@
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::Float;
+use crate::{
+    hash,
+    source::{Borders, Source},
+    Color,
+};
+
+/// Converts a source into print-style halftone screens.
+///
+/// By default every color channel gets its own screen rotated by its own angle,
+/// like cyan, magenta and yellow plates in print.
+pub struct Halftone<S> {
+    source: S,
+    period: f32,
+    shape: Dot,
+    angles: [f32; 3],
+    mono: bool,
+}
+
+impl<S> Halftone<S> {
+    /// The [`Halftone`] constructor.
+    ///
+    /// The `period` is the screen cell size in pixels.
+    ///
+    /// # Panics
+    /// Panics when a `period` is less than one.
+    pub fn new(source: S, period: f32, shape: Dot) -> Self {
+        assert!(period >= 1., "period cannot be less than one");
+        Self {
+            source,
+            period,
+            shape,
+            angles: [15., 75., 0.],
+            mono: false,
+        }
+    }
+
+    /// Sets the screen angles in degrees for red, green and blue channels.
+    /// Defaults to `[15, 75, 0]`.
+    pub fn angles(mut self, angles: [f32; 3]) -> Self {
+        self.angles = angles;
+        self
+    }
+
+    /// Uses a single luminance screen at the first angle instead of one screen per channel.
+    pub fn mono(mut self) -> Self {
+        self.mono = true;
+        self
+    }
+
+    fn screen(&self, (x, y): (f32, f32), angle: f32, value: f32) -> f32 {
+        let (sin, cos) = angle.to_radians().sin_cos();
+        let u = (x * cos + y * sin) / self.period;
+        let v = (y * cos - x * sin) / self.period;
+        let u = u - u.floor() - 0.5;
+        let v = v - v.floor() - 0.5;
+        let f = match self.shape {
+            Dot::Round => core::f32::consts::PI * (u * u + v * v),
+            Dot::Square => (u.abs().max(v.abs()) * 2.).powi(2),
+            Dot::Diamond => 2. * (u.abs() + v.abs()).powi(2),
+            Dot::Line => v.abs() * 2.,
+        };
+
+        // The amount of ink covering the point, smoothed over a pixel footprint
+        let ink = 1. - value.clamp(0., 1.);
+        let aa = 1. / self.period;
+        1. - ((ink - f) / aa + 0.5).clamp(0., 1.)
+    }
+}
+
+impl<S> Source for Halftone<S>
+where
+    S: Source,
+{
+    fn source(&self, (x, y): (i32, i32)) -> Color {
+        let col = self.source.source((x, y));
+        let pos = (x as f32 + 0.5, y as f32 + 0.5);
+        if self.mono {
+            let v = self.screen(pos, self.angles[0], col.luminance());
+            Color {
+                r: v,
+                g: v,
+                b: v,
+                a: col.a,
+            }
+        } else {
+            let [ar, ag, ab] = self.angles;
+            Color {
+                r: self.screen(pos, ar, col.r),
+                g: self.screen(pos, ag, col.g),
+                b: self.screen(pos, ab, col.b),
+                a: col.a,
+            }
+        }
+    }
+
+    fn borders(&self) -> Option<Borders> {
+        self.source.borders()
+    }
+
+    fn prepare(&mut self, region: Borders) {
+        self.source.prepare(region);
+    }
+
+    fn reseed(&mut self, seed: u32) {
+        self.source.reseed(seed);
+    }
+}
+
+pub enum Dot {
+    Round,
+    Square,
+    Diamond,
+    Line,
+}
+
+/// Pen-and-ink stylization.
+///
+/// Darker luminance bands get more layers of hatch lines or denser stipple points.
+pub struct Hatch<S> {
+    source: S,
+    spacing: f32,
+    style: HatchStyle,
+    width: f32,
+    ink: Color,
+    paper: Color,
+}
+
+impl<S> Hatch<S> {
+    /// The [`Hatch`] constructor.
+    ///
+    /// The `spacing` is the distance between lines or stipple points in pixels.
+    ///
+    /// # Panics
+    /// Panics when a `spacing` is less than one.
+    pub fn new(source: S, spacing: f32, style: HatchStyle) -> Self {
+        assert!(spacing >= 1., "spacing cannot be less than one");
+        Self {
+            source,
+            spacing,
+            style,
+            width: 1.,
+            ink: Color::from_u32(0x000000FF),
+            paper: Color::from_u32(0xFFFFFFFF),
+        }
+    }
+
+    /// Sets the line width or the stipple point diameter in pixels. Defaults to `1`.
+    pub fn width(mut self, width: f32) -> Self {
+        self.width = width.max(0.);
+        self
+    }
+
+    /// Sets the ink and paper colors. Defaults to black on white.
+    pub fn colors(mut self, ink: Color, paper: Color) -> Self {
+        self.ink = ink;
+        self.paper = paper;
+        self
+    }
+
+    fn coverage(&self, (x, y): (f32, f32), darkness: f32) -> f32 {
+        match self.style {
+            HatchStyle::Lines => {
+                const ANGLES: [f32; 4] = [45., -45., 0., 90.];
+
+                let mut cov = 0_f32;
+                for (k, angle) in ANGLES.into_iter().enumerate() {
+                    let threshold = (k + 1) as f32 / (ANGLES.len() + 1) as f32;
+                    if darkness <= threshold {
+                        break;
+                    }
+
+                    let (sin, cos) = angle.to_radians().sin_cos();
+                    let v = (y * cos - x * sin) / self.spacing;
+                    let d = (v - v.round()).abs() * self.spacing;
+                    cov = cov.max((self.width * 0.5 - d + 0.5).clamp(0., 1.));
+                }
+
+                cov
+            }
+            HatchStyle::Stipple { seed } => {
+                let cx = (x / self.spacing).floor() as i32;
+                let cy = (y / self.spacing).floor() as i32;
+                let mut cov = 0_f32;
+                for dy in -1..=1 {
+                    for dx in -1..=1 {
+                        let cell = (cx + dx, cy + dy);
+                        if hash::unit(seed, cell) >= darkness {
+                            continue;
+                        }
+
+                        // Jitter the point inside its cell
+                        let px = (cell.0 as f32 + hash::unit(seed ^ 1, cell)) * self.spacing;
+                        let py = (cell.1 as f32 + hash::unit(seed ^ 2, cell)) * self.spacing;
+                        let d = ((x - px).powi(2) + (y - py).powi(2)).sqrt();
+                        cov = cov.max((self.width * 0.5 - d + 0.5).clamp(0., 1.));
+                    }
+                }
+
+                cov
+            }
+        }
+    }
+}
+
+impl<S> Source for Hatch<S>
+where
+    S: Source,
+{
+    fn source(&self, (x, y): (i32, i32)) -> Color {
+        let col = self.source.source((x, y));
+        let darkness = 1. - col.luminance().clamp(0., 1.);
+        let cov = self.coverage((x as f32 + 0.5, y as f32 + 0.5), darkness);
+        let res = self.paper.lerp(self.ink, cov);
+        Color {
+            a: res.a * col.a,
+            ..res
+        }
+    }
+
+    fn borders(&self) -> Option<Borders> {
+        self.source.borders()
+    }
+
+    fn reseed(&mut self, render: u32) {
+        if let HatchStyle::Stipple { seed } = &mut self.style {
+            *seed = hash::hash(*seed, (render as i32, 0));
+        }
+
+        self.source.reseed(render);
+    }
+
+    fn prepare(&mut self, region: Borders) {
+        self.source.prepare(region);
+    }
+}
+
+pub enum HatchStyle {
+    /// Up to four layers of crossing lines.
+    Lines,
+
+    /// Seeded points on a jittered grid.
+    Stipple { seed: u32 },
+}
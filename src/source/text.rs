@@ -0,0 +1,239 @@
+use {
+    crate::{
+        source::{Borders, Source},
+        Color,
+    },
+    alloc::{borrow::ToOwned, string::String, vec, vec::Vec},
+};
+
+/// Text rendered with the built-in 8×8 bitmap font.
+///
+/// The text is rasterized on construction and placed at the origin.
+/// Line feeds start new lines and long lines can be wrapped to a width.
+/// Characters missing in the font are drawn as `?`.
+pub struct Text {
+    text: String,
+    scale: u32,
+    wrap: Option<u32>,
+    spacing: u32,
+    align: TextAlign,
+    mask: Vec<bool>,
+    size: (u32, u32),
+    color: Color,
+}
+
+impl Text {
+    /// The height of a glyph at scale `1`.
+    pub const GLYPH_SIZE: u32 = 8;
+
+    /// The [`Text`] constructor.
+    ///
+    /// Every glyph pixel is drawn as a `scale × scale` square.
+    ///
+    /// # Panics
+    /// Panics when a `scale` is zero.
+    pub fn new(text: &str, scale: u32, color: Color) -> Self {
+        assert!(scale > 0, "scale cannot be zero");
+
+        Self {
+            text: text.to_owned(),
+            scale,
+            wrap: None,
+            spacing: 0,
+            align: TextAlign::Left,
+            mask: vec![],
+            size: (0, 0),
+            color,
+        }
+        .layout()
+    }
+
+    /// Wraps words to fit lines into the width in pixels.
+    /// Words longer than a line are broken.
+    ///
+    /// The text block takes the whole width then.
+    pub fn wrap(mut self, width: u32) -> Self {
+        self.wrap = Some(width);
+        self.layout()
+    }
+
+    /// Sets the extra space between lines in pixels. Defaults to `0`.
+    pub fn line_spacing(mut self, spacing: u32) -> Self {
+        self.spacing = spacing;
+        self.layout()
+    }
+
+    /// Sets the line alignment within the block. Defaults to [`TextAlign::Left`].
+    pub fn align(mut self, align: TextAlign) -> Self {
+        self.align = align;
+        self.layout()
+    }
+
+    /// Returns the text block size in pixels.
+    pub fn size(&self) -> (u32, u32) {
+        self.size
+    }
+
+    fn layout(mut self) -> Self {
+        let glyph = Self::GLYPH_SIZE * self.scale;
+        let max_cols = self.wrap.map(|w| (w / glyph).max(1) as usize);
+        let lines = break_lines(&self.text, max_cols);
+        let line_width = |line: &Line| {
+            let chars: usize = line.words.iter().map(Vec::len).sum();
+            (chars + line.words.len().saturating_sub(1)) as u32 * glyph
+        };
+
+        let w = match self.wrap {
+            Some(w) => w,
+            None => lines.iter().map(line_width).max().unwrap_or_default(),
+        };
+
+        let n = lines.len() as u32;
+        let h = n * glyph + n.saturating_sub(1) * self.spacing;
+        let mut mask = vec![false; w as usize * h as usize];
+        for (i, line) in lines.iter().enumerate() {
+            let y = i as u32 * (glyph + self.spacing);
+            let free = w.saturating_sub(line_width(line));
+            let gaps = line.words.len().saturating_sub(1) as u32;
+            let mut x = match self.align {
+                TextAlign::Left | TextAlign::Justify => 0,
+                TextAlign::Center => free / 2,
+                TextAlign::Right => free,
+            };
+
+            let justify = matches!(self.align, TextAlign::Justify) && !line.last && gaps > 0;
+            for (k, word) in line.words.iter().enumerate() {
+                if k > 0 {
+                    x += glyph;
+                    if justify {
+                        let k = k as u32;
+                        x += free * k / gaps - free * (k - 1) / gaps;
+                    }
+                }
+
+                for &ch in word {
+                    self.draw(&mut mask, (w, h), ch, (x, y));
+                    x += glyph;
+                }
+            }
+        }
+
+        self.mask = mask;
+        self.size = (w, h);
+        self
+    }
+
+    fn draw(&self, mask: &mut [bool], (w, h): (u32, u32), ch: char, (x0, y0): (u32, u32)) {
+        let scale = self.scale;
+        let glyph = Self::GLYPH_SIZE * scale;
+        let rows = glyph_rows(ch);
+        for y in 0..glyph.min(h - y0) {
+            for x in 0..glyph.min(w.saturating_sub(x0)) {
+                let row = rows[(y / scale) as usize];
+                if row & (1 << (x / scale)) != 0 {
+                    mask[((y0 + y) * w + x0 + x) as usize] = true;
+                }
+            }
+        }
+    }
+}
+
+/// The alignment of text lines.
+#[derive(Clone, Copy)]
+pub enum TextAlign {
+    Left,
+    Center,
+    Right,
+    /// Stretches wrapped lines to the full width, except the last line of a paragraph.
+    Justify,
+}
+
+struct Line {
+    words: Vec<Vec<char>>,
+    last: bool,
+}
+
+/// Splits text into lines of words, wrapping them to `max_cols` characters if given.
+fn break_lines(text: &str, max_cols: Option<usize>) -> Vec<Line> {
+    let mut lines = vec![];
+    for paragraph in text.split('\n') {
+        // Without wrapping, empty words keep the original spacing
+        let words = paragraph.split(' ').map(|word| word.chars().collect());
+        let Some(max) = max_cols else {
+            lines.push(Line {
+                words: words.collect(),
+                last: true,
+            });
+
+            continue;
+        };
+
+        let mut line: Vec<Vec<char>> = vec![];
+        let mut len = 0;
+        for mut word in words.filter(|word: &Vec<char>| !word.is_empty()) {
+            while !word.is_empty() {
+                let gap = usize::from(!line.is_empty());
+                if len + gap + word.len() <= max {
+                    len += gap + word.len();
+                    line.push(word);
+                    break;
+                }
+
+                if line.is_empty() {
+                    // The word is longer than a line, so break it
+                    let rest = word.split_off(max);
+                    line.push(word);
+                    word = rest;
+                }
+
+                lines.push(Line {
+                    words: core::mem::take(&mut line),
+                    last: false,
+                });
+
+                len = 0;
+            }
+        }
+
+        lines.push(Line {
+            words: line,
+            last: true,
+        });
+    }
+
+    lines
+}
+
+impl Source for Text {
+    fn source(&self, (x, y): (i32, i32)) -> Color {
+        let (w, h) = self.size;
+        if x < 0 || y < 0 || x >= w as i32 || y >= h as i32 {
+            return Color::default();
+        }
+
+        if self.mask[(y as u32 * w + x as u32) as usize] {
+            self.color
+        } else {
+            Color::default()
+        }
+    }
+
+    fn borders(&self) -> Option<Borders> {
+        let (w, h) = self.size;
+        Some(Borders {
+            w: (0, w as i32 - 1),
+            h: (0, h as i32 - 1),
+        })
+    }
+}
+
+/// Returns glyph rows, the lowest bit of each row is the leftmost pixel.
+fn glyph_rows(ch: char) -> [u8; 8] {
+    use font8x8::{UnicodeFonts, BASIC_FONTS, LATIN_FONTS};
+
+    BASIC_FONTS
+        .get(ch)
+        .or_else(|| LATIN_FONTS.get(ch))
+        .or_else(|| BASIC_FONTS.get('?'))
+        .unwrap_or_default()
+}
@@ -0,0 +1,117 @@
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::Float;
+use crate::{
+    math::smoothstep,
+    source::{Borders, Source},
+    Color,
+};
+
+/// Darkens or lightens a source toward the corners.
+pub struct Vignette<S> {
+    source: S,
+    region: Option<Borders>,
+    amount: f32,
+    radius: f32,
+    softness: f32,
+    roundness: f32,
+}
+
+impl<S> Vignette<S>
+where
+    S: Source,
+{
+    /// The [`Vignette`] constructor.
+    ///
+    /// A positive `amount` darkens the corners, a negative one lightens them.
+    /// The vignette is anchored to the source borders, use [`canvas`](Self::canvas)
+    /// to anchor it to a canvas of given size instead.
+    /// With no borders and no canvas the source is passed through unchanged.
+    pub fn new(source: S, amount: f32) -> Self {
+        Self {
+            region: source.borders(),
+            source,
+            amount: amount.clamp(-1., 1.),
+            radius: 0.75,
+            softness: 0.5,
+            roundness: 1.,
+        }
+    }
+
+    /// Anchors the vignette to a canvas of the given size.
+    pub fn canvas(mut self, (w, h): (u32, u32)) -> Self {
+        self.region = Some(Borders {
+            w: (0, w as i32 - 1),
+            h: (0, h as i32 - 1),
+        });
+
+        self
+    }
+
+    /// Sets the distance from the center where the effect begins,
+    /// where `1` is the edge of the canvas. Defaults to `0.75`.
+    pub fn radius(mut self, radius: f32) -> Self {
+        self.radius = radius.max(0.);
+        self
+    }
+
+    /// Sets the width of the transition. Defaults to `0.5`.
+    pub fn softness(mut self, softness: f32) -> Self {
+        self.softness = softness.max(0.);
+        self
+    }
+
+    /// Sets the roundness of the vignette shape in range `0..=1`,
+    /// where `1` is an ellipse and `0` is close to a rectangle. Defaults to `1`.
+    pub fn roundness(mut self, roundness: f32) -> Self {
+        self.roundness = roundness.clamp(0., 1.);
+        self
+    }
+}
+
+impl<S> Source for Vignette<S>
+where
+    S: Source,
+{
+    fn source(&self, (x, y): (i32, i32)) -> Color {
+        let col = self.source.source((x, y));
+        let Some(Borders {
+            w: (x0, x1),
+            h: (y0, y1),
+        }) = self.region
+        else {
+            return col;
+        };
+
+        let half = |a: i32, b: i32| ((b as f32 - a as f32 + 1.) * 0.5).max(0.5);
+        let center = |a: i32, b: i32| (a as f32 + b as f32) * 0.5;
+        let u = (x as f32 - center(x0, x1)) / half(x0, x1);
+        let v = (y as f32 - center(y0, y1)) / half(y0, y1);
+
+        // A superellipse turns from an ellipse into a rectangle as the exponent grows
+        let p = 2. + (1. - self.roundness) * 8.;
+        let d = (u.abs().powf(p) + v.abs().powf(p)).powf(1. / p);
+        let t = smoothstep(self.radius, self.radius + self.softness, d) * self.amount.abs();
+        let target = if self.amount > 0. {
+            Color::from_u32(0x000000FF)
+        } else {
+            Color::from_u32(0xFFFFFFFF)
+        };
+
+        Color {
+            a: col.a,
+            ..col.lerp(target, t)
+        }
+    }
+
+    fn borders(&self) -> Option<Borders> {
+        self.source.borders()
+    }
+
+    fn prepare(&mut self, region: Borders) {
+        self.source.prepare(region);
+    }
+
+    fn reseed(&mut self, seed: u32) {
+        self.source.reseed(seed);
+    }
+}
@@ -0,0 +1,113 @@
+use {
+    crate::{make::render, source::Source, Frame, RenderOptions},
+    std::{
+        future::Future,
+        panic::{self, AssertUnwindSafe},
+        pin::Pin,
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc, Mutex,
+        },
+        task::{Context, Poll, Waker},
+        thread,
+    },
+};
+
+/// Renders a source like [`make_with`](crate::make_with) without blocking the caller.
+///
+/// The render runs on a worker thread, or on the global thread pool with the
+/// `parallel` feature, and the returned future resolves to the frame.
+/// Dropping the future cancels the render, rows that aren't started yet are skipped.
+///
+/// A panic of the source is caught on the worker and resumed when the future is polled.
+pub fn make_async<S>(source: S, size: (u32, u32), opts: RenderOptions) -> RenderTask
+where
+    S: Source + Send + Sync + 'static,
+{
+    let shared = Arc::new(Shared {
+        state: Mutex::default(),
+        cancel: AtomicBool::new(false),
+    });
+
+    let work = {
+        let shared = Arc::clone(&shared);
+        move || {
+            let result = panic::catch_unwind(AssertUnwindSafe(|| {
+                render(source, size, &opts, None, &shared.cancel)
+            }));
+
+            let mut state = shared.state.lock().expect("lock");
+            state.result = Some(result);
+            state.finished = true;
+            if let Some(waker) = state.waker.take() {
+                waker.wake();
+            }
+        }
+    };
+
+    #[cfg(feature = "parallel")]
+    rayon::spawn(work);
+
+    #[cfg(not(feature = "parallel"))]
+    std::thread::spawn(work);
+
+    RenderTask { shared }
+}
+
+/// A render in progress, see [`make_async`].
+pub struct RenderTask {
+    shared: Arc<Shared>,
+}
+
+struct Shared {
+    state: Mutex<State>,
+    cancel: AtomicBool,
+}
+
+#[derive(Default)]
+struct State {
+    result: Option<thread::Result<Frame>>,
+    finished: bool,
+    waker: Option<Waker>,
+}
+
+impl RenderTask {
+    /// Cancels the render. The future still resolves, to a partially rendered frame.
+    pub fn cancel(&self) {
+        self.shared.cancel.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns `true` if the render is finished, even after the frame is taken.
+    pub fn is_finished(&self) -> bool {
+        self.shared.state.lock().expect("lock").finished
+    }
+}
+
+impl Future for RenderTask {
+    type Output = Frame;
+
+    /// # Panics
+    /// Panics when the source panicked during the render,
+    /// or when the frame is already taken.
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        let mut state = self.shared.state.lock().expect("lock");
+        match state.result.take() {
+            Some(Ok(frame)) => Poll::Ready(frame),
+            Some(Err(payload)) => {
+                drop(state);
+                panic::resume_unwind(payload)
+            }
+            None if state.finished => panic!("the render task is polled after completion"),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl Drop for RenderTask {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
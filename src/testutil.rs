@@ -0,0 +1,218 @@
+use {
+    crate::{
+        source::{Diff, DiffMode},
+        Frame,
+    },
+    std::{
+        env,
+        fs::{self, File},
+        io::{self, BufReader, BufWriter},
+        path::{Path, PathBuf},
+    },
+};
+
+/// The environment variable with a directory where failed assertions dump their images.
+pub const DIFF_DIR_VAR: &str = "NIED_DIFF_DIR";
+
+/// The environment variable which makes [`assert_golden`] overwrite golden images.
+pub const UPDATE_GOLDEN_VAR: &str = "NIED_UPDATE_GOLDEN";
+
+/// Differences between two frames of the same size.
+#[derive(Clone, Copy, Debug)]
+pub struct Report {
+    /// The largest difference of every channel.
+    pub max: [u8; 4],
+
+    /// The number of pixels with any difference.
+    pub differing: usize,
+
+    /// The position of the largest difference in any channel, if the frames differ.
+    pub worst: Option<(u32, u32)>,
+}
+
+impl Report {
+    /// Checks that no channel differs by more than the `tolerance`.
+    pub fn within(&self, tolerance: u8) -> bool {
+        self.max.iter().all(|&d| d <= tolerance)
+    }
+}
+
+/// Compares two frames pixel by pixel.
+///
+/// # Panics
+/// Panics when the frames have different sizes.
+pub fn compare(a: &Frame, b: &Frame) -> Report {
+    assert_eq!(a.size(), b.size(), "frames have different sizes");
+
+    let mut report = Report {
+        max: [0; 4],
+        differing: 0,
+        worst: None,
+    };
+
+    let mut worst = 0;
+    for (n, (pa, pb)) in a.pixels().zip(b.pixels()).enumerate() {
+        let mut largest = 0;
+        for c in 0..4 {
+            let d = pa[c].abs_diff(pb[c]);
+            report.max[c] = report.max[c].max(d);
+            largest = largest.max(d);
+        }
+
+        if largest > 0 {
+            report.differing += 1;
+        }
+
+        if largest > worst {
+            worst = largest;
+            let n = n as u32;
+            report.worst = Some((n % a.width, n / a.width));
+        }
+    }
+
+    report
+}
+
+/// Asserts that no channel of the frames differs by more than the `tolerance`.
+///
+/// When the [`DIFF_DIR_VAR`] environment variable is set, the frames and
+/// their difference are dumped there with [`dump_diff`] before panicking,
+/// named after the current thread, which is the test name under `cargo test`.
+///
+/// # Panics
+/// Panics when the frames have different sizes or differ by more than the `tolerance`.
+#[track_caller]
+pub fn assert_images_eq_within(a: &Frame, b: &Frame, tolerance: u8) {
+    assert_eq!(a.size(), b.size(), "frames have different sizes");
+
+    let report = compare(a, b);
+    if report.within(tolerance) {
+        return;
+    }
+
+    let [mr, mg, mb, ma] = report.max;
+    let mut message = format!(
+        "frames differ by more than {tolerance}: max r {mr} g {mg} b {mb} a {ma}, \
+         {} pixels differ, the worst at {:?}",
+        report.differing,
+        report.worst.expect("frames differ"),
+    );
+
+    if let Some(dir) = env::var_os(DIFF_DIR_VAR) {
+        match dump_diff(a, b, dir, &test_name()) {
+            Ok(path) => message += &format!(", the diff is dumped to {}", path.display()),
+            Err(err) => message += &format!(", failed to dump the diff: {err}"),
+        }
+    }
+
+    panic!("{message}");
+}
+
+/// Asserts that the frame matches the golden PNG image at the `path`
+/// within the `tolerance`, like [`assert_images_eq_within`].
+///
+/// When the [`UPDATE_GOLDEN_VAR`] environment variable is set, the frame is
+/// written as the new golden image instead.
+///
+/// # Panics
+/// Panics when the golden image can't be read or written, isn't an 8-bit RGBA PNG
+/// or differs from the frame.
+#[track_caller]
+pub fn assert_golden<P>(frame: &Frame, path: P, tolerance: u8)
+where
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    if env::var_os(UPDATE_GOLDEN_VAR).is_some() {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).expect("create the golden image directory");
+        }
+
+        if let Err(err) = write_png(frame, path) {
+            panic!("failed to write {}: {err}", path.display());
+        }
+
+        return;
+    }
+
+    let golden = match read_png(path) {
+        Ok(golden) => golden,
+        Err(err) => panic!(
+            "failed to read {}: {err}, set {UPDATE_GOLDEN_VAR} to create it",
+            path.display(),
+        ),
+    };
+
+    assert_eq!(
+        frame.size(),
+        golden.size(),
+        "the frame size differs from {}",
+        path.display(),
+    );
+
+    assert_images_eq_within(frame, &golden, tolerance);
+}
+
+/// Writes both frames and a heat map of their difference as PNG images
+/// named `<name>.a.png`, `<name>.b.png` and `<name>.diff.png` into the directory,
+/// creating it if needed.
+///
+/// Returns the path of the difference image.
+///
+/// # Errors
+/// Returns an error if the directory or an image can't be written.
+pub fn dump_diff<P>(a: &Frame, b: &Frame, dir: P, name: &str) -> io::Result<PathBuf>
+where
+    P: AsRef<Path>,
+{
+    let dir = dir.as_ref();
+    fs::create_dir_all(dir)?;
+    write_png(a, &dir.join(format!("{name}.a.png")))?;
+    write_png(b, &dir.join(format!("{name}.b.png")))?;
+
+    let size = (a.width.max(b.width), a.height.max(b.height));
+    let diff = crate::make(Diff::new(a, b, 4., DiffMode::Heat), size);
+    let path = dir.join(format!("{name}.diff.png"));
+    write_png(&diff, &path)?;
+    Ok(path)
+}
+
+/// Returns the current thread name usable as a file name.
+fn test_name() -> String {
+    let thread = std::thread::current();
+    let name = thread.name().unwrap_or("frame");
+    name.replace("::", "-").replace(
+        |c: char| !c.is_ascii_alphanumeric() && c != '-' && c != '_',
+        "_",
+    )
+}
+
+fn write_png(frame: &Frame, path: &Path) -> io::Result<()> {
+    use png::{BitDepth, ColorType, Encoder};
+
+    let file = BufWriter::new(File::create(path)?);
+    let mut encoder = Encoder::new(file, frame.width, frame.height);
+    encoder.set_color(ColorType::Rgba);
+    encoder.set_depth(BitDepth::Eight);
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(&frame.data)?;
+    writer.finish()?;
+    Ok(())
+}
+
+fn read_png(path: &Path) -> io::Result<Frame> {
+    use png::{BitDepth, ColorType, Decoder};
+
+    let mut reader = Decoder::new(BufReader::new(File::open(path)?)).read_info()?;
+    let info = reader.info();
+    if info.color_type != ColorType::Rgba || info.bit_depth != BitDepth::Eight {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "not an 8-bit RGBA image",
+        ));
+    }
+
+    let mut frame = Frame::new((info.width, info.height));
+    reader.next_frame(&mut frame.data)?;
+    Ok(frame)
+}
@@ -0,0 +1,282 @@
+//! Checks that sources are transparent outside the borders they report,
+//! and that preparing them for a region doesn't change how they render it.
+
+#![cfg(feature = "std")]
+
+use nied::{
+    source::{
+        Affine, Balance, Bilateral, Blur, Borders, Channel, Channels, ChromaKey, Clip, Close,
+        Convolve, Diff, DiffMode, Dilate, DistanceField, DistanceMode, Dot, Element, Equalization,
+        Equalize, Erode, Filter, Fit, FitMode, Framed, GaussianBlur, GradientMap, Grain, Halftone,
+        Hatch, HatchStyle, Hillshade, Insets, Kernel, Kuwahara, KuwaharaKind, Mapped, MorphKey,
+        NineSlice, NormalMap, Offset, OilPaint, Open, Polar, Pyramid, ReplaceColor, RoundedCorners,
+        Scale, SliceMode, Solarize, Source, Temperature, TileCache, Unpolar, Vignette,
+        WhiteBalance,
+    },
+    Frame,
+};
+
+/// A small deterministic generator, so failures reproduce.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u32 {
+        self.0 = self
+            .0
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+
+        (self.0 >> 33) as u32
+    }
+
+    fn range(&mut self, lo: i32, hi: i32) -> i32 {
+        lo + (self.next() % (hi - lo + 1) as u32) as i32
+    }
+}
+
+/// A bounded source with visible pixels on its edges, moved to a random position.
+fn base(rng: &mut Rng) -> Offset<Frame> {
+    let (w, h) = (rng.range(1, 12) as u32, rng.range(1, 12) as u32);
+    let mut frame = Frame::new((w, h));
+    for px in frame.data.chunks_mut(Frame::N_CHANNELS) {
+        for c in &mut px[..3] {
+            *c = rng.next() as u8;
+        }
+
+        px[3] = [0, 128, 255][rng.next() as usize % 3];
+    }
+
+    // Opaque corners make sure the borders are reached
+    for (x, y) in [(0, 0), (w - 1, 0), (0, h - 1), (w - 1, h - 1)] {
+        let i = (y * w + x) as usize * Frame::N_CHANNELS;
+        frame.data[i + 3] = 255;
+    }
+
+    let offset = (rng.range(-20, 20), rng.range(-20, 20));
+    Offset::new(frame, offset)
+}
+
+type Case = (&'static str, Box<dyn Source>);
+
+fn cases(rng: &mut Rng) -> Vec<Case> {
+    let factor = [0.3, 0.5, 0.7, 1., 1.5, 2., 3.][rng.next() as usize % 7];
+    let filter = [Filter::Near, Filter::Linear][rng.next() as usize % 2];
+    let radius = rng.range(0, 4) as u8;
+    let key = [MorphKey::Alpha, MorphKey::Luminance][rng.next() as usize % 2];
+    let element = [Element::Square, Element::Disc][rng.next() as usize % 2];
+    let size = (rng.range(1, 20) as u32, rng.range(1, 20) as u32);
+
+    vec![
+        ("base", Box::new(base(rng))),
+        ("scale", Box::new(Scale::new(base(rng), factor, filter))),
+        (
+            "pyramid",
+            Box::new(Scale::new(Pyramid::new(base(rng)), factor, filter)),
+        ),
+        ("blur", Box::new(Blur::new(base(rng), radius))),
+        (
+            "gaussian",
+            Box::new(GaussianBlur::new(base(rng), 0.5 + radius as f32)),
+        ),
+        (
+            "convolve",
+            Box::new(Convolve::new(
+                base(rng),
+                Kernel::new((3, 3), vec![0., 1., 0., 1., -4., 1., 0., 1., 0.]),
+            )),
+        ),
+        (
+            "dilate",
+            Box::new(Dilate::new(base(rng), radius, element, key)),
+        ),
+        (
+            "erode",
+            Box::new(Erode::new(base(rng), radius, element, key)),
+        ),
+        ("open", Box::new(Open::new(base(rng), radius, element, key))),
+        (
+            "close",
+            Box::new(Close::new(base(rng), radius, element, key)),
+        ),
+        ("bilateral", Box::new(Bilateral::new(base(rng), 2., 0.2))),
+        (
+            "kuwahara",
+            Box::new(Kuwahara::new(base(rng), radius, KuwaharaKind::Classic)),
+        ),
+        ("oil", Box::new(OilPaint::new(base(rng), radius, 8))),
+        (
+            "rounded",
+            Box::new(RoundedCorners::new(base(rng), radius as f32)),
+        ),
+        (
+            "framed",
+            Box::new(Framed::new(
+                base(rng),
+                radius as u32,
+                nied::Color::from_u32(0xFF0000FF),
+            )),
+        ),
+        (
+            "nine-slice",
+            Box::new(NineSlice::new(
+                base(rng),
+                Insets::uniform(1),
+                size,
+                SliceMode::Stretch,
+            )),
+        ),
+        ("fit", Box::new(Fit::new(base(rng), size, FitMode::Contain))),
+        (
+            "diff",
+            Box::new(Diff::new(base(rng), base(rng), 1., DiffMode::Channels)),
+        ),
+        (
+            "gradient-map",
+            Box::new(GradientMap::duotone(
+                base(rng),
+                nied::Color::from_u32(0x000000FF),
+                nied::Color::from_u32(0xFFFFFFFF),
+            )),
+        ),
+        ("solarize", Box::new(Solarize::new(base(rng), 0.5))),
+        (
+            "temperature",
+            Box::new(Temperature::new(base(rng), 4000., 0.)),
+        ),
+        (
+            "white-balance",
+            Box::new(WhiteBalance::new(base(rng), Balance::GrayWorld)),
+        ),
+        ("channels", Box::new(Channels::new(base(rng), Channel::Key))),
+        (
+            "alpha-plate",
+            Box::new(Channels::new(base(rng), Channel::Alpha)),
+        ),
+        ("grain", Box::new(Grain::new(base(rng), rng.next()))),
+        ("vignette", Box::new(Vignette::new(base(rng), 0.5))),
+        ("normal-map", Box::new(NormalMap::new(base(rng), 2.))),
+        ("hillshade", Box::new(Hillshade::new(base(rng), 315., 45.))),
+        (
+            "halftone",
+            Box::new(Halftone::new(base(rng), 3., Dot::Round)),
+        ),
+        (
+            "hatch",
+            Box::new(Hatch::new(base(rng), 3., HatchStyle::Lines)),
+        ),
+        (
+            "equalize",
+            Box::new(Equalize::new(base(rng), Equalization::Histogram)),
+        ),
+        ("tile-cache", Box::new(TileCache::new(base(rng), 4, 16))),
+        (
+            "distance",
+            Box::new(DistanceField::new(base(rng), 3., DistanceMode::Signed)),
+        ),
+        (
+            "chroma-key",
+            Box::new(ChromaKey::new(
+                base(rng),
+                nied::Color::from_u32(0x00FF00FF),
+                0.3,
+            )),
+        ),
+        (
+            "replace-color",
+            Box::new(ReplaceColor::new(
+                base(rng),
+                nied::Color::from_u32(0x00FF00FF),
+                nied::Color::from_u32(0xFF0000FF),
+                0.3,
+            )),
+        ),
+        ("layers", Box::new(vec![base(rng), base(rng), base(rng)])),
+        ("clip", Box::new(Clip::new(Blur::new(base(rng), radius)))),
+        (
+            "polar",
+            Box::new(Polar::new(base(rng), (2.5, -3.), 12.).filter(filter)),
+        ),
+        (
+            "unpolar",
+            Box::new(Unpolar::new(base(rng), (2.5, -3.), 12.).filter(filter)),
+        ),
+        (
+            "mapped",
+            Box::new(
+                Mapped::new(
+                    base(rng),
+                    Affine::rotate(rng.range(0, 628) as f32 / 100., (3., 4.))
+                        .compose(Affine::scale(factor))
+                        .compose(Affine::offset((1.5, -2.))),
+                )
+                .filter(filter),
+            ),
+        ),
+        (
+            "nested",
+            Box::new(Scale::new(
+                Blur::new(vec![base(rng), base(rng)], radius),
+                factor,
+                filter,
+            )),
+        ),
+    ]
+}
+
+#[test]
+fn sources_are_transparent_outside_borders() {
+    const ROUNDS: u64 = 32;
+    const MARGIN: i32 = 8;
+
+    for seed in 0..ROUNDS {
+        let mut rng = Rng(seed);
+        for (name, source) in cases(&mut rng) {
+            let Some(borders) = source.borders() else {
+                continue;
+            };
+
+            let region: Borders = borders.expand(MARGIN);
+            if let Err(pos) = region.validate(&source) {
+                panic!("{name} is visible at {pos:?} outside its borders, seed {seed}");
+            }
+        }
+    }
+}
+
+#[test]
+fn prepared_sources_render_the_same() {
+    const ROUNDS: u64 = 16;
+    const MARGIN: i32 = 8;
+
+    for seed in 0..ROUNDS {
+        let prepared = cases(&mut Rng(seed));
+        let plain = cases(&mut Rng(seed));
+        let mut rng = Rng(!seed);
+        for ((name, mut prepared), (_, plain)) in prepared.into_iter().zip(plain) {
+            let Some(borders) = plain.borders() else {
+                continue;
+            };
+
+            // Prepare a random part of the area around the borders
+            let Borders { w, h } = borders.expand(MARGIN);
+            let (x0, y0) = (rng.range(w.0, w.1), rng.range(h.0, h.1));
+            let region = Borders {
+                w: (x0, rng.range(x0, w.1)),
+                h: (y0, rng.range(y0, h.1)),
+            };
+
+            prepared.prepare(region);
+            for pos in region.positions() {
+                let (a, b) = (prepared.source(pos), plain.source(pos));
+                let close = [(a.r, b.r), (a.g, b.g), (a.b, b.b), (a.a, b.a)]
+                    .iter()
+                    .all(|(a, b)| (a - b).abs() < 1e-4);
+
+                assert!(
+                    close,
+                    "{name} differs at {pos:?} when prepared, seed {seed}"
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,41 @@
+//! Checks that relief sources read the real slope of a heightmap.
+
+use nied::{
+    source::{Hillshade, NormalMap, Source},
+    Color,
+};
+
+/// The luminance grows by one every `RUN` pixels.
+const RUN: f32 = 16.;
+
+/// A luminance ramp rising to the right.
+struct Ramp;
+
+impl Source for Ramp {
+    fn source(&self, (x, _): (i32, i32)) -> Color {
+        let v = x as f32 / RUN;
+        Color {
+            r: v,
+            g: v,
+            b: v,
+            a: 1.,
+        }
+    }
+}
+
+#[test]
+fn ramp_slope_is_read_exactly() {
+    const POS: (i32, i32) = (8, 8);
+
+    // Full luminance is as high as the ramp is long, so the slope is 45 degrees
+    let diagonal = 0.5_f32.sqrt();
+
+    // The sun sets in the west, lighting the slope head-on
+    let shade = Hillshade::new(Ramp, 270., 0.).exaggeration(RUN);
+    let v = shade.source(POS).r;
+    assert!((v - diagonal).abs() < 1e-4, "hillshade is {v}");
+
+    let normal = NormalMap::new(Ramp, RUN).source(POS);
+    let x = normal.r * 2. - 1.;
+    assert!((x + diagonal).abs() < 1e-4, "normal x is {x}");
+}
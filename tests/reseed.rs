@@ -0,0 +1,131 @@
+//! Checks that the seed of a render reaches noise wrapped in other sources.
+
+#![cfg(feature = "std")]
+
+use nied::{
+    make, make_with,
+    source::{
+        Blur, Channel, Channels, ChromaKey, Close, Convolve, Diff, DiffMode, Dilate, DistanceField,
+        DistanceMode, Dot, Element, Equalization, Equalize, Fill, Filter, Fit, FitMode, Flat,
+        Framed, GaussianBlur, GradientMap, Halftone, Hillshade, Kernel, Kuwahara, KuwaharaKind,
+        MorphKey, Noise, NormalMap, Path, Perlin, RoundedCorners, Scale, Source, Vignette,
+        ZComposite,
+    },
+    Color, RenderOptions,
+};
+
+const SIZE: (u32, u32) = (24, 24);
+const SEED: u32 = 7;
+
+/// Noise bounded by a square, so sources needing borders can wrap it.
+fn noise(seed: Option<u32>) -> Fill<Noise<Perlin>> {
+    let mut noise = Noise::new(Perlin::new(1), 4.);
+    if let Some(seed) = seed {
+        noise.reseed(seed);
+    }
+
+    let square = Path::new()
+        .move_to((2., 2.))
+        .line_to((22., 2.))
+        .line_to((22., 22.))
+        .line_to((2., 22.))
+        .close();
+
+    Fill::new(&square, noise)
+}
+
+type Case = (&'static str, Box<dyn Source + Send + Sync>);
+
+fn cases(seed: Option<u32>) -> Vec<Case> {
+    let noise = || noise(seed);
+    let white = Color::from_u32(0xFFFFFFFF);
+    let black = Color::from_u32(0x000000FF);
+
+    vec![
+        ("fill", Box::new(noise())),
+        ("blur", Box::new(Blur::new(noise(), 2))),
+        ("scale", Box::new(Scale::new(noise(), 2., Filter::Linear))),
+        (
+            "convolve",
+            Box::new(Convolve::new(
+                noise(),
+                Kernel::new((3, 3), vec![0., 1., 0., 1., -4., 1., 0., 1., 0.]),
+            )),
+        ),
+        ("gaussian", Box::new(GaussianBlur::new(noise(), 6.))),
+        (
+            "dilate",
+            Box::new(Dilate::new(noise(), 1, Element::Disc, MorphKey::Luminance)),
+        ),
+        (
+            "close",
+            Box::new(Close::new(noise(), 1, Element::Disc, MorphKey::Luminance)),
+        ),
+        (
+            "kuwahara",
+            Box::new(Kuwahara::new(noise(), 2, KuwaharaKind::Classic)),
+        ),
+        (
+            "gradient-map",
+            Box::new(GradientMap::duotone(noise(), black, white)),
+        ),
+        ("channels", Box::new(Channels::new(noise(), Channel::Red))),
+        ("chroma-key", Box::new(ChromaKey::new(noise(), white, 0.1))),
+        ("vignette", Box::new(Vignette::new(noise(), 0.5))),
+        ("halftone", Box::new(Halftone::new(noise(), 3., Dot::Round))),
+        ("normal-map", Box::new(NormalMap::new(noise(), 2.))),
+        ("hillshade", Box::new(Hillshade::new(noise(), 315., 45.))),
+        ("rounded", Box::new(RoundedCorners::new(noise(), 4.))),
+        ("framed", Box::new(Framed::new(noise(), 1, noise()))),
+        (
+            "diff",
+            Box::new(Diff::new(noise(), black, 1., DiffMode::Channels)),
+        ),
+        (
+            "equalize",
+            Box::new(Equalize::new(noise(), Equalization::Histogram)),
+        ),
+        (
+            "distance",
+            Box::new(DistanceField::new(
+                // Luminance as alpha, so the shape depends on the noise
+                GradientMap::duotone(noise(), Color::default(), white),
+                3.,
+                DistanceMode::Signed,
+            )),
+        ),
+        (
+            "fit",
+            Box::new(Fit::new(noise(), (12, 12), FitMode::Contain)),
+        ),
+        (
+            "z-composite",
+            Box::new(ZComposite::new().push(Flat::new(noise(), 0.))),
+        ),
+    ]
+}
+
+#[test]
+fn wrapped_noise_is_reseeded() {
+    let opts = RenderOptions {
+        seed: SEED,
+        ..Default::default()
+    };
+
+    let cases = cases(None)
+        .into_iter()
+        .zip(cases(None))
+        .zip(cases(Some(SEED)));
+    for (((name, source), (_, plain)), (_, reseeded)) in cases {
+        let rendered = make_with(source, SIZE, &opts).data;
+        assert!(
+            rendered == make(reseeded, SIZE).data,
+            "{name} ignores the render seed",
+        );
+
+        assert!(
+            rendered != make(plain, SIZE).data,
+            "{name} renders the same with another seed",
+        );
+    }
+}